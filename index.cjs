@@ -284,9 +284,31 @@ if (!nativeBinding) {
 	throw new Error(`Failed to load native binding`);
 }
 
-const { ConnectFour, TicTacToe, Player, INVALID_INDEX } = nativeBinding;
+const { Piece, Checkers, ConnectFourVariant, MoveKind, ConnectFour, HintKind, MultiplayerSearch, MultiplayerConnectFour, MoveLabel, DotsAndBoxes, Hex, Mancala, LineRule, MnkGame, Phase, NineMensMorris, Notakto, Othello, Qubic, TicTacToe, BoardStatus, UltimateTicTacToe, Player, Engine, INVALID_INDEX, Playout } = nativeBinding;
 
+module.exports.Piece = Piece;
+module.exports.Checkers = Checkers;
+module.exports.ConnectFourVariant = ConnectFourVariant;
+module.exports.MoveKind = MoveKind;
 module.exports.ConnectFour = ConnectFour;
+module.exports.HintKind = HintKind;
+module.exports.MultiplayerSearch = MultiplayerSearch;
+module.exports.MultiplayerConnectFour = MultiplayerConnectFour;
+module.exports.MoveLabel = MoveLabel;
+module.exports.DotsAndBoxes = DotsAndBoxes;
+module.exports.Hex = Hex;
+module.exports.Mancala = Mancala;
+module.exports.LineRule = LineRule;
+module.exports.MnkGame = MnkGame;
+module.exports.Phase = Phase;
+module.exports.NineMensMorris = NineMensMorris;
+module.exports.Notakto = Notakto;
+module.exports.Othello = Othello;
+module.exports.Qubic = Qubic;
 module.exports.TicTacToe = TicTacToe;
+module.exports.BoardStatus = BoardStatus;
+module.exports.UltimateTicTacToe = UltimateTicTacToe;
 module.exports.Player = Player;
+module.exports.Engine = Engine;
 module.exports.INVALID_INDEX = INVALID_INDEX;
+module.exports.Playout = Playout;
@@ -8,20 +8,1036 @@ export enum Player {
 	Human = 1,
 	Machine = 2
 }
+/** The algorithm a game uses to pick its moves. */
+export enum Engine {
+	/** Minimax search with alpha-beta pruning over the game's evaluation. */
+	AlphaBeta = 0,
+	/**
+	 * Monte Carlo Tree Search, which plays out random games and needs no
+	 * evaluation.
+	 */
+	MonteCarlo = 1
+}
 export const INVALID_INDEX: number;
+/** How the moves of a simulation are picked. */
+export enum Playout {
+	/** Every move is picked at random. */
+	Random = 0,
+	/**
+	 * Moves are picked by the game's heuristic, such as taking immediate wins
+	 * or blocking the opponent's, falling back to random moves.
+	 */
+	Heuristic = 1
+}
+/** The options of the Monte Carlo Tree Search engine. */
+export interface MonteCarloOptions {
+	/**
+	 * The maximum amount of iterations per move, at least 1, defaults to
+	 * 10,000 when neither this nor `time` are set.
+	 */
+	iterations?: number;
+	/** The maximum time per move, in milliseconds. */
+	time?: number;
+	/** How the simulations are played, defaults to `Playout.Random`. */
+	playout?: Playout;
+	/** The seed of the random number generator, defaults to the system clock. */
+	seed?: number;
+}
+/**
+ * The options of `getBestMove`, for the games where either player can be
+ * searched for.
+ */
+export interface BestMoveOptions {
+	/** How many plies deep to search, defaults to the depth of the game. */
+	depth?: number;
+	/**
+	 * The player to find a move for, defaults to `Player.Machine`, or to the
+	 * player to move in the games that track whose turn it is.
+	 */
+	for?: Player;
+}
+/** The statistics of the last search, as returned by `lastSearchStats`. */
+export interface SearchStats {
+	/** The amount of nodes visited, including the leaves. */
+	nodes: number;
+	/**
+	 * The amount of positions scored without searching further, either by the
+	 * static evaluation or because the game ended.
+	 */
+	leafEvaluations: number;
+	/** The amount of nodes whose remaining moves were pruned. */
+	cutoffs: number;
+	/** The amount of positions found in the transposition table. */
+	ttHits: number;
+	/** The deepest ply reached, counting from the current position. */
+	maxDepth: number;
+	/** The time spent searching, in milliseconds. */
+	elapsed: number;
+}
+/**
+ * The content of a square, as exported by the `board` getter. The values of
+ * the men match the ones of `Player`.
+ */
+export enum Piece {
+	Empty = 0,
+	HumanMan = 1,
+	MachineMan = 2,
+	HumanKing = 3,
+	MachineKing = 4
+}
+/**
+ * A move as exposed to JavaScript: the square the piece leaves and every
+ * square it lands on, several of them for a multi-jump.
+ */
+export interface CheckersMove {
+	from: number;
+	/** The squares the piece lands on, the last one being its destination. */
+	path: Array<number>;
+	/** The squares of the captured pieces, which is ignored by `add`. */
+	captures?: Array<number>;
+}
+/**
+ * English draughts: men move and capture diagonally forwards, kings in every
+ * direction, captures are mandatory and chain into multi-jumps, and a man
+ * reaching the far row is promoted, which ends its move.
+ *
+ * The game is drawn after 40 moves per player without captures nor men
+ * moving, or when a position occurs for the third time.
+ */
+export class Checkers {
+	/**
+	 * Creates a board from the `Piece` of its 32 playable squares, which
+	 * defaults to the starting position, with `turn` to move, defaulting to
+	 * the human.
+	 */
+	constructor(values?: Uint8Array | undefined | null, turn?: Player | undefined | null);
+	/** The `Piece` of every playable square. */
+	get board(): Uint8Array;
+	/** The player to move. */
+	get turn(): Player;
+	/** The legal moves of the player to move. */
+	get moves(): Array<CheckersMove>;
+	get finished(): boolean;
+	/**
+	 * The player who won, `Player.Unset` while the game is ongoing or if it
+	 * was drawn.
+	 */
+	get winner(): Player;
+	/** Plays a move of the player to move, returning whether it won the game. */
+	add(mv: CheckersMove): boolean;
+	get engine(): Engine;
+	setEngine(engine: Engine, options?: MonteCarloOptions | undefined | null): void;
+	/**
+	 * The statistics of the last call to `getBestMove`, every counter is zero
+	 * before the first search.
+	 */
+	get lastSearchStats(): SearchStats;
+	/**
+	 * Returns the best move for the player to move, or for the player given
+	 * in the options, or `null` if the game is finished.
+	 */
+	getBestMove(options?: number | BestMoveOptions | undefined | null): CheckersMove | null;
+}
+/** The variants of [`ConnectFour`] played on other boards. */
+export enum ConnectFourVariant {
+	/** The classic 7×6 board, where 4 pieces in a row win. */
+	Classic = 0,
+	/** A 6×5 board where 3 pieces in a row win, for quick games. */
+	ConnectThree = 1,
+	/**
+	 * A 9×6 board where 5 pieces in a row win, whose outside columns start
+	 * filled with alternating pieces.
+	 */
+	FiveInARow = 2
+}
+/**
+ * The options of a [`ConnectFour`] board, which defaults to the classic 7×6
+ * board where 4 pieces in a row win.
+ */
+export interface ConnectFourOptions {
+	/**
+	 * The variant that sets the default size, defaults to
+	 * `ConnectFourVariant.Classic`.
+	 */
+	variant?: ConnectFourVariant;
+	/**
+	 * The amount of columns, between 3 and 10, defaults to the one of the
+	 * variant.
+	 */
+	width?: number;
+	/**
+	 * The amount of rows, between 3 and 8, defaults to the one of the
+	 * variant.
+	 */
+	height?: number;
+	/**
+	 * The amount of pieces in a row needed to win, between 3 and 8 and no
+	 * longer than the board, defaults to the one of the variant.
+	 */
+	connect?: number;
+	/**
+	 * Whether players may pop one of their own pieces out of the bottom row
+	 * instead of dropping one, defaults to `false`.
+	 */
+	popOut?: boolean;
+	/**
+	 * Whether the board is a cylinder, where horizontal and diagonal lines
+	 * wrap from the last column back to the first one, defaults to `false`.
+	 * Lines cannot be longer than the width of a cylinder.
+	 */
+	cylinder?: boolean;
+}
+/** The kinds of moves of a [`ConnectFour`] board. */
+export enum MoveKind {
+	/** Drops a piece on top of a column. */
+	Drop = 0,
+	/**
+	 * Removes a piece of the player from the bottom of a column, moving the
+	 * rest of the column one row down. Only available in PopOut games.
+	 */
+	Pop = 1
+}
 export class ConnectFour {
-	constructor(values?: Uint8Array | undefined | null);
+	constructor(values?: Uint8Array | undefined | null, options?: ConnectFourOptions | undefined | null);
+	get board(): Uint8Array;
+	get width(): number;
+	get height(): number;
+	/** The amount of pieces in a row needed to win. */
+	get connect(): number;
+	/**
+	 * Whether horizontal and diagonal lines wrap from the last column back to
+	 * the first one.
+	 */
+	get cylinder(): boolean;
+	/** Whether players may pop their pieces out of the bottom row. */
+	get popOut(): boolean;
+	available(column: number): boolean;
+	/** Whether `player` can pop its piece at the bottom of `column`. */
+	canPop(column: number, player: Player): boolean;
+	/**
+	 * Whether a move won the game, or the board is full. A full PopOut board
+	 * is not finished, as pieces can still be popped.
+	 */
+	get finished(): boolean;
+	/**
+	 * The player who completed a line with the last move, `Player.Unset` while
+	 * nobody did.
+	 */
+	get winner(): Player;
+	/**
+	 * Drops a piece of `player` in `column`, or pops its piece out of the
+	 * bottom of it, returning whether `player` won the game.
+	 *
+	 * A pop may complete lines of both players, in which case the player who
+	 * popped wins. When it only completes lines of the opponent, the opponent
+	 * wins and `winner` tells so. Fails once the game is won.
+	 */
+	add(column: number, player: Player, kind?: MoveKind | undefined | null): boolean;
+	get engine(): Engine;
+	setEngine(engine: Engine, options?: MonteCarloOptions | undefined | null): void;
+	/**
+	 * The amount of threads used by the alpha-beta search, defaults to 1. With
+	 * a single thread, the search is deterministic.
+	 */
+	get threads(): number;
+	set threads(threads: number);
+	/**
+	 * The statistics of the last call to `getBestMove`, every counter is zero
+	 * before the first search.
+	 */
+	get lastSearchStats(): SearchStats;
+	/**
+	 * Returns the best move for the machine, or for the player given in the
+	 * options, the column to drop a piece in, or `width + column` to pop the
+	 * piece at the bottom of a column.
+	 */
+	getBestMove(options?: number | BestMoveOptions | undefined | null): number;
+	/**
+	 * Returns a hint for the player given in the options, which defaults to
+	 * `Player.Human`: level 1 warns about the lines either player can complete
+	 * with their next move, level 2 also points to the columns around the
+	 * best move, and level 3 gives the best move with its reason. Levels 2
+	 * and 3 search as `getBestMove` does, and update `lastSearchStats`.
+	 */
+	getHint(level: number, options?: BestMoveOptions | undefined | null): ConnectFourHint;
+	/**
+	 * Lists the threats of both players: the empty cells that complete one of
+	 * their lines, whether they are in an odd or an even row, the double
+	 * threats they make, and who controls the zugzwang. `first` is the player
+	 * who moved first, which defaults to `Player.Human`.
+	 */
+	analyzeThreats(first?: Player | undefined | null): ThreatAnalysis;
+	/**
+	 * Replays `moves` from this board, which is left unchanged, and reviews
+	 * every one of them against the best move found by the alpha-beta
+	 * search, for both players. The moves are encoded like the ones of
+	 * `getBestMove`, and the players take turns.
+	 */
+	reviewGame(moves: Array<number>, options?: ReviewOptions | undefined | null): Array<MoveReview>;
+}
+/** What a hint of [`ConnectFour`] is about. */
+export enum HintKind {
+	/**
+	 * Neither player can complete a line with their next move, or there is no
+	 * move left.
+	 */
+	None = 0,
+	/** The player can complete a line with their next move. */
+	Win = 1,
+	/** The opponent can complete a line with their next move. */
+	Block = 2,
+	/** The columns around the best move found by the search. */
+	Region = 3,
+	/**
+	 * The best move leaves the player two lines to complete, and the opponent
+	 * can only block one of them.
+	 */
+	DoubleThreat = 4,
+	/** The best move found by the search, without a more specific reason. */
+	Move = 5
+}
+/** A hint of [`ConnectFour`] for the player to move. */
+export interface ConnectFourHint {
+	kind: HintKind;
+	/**
+	 * The columns the hint is about, numbered from 0 like in the rest of the
+	 * API.
+	 */
+	columns: Array<number>;
+	/**
+	 * The best move, as returned by `getBestMove`, only given by the hints of
+	 * level 3.
+	 */
+	bestMove?: number;
+	/**
+	 * The hint as a sentence for the player, which numbers the columns from 1
+	 * as they are shown to them.
+	 */
+	message: string;
+}
+/** How the search expects the other seats to play. */
+export enum MultiplayerSearch {
+	/**
+	 * Every other seat plays against the searching one, as if they were a
+	 * single opponent, which allows alpha-beta pruning.
+	 */
+	Paranoid = 0,
+	/**
+	 * Every seat plays for itself, maximizing its own score (max-n). Slower,
+	 * as nothing is pruned, but less pessimistic.
+	 */
+	MaxN = 1
+}
+/** The options of a [`MultiplayerConnectFour`] board. */
+export interface MultiplayerConnectFourOptions {
+	/** The amount of seats, 3 or 4, defaults to 3. */
+	players?: number;
+	/**
+	 * The amount of columns, between 3 and 10, defaults to 9 for 3 players
+	 * and 10 for 4.
+	 */
+	width?: number;
+	/**
+	 * The amount of rows, between 3 and 8, defaults to 7 for 3 players and 8
+	 * for 4.
+	 */
+	height?: number;
+	/**
+	 * The amount of pieces in a row needed to win, between 3 and 8 and no
+	 * longer than the board, defaults to 4.
+	 */
+	connect?: number;
+	/** The seat to move, defaults to 1. */
+	turn?: number;
+	/**
+	 * How the other seats are expected to play, defaults to
+	 * `MultiplayerSearch.Paranoid`.
+	 */
+	search?: MultiplayerSearch;
+}
+/**
+ * Connect Four for 3 or 4 players, who take turns by seat, from 1 to the
+ * amount of players. The first one with a line wins.
+ *
+ * Cells hold the seat of their piece, or 0 when empty, and are numbered like
+ * the ones of `ConnectFour`.
+ */
+export class MultiplayerConnectFour {
+	/**
+	 * Creates a board from the seats of its cells, which defaults to an empty
+	 * one.
+	 */
+	constructor(values?: Uint8Array | undefined | null, options?: MultiplayerConnectFourOptions | undefined | null);
 	get board(): Uint8Array;
+	get width(): number;
+	get height(): number;
+	/** The amount of pieces in a row needed to win. */
+	get connect(): number;
+	/** The amount of seats. */
+	get players(): number;
+	/** The seat to move. */
+	get turn(): number;
 	available(column: number): boolean;
 	get finished(): boolean;
-	add(column: number, player: Player): boolean;
+	/** The seat who completed a line, 0 while nobody did. */
+	get winner(): number;
+	/**
+	 * Drops a piece of the seat to move in `column`, returning whether it won
+	 * the game.
+	 */
+	add(column: number): boolean;
+	/**
+	 * The statistics of the last call to `getBestMove`, every counter is zero
+	 * before the first search.
+	 */
+	get lastSearchStats(): SearchStats;
+	/**
+	 * Returns the best column for the seat to move, or `INVALID_INDEX` if the
+	 * game is finished.
+	 */
 	getBestMove(depth?: number | undefined | null): number;
 }
-export class TicTacToe {
+/** How a move of a reviewed game compares to the best one. */
+export enum MoveLabel {
+	/**
+	 * The move scores as well as the best one, which includes every move of
+	 * a position that is lost anyway, however soon it loses.
+	 */
+	Best = 0,
+	/** The move loses a little of the score of the best one. */
+	Good = 1,
+	/**
+	 * The move loses a noticeable part of the score of the best one, or
+	 * still forces a win, but slower than the best one.
+	 */
+	Inaccuracy = 2,
+	/** The move loses a large part of the score of the best one. */
+	Mistake = 3,
+	/**
+	 * The move loses much of the score of the best one, or lets the opponent
+	 * force a win the best move avoided.
+	 */
+	Blunder = 4,
+	/** The best move forced a win, and the move does not. */
+	MissedWin = 5
+}
+/** The options of `reviewGame`. */
+export interface ReviewOptions {
+	/** The player who made the first move, defaults to `Player.Human`. */
+	first?: Player;
+	/** How many plies deep to search every position, defaults to 5. */
+	depth?: number;
+}
+/** The review of a move of a game, as returned by `reviewGame`. */
+export interface MoveReview {
+	/** The player who made the move. */
+	player: Player;
+	/** The move that was played, encoded like the ones of `getBestMove`. */
+	played: number;
+	/** The best move found by the search. */
+	best: number;
+	/**
+	 * How much of the score of the best move the played one lost, from the
+	 * perspective of the player. Decisive outcomes score ±999999999, plus
+	 * the plies left to search when they happen, so faster wins score more.
+	 */
+	loss: number;
+	label: MoveLabel;
+}
+/**
+ * An empty cell that completes a line of a player, as found by
+ * `analyzeThreats`.
+ */
+export interface Threat {
+	cell: number;
+	column: number;
+	/**
+	 * The row of the cell counted from the bottom, starting at 1, whose
+	 * parity decides which player the threat is good for.
+	 */
+	row: number;
+	/**
+	 * Whether a piece can be dropped on the cell right away, otherwise the
+	 * threat waits for the column to fill up to it.
+	 */
+	playable: boolean;
+}
+/**
+ * Two threats of a player the opponent cannot both block, as found by
+ * `analyzeThreats`. Three threats playable right away make three of them, one
+ * per pair.
+ */
+export interface DoubleThreat {
+	/** The cells of the threats, the lowest first. */
+	cells: Array<number>;
+	/**
+	 * Whether the threats are on top of each other in a column, so blocking
+	 * the lower one lets the player complete the upper one. Otherwise, they
+	 * are both playable right away.
+	 */
+	stacked: boolean;
+}
+/** The threats of a player, as found by `analyzeThreats`. */
+export interface PlayerThreats {
+	/** The threats of the player, by cell. */
+	threats: Array<Threat>;
+	/** The amount of threats in an odd row, which the first player wants. */
+	odd: number;
+	/** The amount of threats in an even row, which the second player wants. */
+	even: number;
+	/** Every pair of threats that makes a double threat. */
+	doubleThreats: Array<DoubleThreat>;
+}
+/** The threats of both players of a [`ConnectFour`] board. */
+export interface ThreatAnalysis {
+	human: PlayerThreats;
+	machine: PlayerThreats;
+	/**
+	 * The player who controls the zugzwang, and gets to complete their
+	 * threats once the board fills up. `Player.Unset` in PopOut games and on
+	 * boards of an odd height, where the parity rules do not hold.
+	 */
+	zugzwang: Player;
+}
+/** The options of a [`DotsAndBoxes`] board. */
+export interface DotsAndBoxesOptions {
+	/** The amount of boxes per row, between 1 and 7, defaults to 3. */
+	width?: number;
+	/** The amount of boxes per column, between 1 and 7, defaults to 3. */
+	height?: number;
+	/** The player to move, defaults to the human. */
+	turn?: Player;
+}
+/**
+ * Dots and Boxes, where players take turns drawing an edge between two
+ * adjacent dots, and completing a box claims it and grants another move. The
+ * player with the most boxes wins once every edge is drawn.
+ */
+export class DotsAndBoxes {
+	/**
+	 * Creates a board from a bitmap of its drawn edges and the owner of every
+	 * box, row by row, which defaults to an empty board. A box is owned
+	 * exactly when its four edges are drawn.
+	 */
+	constructor(
+		edges?: Uint8Array | undefined | null,
+		boxes?: Uint8Array | undefined | null,
+		options?: DotsAndBoxesOptions | undefined | null
+	);
+	/** The amount of boxes per row. */
+	get width(): number;
+	/** The amount of boxes per column. */
+	get height(): number;
+	/**
+	 * The bitmap of the drawn edges, the edge `i` being the bit `i % 8` of the
+	 * byte `i / 8`. The horizontal edges come first, row by row from the
+	 * top, followed by the vertical edges, row by row from the left.
+	 */
+	get edges(): Uint8Array;
+	/**
+	 * The owner of every box, row by row, `Player.Unset` if it is not
+	 * complete.
+	 */
+	get boxes(): Uint8Array;
+	/** The player to move, which moves again after completing a box. */
+	get turn(): Player;
+	/** The amount of boxes owned by `player`. */
+	score(player: Player): number;
+	available(edge: number): boolean;
+	get finished(): boolean;
+	/**
+	 * The player who won, `Player.Unset` while the game is ongoing or if it
+	 * was drawn.
+	 */
+	get winner(): Player;
+	/** Draws `edge` for the player to move, returning whether it won the game. */
+	add(edge: number): boolean;
+	get engine(): Engine;
+	setEngine(engine: Engine, options?: MonteCarloOptions | undefined | null): void;
+	/**
+	 * The statistics of the last call to `getBestMove`, every counter is zero
+	 * before the first search.
+	 */
+	get lastSearchStats(): SearchStats;
+	/**
+	 * Returns the best edge for the player to move, or for the player given
+	 * in the options, or `INVALID_INDEX` if the game is finished. Every drawn
+	 * edge counts as a ply, extra moves included.
+	 */
+	getBestMove(options?: number | BestMoveOptions | undefined | null): number;
+}
+/** The options of a [`Hex`] board. */
+export interface HexOptions {
+	/** The amount of rows and columns, between 2 and 13, defaults to 11. */
+	size?: number;
+	/**
+	 * Whether the second player may take over the first stone instead of
+	 * placing one, defaults to `true`.
+	 */
+	swap?: boolean;
+	/** The player to move, defaults to the human. */
+	turn?: Player;
+}
+/**
+ * Hex, where players take turns placing stones on a rhombus of hexagons. The
+ * human connects the top and bottom sides, the machine the left and right
+ * ones, and as they cannot both do so, the game never ends in a draw.
+ *
+ * The engine is a Monte Carlo Tree Search, as connections are hard to
+ * evaluate without playing them out.
+ */
+export class Hex {
+	/**
+	 * Creates a board from its cells, row by row, which defaults to an empty
+	 * board. With a single stone, the player to move may swap it.
+	 */
+	constructor(values?: Uint8Array | undefined | null, options?: HexOptions | undefined | null);
+	get board(): Uint8Array;
+	/** The amount of rows and columns. */
+	get size(): number;
+	get turn(): Player;
+	/**
+	 * The first stone while the player to move may swap it, otherwise
+	 * `INVALID_INDEX`.
+	 */
+	get swapCell(): number;
+	/**
+	 * Whether the player to move can play on `cell`, which is either empty or
+	 * the first stone to swap.
+	 */
+	available(cell: number): boolean;
+	get finished(): boolean;
+	/**
+	 * The player who connected its sides, `Player.Unset` while the game is
+	 * ongoing.
+	 */
+	get winner(): Player;
+	/**
+	 * Places a stone of the player to move on `cell`, or swaps the first stone
+	 * if `cell` is `swapCell`, returning whether it won the game.
+	 */
+	add(cell: number): boolean;
+	/**
+	 * Replaces the options of the Monte Carlo Tree Search, which uses the
+	 * default ones until then.
+	 */
+	setSearchOptions(options: MonteCarloOptions): void;
+	/**
+	 * The statistics of the last call to `getBestMove`, every counter is zero
+	 * before the first search.
+	 */
+	get lastSearchStats(): SearchStats;
+	/**
+	 * Returns the best cell for the player to move, or for the player given
+	 * in the options, which may be `swapCell`, or `INVALID_INDEX` if the game
+	 * is finished. The depth is ignored, as the search is bounded by the
+	 * options given to `setSearchOptions`.
+	 */
+	getBestMove(options?: number | BestMoveOptions | undefined | null): number;
+}
+/** The options of a [`Mancala`] board. */
+export interface MancalaOptions {
+	/**
+	 * The amount of seeds in every pit, between 1 and 12, defaults to 4, when
+	 * no board is given.
+	 */
+	seeds?: number;
+	/** The player to move, defaults to the human. */
+	turn?: Player;
+}
+/**
+ * Kalah, where each player has 6 pits and a store, and collects the most
+ * seeds in its store. Landing the last sown seed in the own store grants
+ * another turn, so a player may move several times in a row.
+ */
+export class Mancala {
+	/**
+	 * Creates a board from its 14 pits and stores, holding up to 255 seeds
+	 * in total, which defaults to every pit holding `options.seeds` seeds.
+	 * The seeds cannot be given alongside the board.
+	 */
+	constructor(values?: Uint8Array | undefined | null, options?: MancalaOptions | undefined | null);
+	/** The seeds of the human's pits and store, followed by the machine's. */
+	get board(): Uint8Array;
+	/**
+	 * The player to move, which moves again after sowing its last seed in
+	 * its store.
+	 */
+	get turn(): Player;
+	/** Whether the player to move can sow the seeds of `pit`. */
+	available(pit: number): boolean;
+	get finished(): boolean;
+	/**
+	 * The player who won, `Player.Unset` while the game is ongoing or if it
+	 * was drawn.
+	 */
+	get winner(): Player;
+	/**
+	 * Sows the seeds of `pit` for the player to move, returning whether it won
+	 * the game.
+	 */
+	add(pit: number): boolean;
+	get engine(): Engine;
+	setEngine(engine: Engine, options?: MonteCarloOptions | undefined | null): void;
+	/**
+	 * The statistics of the last call to `getBestMove`, every counter is zero
+	 * before the first search.
+	 */
+	get lastSearchStats(): SearchStats;
+	/**
+	 * Returns the best pit for the player to move, or for the player given in
+	 * the options, or `INVALID_INDEX` if the game is finished. Every sowing
+	 * counts as a ply, extra turns included.
+	 */
+	getBestMove(options?: number | BestMoveOptions | undefined | null): number;
+}
+/** Which lines of `k` pieces win the game. */
+export enum LineRule {
+	/** Lines of `k` or more pieces win. */
+	Freestyle = 0,
+	/**
+	 * Only lines of exactly `k` pieces win, longer lines (overlines) do not,
+	 * as in standard Gomoku.
+	 */
+	Exact = 1
+}
+/**
+ * The options of an [`MnkGame`] board, which defaults to freestyle Gomoku on
+ * a 15×15 board.
+ */
+export interface MnkOptions {
+	/** The amount of columns (m), between 3 and 19, defaults to 15. */
+	width?: number;
+	/** The amount of rows (n), between 3 and 19, defaults to 15. */
+	height?: number;
+	/**
+	 * The amount of pieces in a row needed to win, between 3 and 8 and no
+	 * longer than the board, defaults to 5.
+	 */
+	k?: number;
+	/** Which lines win the game, defaults to `LineRule.Freestyle`. */
+	rule?: LineRule;
+}
+/**
+ * An m,n,k-game, where two players take turns placing pieces in an m×n board
+ * and the first one with `k` pieces in a row wins. Tic-tac-toe is the 3,3,3
+ * game, and Gomoku the 15,15,5 one.
+ */
+export class MnkGame {
+	constructor(values?: Uint8Array | undefined | null, options?: MnkOptions | undefined | null);
+	get board(): Uint8Array;
+	get width(): number;
+	get height(): number;
+	/** The amount of pieces in a row needed to win. */
+	get k(): number;
+	get rule(): LineRule;
+	available(cell: number): boolean;
+	/** Whether a player completed a line, or the board is full. */
+	get finished(): boolean;
+	/** The player who completed a line, `Player.Unset` while nobody did. */
+	get winner(): Player;
+	/**
+	 * Places a piece of `player` on `cell`, returning whether it completed a
+	 * line. Fails once the game is won.
+	 */
+	add(cell: number, player: Player): boolean;
+	/**
+	 * The statistics of the last call to `getBestMove`, every counter is zero
+	 * before the first search.
+	 */
+	get lastSearchStats(): SearchStats;
+	/**
+	 * Returns the best cell for the machine, or for the player given in the
+	 * options, or `INVALID_INDEX` if the game is finished.
+	 */
+	getBestMove(options?: number | BestMoveOptions | undefined | null): number;
+}
+/** How a player moves its pieces. */
+export enum Phase {
+	/** The player places the pieces in its hand on empty points. */
+	Placing = 0,
+	/** The player moves a piece to an adjacent empty point. */
+	Moving = 1,
+	/** The player has three pieces left, which move to any empty point. */
+	Flying = 2
+}
+/**
+ * A move as exposed to JavaScript: the point a piece leaves, unless it is
+ * placed, the point it lands on, and the opponent's piece to remove if it
+ * closes a mill.
+ */
+export interface MorrisMove {
+	/** The point the piece leaves, unset while placing pieces. */
+	from?: number;
+	to: number;
+	/**
+	 * The point of the opponent's piece to remove, which must be set exactly
+	 * when the move closes a mill while the opponent has a piece on the
+	 * board.
+	 */
+	remove?: number;
+}
+/**
+ * Nine men's morris: players place their 9 pieces in turns, then move them
+ * to adjacent points, and fly them to any empty point once they have 3 left.
+ * Forming a line of three pieces, a mill, removes a piece of the opponent,
+ * which loses once reduced to 2 pieces or unable to move.
+ *
+ * The game is drawn after 50 moves per player without mills, or when a
+ * position occurs for the third time.
+ */
+export class NineMensMorris {
+	/**
+	 * Creates a board from its 24 points, which defaults to an empty board.
+	 * Each player has `hand` pieces left to place, or the human and the
+	 * machine have the ones of a `[human, machine]` pair, defaulting to 9 on
+	 * an empty board and 0 otherwise, and `turn` moves first, defaulting to
+	 * the human.
+	 */
+	constructor(
+		values?: Uint8Array | undefined | null,
+		hand?: number | Array<number> | undefined | null,
+		turn?: Player | undefined | null
+	);
+	get board(): Uint8Array;
+	/** The player to move. */
+	get turn(): Player;
+	/** The amount of pieces `player` has yet to place. */
+	inHand(player: Player): number;
+	/** How the player to move moves its pieces. */
+	get phase(): Phase;
+	/** The legal moves of the player to move. */
+	get moves(): Array<MorrisMove>;
+	get finished(): boolean;
+	/**
+	 * The player who won, `Player.Unset` while the game is ongoing or if it
+	 * was drawn.
+	 */
+	get winner(): Player;
+	/** Plays a move of the player to move, returning whether it won the game. */
+	add(mv: MorrisMove): boolean;
+	get engine(): Engine;
+	setEngine(engine: Engine, options?: MonteCarloOptions | undefined | null): void;
+	/**
+	 * The statistics of the last call to `getBestMove`, every counter is zero
+	 * before the first search.
+	 */
+	get lastSearchStats(): SearchStats;
+	/**
+	 * Returns the best move for the player to move, or for the player given
+	 * in the options, or `null` if the game is finished.
+	 */
+	getBestMove(options?: number | BestMoveOptions | undefined | null): MorrisMove | null;
+}
+/** The options of a [`Notakto`] game. */
+export interface NotaktoOptions {
+	/** The amount of boards, between 1 and 4, defaults to 1. */
+	boards?: number;
+	/** The player to move, defaults to the human. */
+	turn?: Player;
+}
+/**
+ * Notakto, where both players mark cells of one or more tic-tac-toe boards
+ * with the same mark. A board with a line is dead and takes no more marks,
+ * and the player who kills the last board loses.
+ *
+ * Cells are numbered by board, so cell `9 * b + c` is the cell `c` of the
+ * board `b`, numbered like the cells of `TicTacToe`.
+ */
+export class Notakto {
+	/**
+	 * Creates a game from the cells of its boards, where any non-zero value is
+	 * a mark, which defaults to empty boards.
+	 */
+	constructor(values?: Uint8Array | undefined | null, options?: NotaktoOptions | undefined | null);
+	/** The cells of every board, 1 for the marked ones. */
+	get board(): Uint8Array;
+	/** The amount of boards. */
+	get boards(): number;
+	/** Whether `board` has no line yet, and so takes marks. */
+	alive(board: number): boolean;
+	get turn(): Player;
+	available(cell: number): boolean;
+	get finished(): boolean;
+	/**
+	 * The player who won, as the opponent killed the last board,
+	 * `Player.Unset` while the game is ongoing.
+	 */
+	get winner(): Player;
+	/**
+	 * Marks `cell` for the player to move, returning whether it completed a
+	 * line, which loses the game if it killed the last board.
+	 */
+	add(cell: number): boolean;
+	/**
+	 * The statistics of the last call to `getBestMove`, every counter is zero
+	 * before the first search. Positions solved by previous searches, of any
+	 * game, count as transposition table hits.
+	 */
+	get lastSearchStats(): SearchStats;
+	/**
+	 * Returns the best cell for the player to move, or for the player given
+	 * in the options, or `INVALID_INDEX` if the game is finished. The game is
+	 * solved, so a winning move is found whenever there is one. When every
+	 * move loses, the first one that does not kill a board is returned, to
+	 * make the opponent work for the win, or the first one if they all do.
+	 *
+	 * Both players make the same marks, so the best cell is the same for
+	 * either of them, and the depth is ignored as the search always plays to
+	 * the end of the game.
+	 *
+	 * The first search of 4 empty boards blocks for a fraction of a second
+	 * in release builds. The solved positions are shared by every game, so
+	 * later searches mostly find them in the table.
+	 */
+	getBestMove(options?: number | BestMoveOptions | undefined | null): number;
+}
+export class Othello {
+	/**
+	 * Creates a board from its 64 cells, row by row, which defaults to the
+	 * starting position with the human playing first.
+	 */
+	constructor(values?: Uint8Array | undefined | null);
+	get board(): Uint8Array;
+	available(cell: number, player: Player): boolean;
+	/**
+	 * The cells where `player` can place a disc, a player without any has to
+	 * pass.
+	 */
+	moves(player: Player): Array<number>;
+	/** Whether neither player can place a disc. */
+	get finished(): boolean;
+	/**
+	 * The amount of discs of `player`, which decides the winner once the game
+	 * is finished.
+	 */
+	count(player: Player): number;
+	/**
+	 * Places a disc of `player`, returning whether it finished the game with
+	 * `player` having more discs.
+	 */
+	add(cell: number, player: Player): boolean;
+	/**
+	 * The statistics of the last call to `getBestMove`, every counter is zero
+	 * before the first search.
+	 */
+	get lastSearchStats(): SearchStats;
+	/**
+	 * Returns the best cell for the machine, or for the player given in the
+	 * options, or `INVALID_INDEX` if it has to pass. The last 14 empty cells
+	 * are always searched to the end of the game.
+	 */
+	getBestMove(options?: number | BestMoveOptions | undefined | null): number;
+}
+/**
+ * Qubic, tic-tac-toe in a 4×4×4 cube, where the first player with 4 pieces in
+ * any of its 76 lines wins.
+ *
+ * The cell `16 * layer + 4 * row + column` is the cell at that column of that
+ * row of that layer.
+ */
+export class Qubic {
+	/** Creates a cube from its 64 cells, which defaults to an empty one. */
 	constructor(values?: Uint8Array | undefined | null);
 	get board(): Uint8Array;
 	available(cell: number): boolean;
 	get finished(): boolean;
+	/**
+	 * The player with 4 pieces in a line, `Player.Unset` while the game is
+	 * ongoing or if it was drawn.
+	 */
+	get winner(): Player;
+	/**
+	 * Places a piece of `player` on `cell`, returning whether it completed a
+	 * line.
+	 */
 	add(cell: number, player: Player): boolean;
-	getBestMove(depth?: number | undefined | null): number;
+	/**
+	 * The statistics of the last call to `getBestMove`, every counter is zero
+	 * before the first search.
+	 */
+	get lastSearchStats(): SearchStats;
+	/**
+	 * Returns the best cell for the machine, or for the player given in the
+	 * options, or `INVALID_INDEX` if the game is finished.
+	 */
+	getBestMove(options?: number | BestMoveOptions | undefined | null): number;
+}
+/** The options of a [`TicTacToe`] board. */
+export interface TicTacToeOptions {
+	/**
+	 * Whether completing a line loses the game instead of winning it, defaults
+	 * to `false`.
+	 */
+	misere?: boolean;
+}
+export class TicTacToe {
+	constructor(values?: Uint8Array | undefined | null, options?: TicTacToeOptions | undefined | null);
+	get board(): Uint8Array;
+	/** Whether completing a line loses the game. */
+	get misere(): boolean;
+	available(cell: number): boolean;
+	get finished(): boolean;
+	/**
+	 * Places a piece of `player` on `cell`, returning whether it completed a
+	 * line, which loses the game in misère.
+	 */
+	add(cell: number, player: Player): boolean;
+	/**
+	 * The statistics of the last call to `getBestMove`, every counter is zero
+	 * before the first search.
+	 */
+	get lastSearchStats(): SearchStats;
+	/**
+	 * Returns the best cell for the machine, or for the player given in the
+	 * options, or `INVALID_INDEX` if the board is full.
+	 */
+	getBestMove(options?: number | BestMoveOptions | undefined | null): number;
+}
+/** The state of a sub-board. */
+export enum BoardStatus {
+	/** The sub-board still accepts moves. */
+	Ongoing = 0,
+	/** The human completed a line in the sub-board. */
+	Human = 1,
+	/** The machine completed a line in the sub-board. */
+	Machine = 2,
+	/** The sub-board was filled without a line. */
+	Draw = 3
+}
+/**
+ * Ultimate tic-tac-toe: a tic-tac-toe board of tic-tac-toe boards, where the
+ * cell a player moves in sends the opponent to the matching sub-board, and
+ * winning three sub-boards in a row wins the game.
+ *
+ * Cells are numbered by sub-board, so cell `9 * b + c` is the cell `c` of the
+ * sub-board `b`, both numbered like the cells of `TicTacToe`.
+ */
+export class UltimateTicTacToe {
+	/**
+	 * Creates a board from its 81 cells, where the next move must be played in
+	 * the sub-board `nextBoard`, or in any open one if it is not set.
+	 */
+	constructor(values?: Uint8Array | undefined | null, nextBoard?: number | undefined | null);
+	get board(): Uint8Array;
+	/** The state of every sub-board, as `BoardStatus` values. */
+	get boards(): Uint8Array;
+	/**
+	 * The sub-board the next move must be played in, or `INVALID_INDEX` if
+	 * any open sub-board is allowed.
+	 */
+	get nextBoard(): number;
+	available(cell: number): boolean;
+	get finished(): boolean;
+	add(cell: number, player: Player): boolean;
+	/**
+	 * The statistics of the last call to `getBestMove`, every counter is zero
+	 * before the first search.
+	 */
+	get lastSearchStats(): SearchStats;
+	/**
+	 * Returns the best cell for the machine, or for the player given in the
+	 * options, or `INVALID_INDEX` if the game is finished.
+	 */
+	getBestMove(options?: number | BestMoveOptions | undefined | null): number;
 }
@@ -1,7 +1,29 @@
 import mod from './index.cjs';
 
 export default mod;
+export const { Piece } = mod;
+export const { Checkers } = mod;
+export const { ConnectFourVariant } = mod;
+export const { MoveKind } = mod;
 export const { ConnectFour } = mod;
+export const { HintKind } = mod;
+export const { MultiplayerSearch } = mod;
+export const { MultiplayerConnectFour } = mod;
+export const { MoveLabel } = mod;
+export const { DotsAndBoxes } = mod;
+export const { Hex } = mod;
+export const { Mancala } = mod;
+export const { LineRule } = mod;
+export const { MnkGame } = mod;
+export const { Phase } = mod;
+export const { NineMensMorris } = mod;
+export const { Notakto } = mod;
+export const { Othello } = mod;
+export const { Qubic } = mod;
 export const { TicTacToe } = mod;
+export const { BoardStatus } = mod;
+export const { UltimateTicTacToe } = mod;
 export const { Player } = mod;
+export const { Engine } = mod;
 export const { INVALID_INDEX } = mod;
+export const { Playout } = mod;
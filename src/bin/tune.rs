@@ -0,0 +1,64 @@
+//! Tunes the Connect Four evaluation weights from self-play games.
+//!
+//! Usage:
+//!
+//! ```sh
+//! cargo run --release --features tune --bin tune -- [--games N] [--depth N] [--seed N] [--output PATH]
+//! ```
+//!
+//! The tuned weights are printed as the source of
+//! `src/games/connect_four/weights.rs`, or written to `--output` if given.
+
+use std::{env, fs, process};
+
+use skyra_ai::tuning::{error, generate_samples, scaling_factor, to_source, tune, Random, DEFAULT_WEIGHTS};
+
+struct Options {
+	games: usize,
+	depth: u8,
+	seed: u64,
+	output: Option<String>,
+}
+
+fn parse_options() -> Result<Options, String> {
+	let mut options = Options { games: 2000, depth: 4, seed: 0, output: None };
+
+	let mut args = env::args().skip(1);
+	while let Some(arg) = args.next() {
+		let value = args.next().ok_or_else(|| format!("{arg} requires a value"))?;
+		match arg.as_str() {
+			"--games" => options.games = value.parse().map_err(|_| "--games must be a positive number")?,
+			"--depth" => options.depth = value.parse().map_err(|_| "--depth must be a number between 1 and 42")?,
+			"--seed" => options.seed = value.parse().map_err(|_| "--seed must be a positive number")?,
+			"--output" => options.output = Some(value),
+			_ => return Err(format!("unknown option {arg}")),
+		}
+	}
+
+	Ok(options)
+}
+
+fn main() {
+	let options = parse_options().unwrap_or_else(|e| {
+		eprintln!("error: {e}");
+		process::exit(1);
+	});
+
+	eprintln!("Playing {} self-play games at depth {}...", options.games, options.depth);
+	let samples = generate_samples(options.games, options.depth, &mut Random::new(options.seed));
+
+	let k = scaling_factor(&samples, DEFAULT_WEIGHTS);
+	eprintln!("Collected {} positions, K = {k:.6}, error = {:.6}", samples.len(), error(&samples, DEFAULT_WEIGHTS, k));
+
+	let weights = tune(&samples, DEFAULT_WEIGHTS, k, 16);
+	eprintln!("Tuned weights: {weights:?}, error = {:.6}", error(&samples, weights, k));
+
+	let source = to_source(weights);
+	match options.output {
+		Some(path) => fs::write(&path, source).unwrap_or_else(|e| {
+			eprintln!("error: could not write {path}: {e}");
+			process::exit(1);
+		}),
+		None => print!("{source}"),
+	}
+}
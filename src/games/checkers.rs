@@ -0,0 +1,816 @@
+use std::time::Instant;
+
+use napi::{bindgen_prelude::Uint8Array, Error, Result};
+
+use crate::{
+	napi_assert,
+	search::{
+		alpha_beta::{AlphaBeta, Evaluate},
+		game::{Game, Outcome},
+		mcts::{MonteCarlo, MonteCarloOptions},
+		options::{BestMoveArgument, BestMoveOptions},
+		random::Random,
+		stats::{SearchStats, Stats},
+		tt::zobrist_keys,
+	},
+	Engine, Player,
+};
+
+/// The amount of playable (dark) squares, numbered from 0 at the top left to
+/// 31 at the bottom right, row by row.
+pub const SQUARES: usize = 32;
+const ALL_SQUARES: u32 = u32::MAX;
+
+/// The rows the human and the machine promote their men in.
+const HUMAN_KING_ROW: u32 = 0x0000_000F;
+const MACHINE_KING_ROW: u32 = 0xF000_0000;
+
+/// The most squares a piece can land on in a single move, as every jump
+/// captures one of the 12 pieces of the opponent.
+const MAX_JUMPS: usize = 12;
+
+/// The amount of plies without captures nor men moving after which the game is
+/// drawn, 40 moves per player.
+const INACTIVITY_PLIES: u8 = 80;
+
+/// How many times a position has to occur for the game to be drawn.
+const REPETITIONS: usize = 3;
+
+/// The evaluation weights, per piece of the player to move over the opponent.
+const MAN_WEIGHT: i32 = 100;
+const KING_WEIGHT: i32 = 160;
+/// The score of a man per row it advanced towards promotion.
+const ADVANCE_WEIGHT: i32 = 3;
+/// The score of a man kept in its back row, which stops opposing men from
+/// promoting.
+const BACK_ROW_WEIGHT: i32 = 8;
+
+/// The Zobrist keys of every square for each kind of piece, followed by the
+/// key of the positions where [`Player::Human`] is to move.
+const ZOBRIST_KEYS: [u64; SQUARES * 4 + 1] = zobrist_keys(0xC4EC_4E25);
+
+/// The diagonal directions: up-left, up-right, down-left and down-right.
+const UP: [usize; 2] = [0, 1];
+const DOWN: [usize; 2] = [2, 3];
+const ALL: [usize; 4] = [0, 1, 2, 3];
+
+/// A value of [`NEIGHBOURS`] for the directions leaving the board.
+const NONE: u8 = u8::MAX;
+
+/// The neighbour of every square in each direction.
+const NEIGHBOURS: [[u8; 4]; SQUARES] = neighbours();
+
+const fn neighbours() -> [[u8; 4]; SQUARES] {
+	let mut table = [[NONE; 4]; SQUARES];
+	let mut square = 0;
+	while square < SQUARES {
+		let row = (square / 4) as isize;
+		// The dark squares are the ones in odd columns of the even rows, and in
+		// even columns of the odd rows:
+		let column = (2 * (square % 4) + (row as usize + 1) % 2) as isize;
+
+		let deltas = [(-1, -1), (-1, 1), (1, -1), (1, 1)];
+		let mut direction = 0;
+		while direction < 4 {
+			let (r, c) = (row + deltas[direction].0, column + deltas[direction].1);
+			if r >= 0 && r < 8 && c >= 0 && c < 8 {
+				table[square][direction] = (r * 4 + c / 2) as u8;
+			}
+			direction += 1;
+		}
+
+		square += 1;
+	}
+
+	table
+}
+
+/// Iterates over the squares set in `bits`.
+fn squares(mut bits: u32) -> impl Iterator<Item = usize> {
+	std::iter::from_fn(move || {
+		(bits != 0).then(|| {
+			let square = bits.trailing_zeros() as usize;
+			bits &= bits - 1;
+			square
+		})
+	})
+}
+
+/// The content of a square, as exported by the `board` getter. The values of
+/// the men match the ones of `Player`.
+#[napi]
+#[repr(u8)]
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "tune", derive(Clone, Copy))]
+pub enum Piece {
+	Empty,
+	HumanMan,
+	MachineMan,
+	HumanKing,
+	MachineKing,
+}
+
+/// A move as exposed to JavaScript: the square the piece leaves and every
+/// square it lands on, several of them for a multi-jump.
+#[napi(object)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CheckersMove {
+	pub from: u32,
+	/// The squares the piece lands on, the last one being its destination.
+	pub path: Vec<u32>,
+	/// The squares of the captured pieces, which is ignored by `add`.
+	pub captures: Option<Vec<u32>>,
+}
+
+/// A move of a piece, with every square it lands on and the pieces it captures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Move {
+	from: u8,
+	path: [u8; MAX_JUMPS],
+	len: u8,
+	/// The squares of the captured pieces.
+	captured: u32,
+}
+
+impl Move {
+	fn new(from: usize) -> Self {
+		Self { from: from as u8, path: [0; MAX_JUMPS], len: 0, captured: 0 }
+	}
+
+	/// Returns the move extended to land on `to`, capturing `over` if set.
+	fn then(mut self, to: usize, over: Option<usize>) -> Self {
+		self.path[self.len as usize] = to as u8;
+		self.len += 1;
+		if let Some(over) = over {
+			self.captured |= 1 << over;
+		}
+		self
+	}
+
+	fn path(&self) -> &[u8] {
+		&self.path[..self.len as usize]
+	}
+
+	fn to(&self) -> usize {
+		self.path[self.len as usize - 1] as usize
+	}
+}
+
+impl From<Move> for CheckersMove {
+	fn from(mv: Move) -> Self {
+		Self {
+			from: mv.from.into(),
+			path: mv.path().iter().map(|&s| s.into()).collect(),
+			captures: Some(squares(mv.captured).map(|s| s as u32).collect()),
+		}
+	}
+}
+
+/// A board alongside the player to move and the history needed by the draw
+/// rules, as searched by both engines.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Position {
+	human: u32,
+	machine: u32,
+	kings: u32,
+	player: Player,
+	/// The plies since the last capture or move of a man.
+	quiet: u8,
+	/// The Zobrist keys of the positions since the last capture or move of a
+	/// man, including the current one, as earlier ones cannot occur again.
+	history: Vec<u64>,
+	outcome: Option<Outcome>,
+}
+
+impl Position {
+	fn new(human: u32, machine: u32, kings: u32, player: Player) -> Self {
+		let mut position = Self { human, machine, kings, player, quiet: 0, history: Vec::new(), outcome: None };
+		position.history.push(position.hash());
+		position.outcome = position.compute_outcome();
+		position
+	}
+
+	/// Returns a copy of this position with `player` to move, whose draw rules
+	/// count it as the latest position.
+	fn with_player(&self, player: Player) -> Self {
+		let mut position = Self { player, ..self.clone() };
+		position.history.pop();
+		position.history.push(position.hash());
+		position.outcome = position.compute_outcome();
+		position
+	}
+
+	/// Returns the pieces of `player` and of its opponent.
+	fn sides(&self, player: Player) -> (u32, u32) {
+		match player {
+			Player::Human => (self.human, self.machine),
+			Player::Machine => (self.machine, self.human),
+			Player::Unset => unreachable!(),
+		}
+	}
+
+	fn piece(&self, square: usize) -> Piece {
+		let king = self.kings & 1 << square != 0;
+		match (self.human & 1 << square != 0, self.machine & 1 << square != 0, king) {
+			(true, _, false) => Piece::HumanMan,
+			(true, _, true) => Piece::HumanKing,
+			(_, true, false) => Piece::MachineMan,
+			(_, true, true) => Piece::MachineKing,
+			_ => Piece::Empty,
+		}
+	}
+
+	fn hash(&self) -> u64 {
+		let pieces = squares(self.human | self.machine)
+			.fold(0, |hash, square| hash ^ ZOBRIST_KEYS[square * 4 + self.piece(square) as usize - 1]);
+
+		match self.player {
+			Player::Human => pieces ^ ZOBRIST_KEYS[SQUARES * 4],
+			_ => pieces,
+		}
+	}
+
+	/// The directions the men of `player` move in.
+	fn forward(player: Player) -> &'static [usize] {
+		match player {
+			Player::Human => &UP,
+			_ => &DOWN,
+		}
+	}
+
+	/// The row the men of `player` promote in.
+	fn king_row(player: Player) -> u32 {
+		match player {
+			Player::Human => HUMAN_KING_ROW,
+			_ => MACHINE_KING_ROW,
+		}
+	}
+
+	/// Returns the legal moves of the player to move, ignoring the outcome.
+	fn legal_moves(&self) -> Vec<Move> {
+		let (own, opponent) = self.sides(self.player);
+		let empty = !(own | opponent) & ALL_SQUARES;
+
+		// Captures are mandatory:
+		let mut moves = Vec::new();
+		for from in squares(own) {
+			let directions = if self.kings & 1 << from != 0 { &ALL } else { Self::forward(self.player) };
+			// The moving piece leaves its square, which it may jump back into:
+			self.jumps(Move::new(from), from, directions, opponent, empty | 1 << from, &mut moves);
+		}
+
+		if !moves.is_empty() {
+			return moves;
+		}
+
+		for from in squares(own) {
+			let directions = if self.kings & 1 << from != 0 { &ALL } else { Self::forward(self.player) };
+			for &direction in directions {
+				let to = NEIGHBOURS[from][direction];
+				if to != NONE && empty & 1 << to != 0 {
+					moves.push(Move::new(from).then(to as usize, None));
+				}
+			}
+		}
+
+		moves
+	}
+
+	/// Extends `mv`, which stands at `at`, with every jump available, adding
+	/// the complete sequences to `moves`. A man promoting ends its move.
+	fn jumps(&self, mv: Move, at: usize, directions: &[usize], opponent: u32, empty: u32, moves: &mut Vec<Move>) {
+		let mut extended = false;
+		for &direction in directions {
+			let over = NEIGHBOURS[at][direction];
+			if over == NONE || opponent & !mv.captured & 1 << over == 0 {
+				continue;
+			}
+
+			let to = NEIGHBOURS[over as usize][direction];
+			if to == NONE || empty & 1 << to == 0 {
+				continue;
+			}
+
+			extended = true;
+			let next = mv.then(to as usize, Some(over as usize));
+			let promotes = directions.len() == 2 && Self::king_row(self.player) & 1 << to != 0;
+			if promotes {
+				moves.push(next);
+			} else {
+				self.jumps(next, to as usize, directions, opponent, empty, moves);
+			}
+		}
+
+		if !extended && mv.len > 0 {
+			moves.push(mv);
+		}
+	}
+
+	fn compute_outcome(&self) -> Option<Outcome> {
+		// A player without moves, captured or blocked, loses:
+		if self.legal_moves().is_empty() {
+			return Some(Outcome::Win(self.player.opponent()));
+		}
+
+		let hash = self.history.last().copied().unwrap_or_default();
+		if self.quiet >= INACTIVITY_PLIES || self.history.iter().filter(|&&h| h == hash).count() >= REPETITIONS {
+			return Some(Outcome::Draw);
+		}
+
+		None
+	}
+
+	/// Scores a player's men by their advancement and back row.
+	fn score_men(men: u32, player: Player) -> i32 {
+		let (advance, back_row) = match player {
+			Player::Human => (squares(men).map(|s| 7 - (s / 4) as i32).sum::<i32>(), men & MACHINE_KING_ROW),
+			_ => (squares(men).map(|s| (s / 4) as i32).sum::<i32>(), men & HUMAN_KING_ROW),
+		};
+
+		ADVANCE_WEIGHT * advance + BACK_ROW_WEIGHT * back_row.count_ones() as i32
+	}
+
+	/// Scores the pieces of `player`.
+	fn score(&self, player: Player) -> i32 {
+		let (own, _) = self.sides(player);
+		let men = own & !self.kings;
+		MAN_WEIGHT * men.count_ones() as i32
+			+ KING_WEIGHT * (own & self.kings).count_ones() as i32
+			+ Self::score_men(men, player)
+	}
+}
+
+impl Game for Position {
+	type Move = Move;
+
+	fn player(&self) -> Player {
+		self.player
+	}
+
+	fn moves(&self) -> Vec<Move> {
+		if self.outcome.is_some() {
+			return Vec::new();
+		}
+
+		self.legal_moves()
+	}
+
+	fn play(&mut self, mv: Move) {
+		let (from, to) = (1 << mv.from, 1 << mv.to());
+		let king = self.kings & from != 0;
+		let (mut own, mut opponent) = self.sides(self.player);
+		own = own & !from | to;
+		opponent &= !mv.captured;
+		self.kings &= !(from | mv.captured);
+		if king || Self::king_row(self.player) & to != 0 {
+			self.kings |= to;
+		}
+
+		match self.player {
+			Player::Human => (self.human, self.machine) = (own, opponent),
+			_ => (self.machine, self.human) = (own, opponent),
+		}
+
+		self.player = self.player.opponent();
+
+		// Captures and men moving cannot be undone, so no earlier position can
+		// occur again:
+		if mv.captured != 0 || !king {
+			self.quiet = 0;
+			self.history.clear();
+		} else {
+			self.quiet += 1;
+		}
+
+		self.history.push(self.hash());
+		self.outcome = self.compute_outcome();
+	}
+
+	fn outcome(&self) -> Option<Outcome> {
+		self.outcome
+	}
+
+	/// Takes the move capturing the most pieces, or promoting a man.
+	fn heuristic_move(&mut self, moves: &[Move], _random: &mut Random) -> Option<Move> {
+		let best = moves.iter().max_by_key(|mv| mv.captured.count_ones())?;
+		if best.captured != 0 {
+			return Some(*best);
+		}
+
+		let king_row = Self::king_row(self.player);
+		moves.iter().copied().find(|mv| self.kings & 1 << mv.from == 0 && king_row & 1 << mv.to() != 0)
+	}
+}
+
+impl Evaluate for Position {
+	fn evaluate(&self) -> i32 {
+		self.score(self.player) - self.score(self.player.opponent())
+	}
+}
+
+/// English draughts: men move and capture diagonally forwards, kings in every
+/// direction, captures are mandatory and chain into multi-jumps, and a man
+/// reaching the far row is promoted, which ends its move.
+///
+/// The game is drawn after 40 moves per player without captures nor men
+/// moving, or when a position occurs for the third time.
+#[napi]
+pub struct Checkers {
+	position: Position,
+	mcts: Option<Box<MonteCarlo<Position>>>,
+	/// The counters of the last search.
+	stats: Stats,
+}
+
+impl Checkers {
+	pub fn new(position: Position) -> Self {
+		Self { position, mcts: None, stats: Stats::default() }
+	}
+
+	/// Returns the best move for `player` using the selected engine, the depth
+	/// is only used by the alpha-beta engine. The search hands `player` the
+	/// turn if it is not theirs.
+	pub fn get_best_move(&mut self, maximum_depth: u8, player: Player) -> Option<Move> {
+		let start = Instant::now();
+
+		let handed;
+		let position = if player == self.position.player {
+			&self.position
+		} else {
+			handed = self.position.with_player(player);
+			&handed
+		};
+
+		let mv = if let Some(mut mcts) = self.mcts.take() {
+			let mv = mcts.search(position);
+			self.stats = mcts.stats();
+			self.mcts = Some(mcts);
+			mv
+		} else {
+			let mut search = AlphaBeta::default();
+			let mv = search.search(position, maximum_depth);
+			self.stats = search.stats();
+			mv
+		};
+
+		self.stats.elapsed = start.elapsed();
+		mv
+	}
+}
+
+#[napi]
+impl Checkers {
+	/// Creates a board from the `Piece` of its 32 playable squares, which
+	/// defaults to the starting position, with `turn` to move, defaulting to
+	/// the human.
+	#[napi(constructor)]
+	pub fn js_new(values: Option<Uint8Array>, turn: Option<Player>) -> Result<Self> {
+		let turn = turn.unwrap_or(Player::Human);
+		napi_assert!(turn != Player::Unset);
+
+		let Some(values) = values else {
+			return Ok(Checkers::new(Position::new(0xFFF0_0000, 0x0000_0FFF, 0, turn)));
+		};
+
+		let input = values.to_vec();
+		if input.len() != SQUARES {
+			return Err(Error::from_reason("data must have exactly 32 numbers"));
+		}
+
+		let (mut human, mut machine, mut kings) = (0, 0, 0);
+		for (square, value) in input.into_iter().enumerate() {
+			match value {
+				0 => {}
+				1 | 3 => human |= 1 << square,
+				2 | 4 => machine |= 1 << square,
+				_ => return Err(Error::from_reason("Piece only accepts 0, 1, 2, 3, or 4!")),
+			}
+
+			if value >= 3 {
+				kings |= 1 << square;
+			}
+		}
+
+		Ok(Checkers::new(Position::new(human, machine, kings, turn)))
+	}
+
+	/// The `Piece` of every playable square.
+	#[napi(getter = board)]
+	pub fn js_get_board(&self) -> Uint8Array {
+		Uint8Array::new((0..SQUARES).map(|s| self.position.piece(s) as u8).collect())
+	}
+
+	/// The player to move.
+	#[napi(getter = turn)]
+	pub fn js_get_turn(&self) -> Player {
+		self.position.player
+	}
+
+	/// The legal moves of the player to move.
+	#[napi(getter = moves)]
+	pub fn js_get_moves(&self) -> Vec<CheckersMove> {
+		self.position.moves().into_iter().map(CheckersMove::from).collect()
+	}
+
+	#[napi(getter = finished)]
+	pub fn js_finished(&self) -> bool {
+		self.position.outcome.is_some()
+	}
+
+	/// The player who won, `Player.Unset` while the game is ongoing or if it
+	/// was drawn.
+	#[napi(getter = winner)]
+	pub fn js_get_winner(&self) -> Player {
+		match self.position.outcome {
+			Some(Outcome::Win(player)) => player,
+			_ => Player::Unset,
+		}
+	}
+
+	/// Plays a move of the player to move, returning whether it won the game.
+	#[napi(js_name = "add")]
+	pub fn js_add(&mut self, mv: CheckersMove) -> Result<bool> {
+		let Some(mv) = self.position.moves().into_iter().find(|legal| {
+			u32::from(legal.from) == mv.from && legal.path().iter().map(|&s| u32::from(s)).eq(mv.path.iter().copied())
+		}) else {
+			return Err(Error::from_reason("The move is not legal"));
+		};
+
+		let player = self.position.player;
+		self.position.play(mv);
+		if let Some(mcts) = self.mcts.as_mut() {
+			mcts.advance(mv);
+		}
+
+		Ok(self.position.outcome == Some(Outcome::Win(player)))
+	}
+
+	#[napi(getter = engine)]
+	pub fn js_get_engine(&self) -> Engine {
+		if self.mcts.is_some() {
+			Engine::MonteCarlo
+		} else {
+			Engine::AlphaBeta
+		}
+	}
+
+	#[napi(js_name = "setEngine")]
+	pub fn js_set_engine(&mut self, engine: Engine, options: Option<MonteCarloOptions>) -> Result<()> {
+		self.mcts = match engine {
+			Engine::AlphaBeta => None,
+			Engine::MonteCarlo => {
+				Some(Box::new(MonteCarlo::new(options.unwrap_or_default()).map_err(Error::from_reason)?))
+			}
+		};
+
+		Ok(())
+	}
+
+	/// The statistics of the last call to `getBestMove`, every counter is zero
+	/// before the first search.
+	#[napi(getter = lastSearchStats)]
+	pub fn js_get_last_search_stats(&self) -> SearchStats {
+		self.stats.into()
+	}
+
+	/// Returns the best move for the player to move, or for the player given
+	/// in the options, or `null` if the game is finished.
+	#[napi(js_name = "getBestMove")]
+	pub fn js_get_best_move(&mut self, options: Option<BestMoveArgument>) -> Result<Option<CheckersMove>> {
+		let (depth, player) = BestMoveOptions::resolve_or(options, 8, self.position.player)?;
+		Ok(self.get_best_move(depth, player).map(CheckersMove::from))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Creates a position from the squares of each kind of piece.
+	fn position(human: &[usize], machine: &[usize], kings: &[usize], player: Player) -> Position {
+		let bits = |squares: &[usize]| squares.iter().fold(0, |bits, &s| bits | 1 << s);
+		Position::new(bits(human), bits(machine), bits(kings), player)
+	}
+
+	fn start() -> Position {
+		Position::new(0xFFF0_0000, 0x0000_0FFF, 0, Player::Human)
+	}
+
+	/// Returns the moves as `(from, path, captured squares)`.
+	fn moves(position: &Position) -> Vec<(u8, Vec<u8>, Vec<usize>)> {
+		let mut moves: Vec<_> =
+			position.moves().iter().map(|mv| (mv.from, mv.path().to_vec(), squares(mv.captured).collect())).collect();
+		moves.sort();
+		moves
+	}
+
+	#[test]
+	fn test_neighbours() {
+		assert_eq!(NEIGHBOURS[0], [NONE, NONE, 4, 5]);
+		assert_eq!(NEIGHBOURS[3], [NONE, NONE, 7, NONE]);
+		assert_eq!(NEIGHBOURS[4], [NONE, 0, NONE, 8]);
+		assert_eq!(NEIGHBOURS[13], [8, 9, 16, 17]);
+		assert_eq!(NEIGHBOURS[31], [26, 27, NONE, NONE]);
+	}
+
+	mod moves {
+		use super::*;
+
+		#[test]
+		fn test_start() {
+			let position = start();
+			let froms: Vec<u8> = moves(&position).iter().map(|(from, _, _)| *from).collect();
+
+			assert_eq!(position.moves().len(), 7);
+			assert_eq!(froms, vec![20, 21, 21, 22, 22, 23, 23]);
+		}
+
+		#[test]
+		fn test_machine_moves_down() {
+			let position = position(&[31], &[13], &[], Player::Machine);
+
+			assert_eq!(moves(&position), vec![(13, vec![16], vec![]), (13, vec![17], vec![])]);
+		}
+
+		#[test]
+		fn test_capture_is_mandatory() {
+			let position = position(&[17, 30], &[13], &[], Player::Human);
+
+			assert_eq!(moves(&position), vec![(17, vec![8], vec![13])]);
+		}
+
+		#[test]
+		fn test_multi_jump() {
+			let position = position(&[26], &[22, 13, 14], &[], Player::Human);
+
+			assert_eq!(moves(&position), vec![(26, vec![17, 8], vec![13, 22]), (26, vec![17, 10], vec![14, 22])]);
+		}
+
+		#[test]
+		fn test_men_do_not_capture_backwards() {
+			let position = position(&[13], &[17], &[], Player::Human);
+
+			assert_eq!(moves(&position), vec![(13, vec![8], vec![]), (13, vec![9], vec![])]);
+		}
+
+		#[test]
+		fn test_king_captures_backwards() {
+			let position = position(&[13], &[17], &[13], Player::Human);
+
+			assert_eq!(moves(&position), vec![(13, vec![22], vec![17])]);
+		}
+
+		#[test]
+		fn test_promotion_ends_move() {
+			// After promoting in 1, the new king could jump back over 5:
+			let position = position(&[10], &[6, 5], &[], Player::Human);
+
+			assert_eq!(moves(&position), vec![(10, vec![1], vec![6])]);
+		}
+	}
+
+	mod play {
+		use super::*;
+
+		#[test]
+		fn test_capture() {
+			let mut position = position(&[26, 31], &[22, 13, 14], &[], Player::Human);
+			position.play(position.moves()[0]);
+
+			assert_eq!(position.human, 1 << 31 | 1 << 8);
+			assert_eq!(position.machine, 1 << 14);
+			assert_eq!(position.player, Player::Machine);
+			assert_eq!(position.quiet, 0);
+		}
+
+		#[test]
+		fn test_promotion() {
+			let mut position = position(&[5], &[31], &[], Player::Human);
+			position.play(Move::new(5).then(0, None));
+
+			assert_eq!(position.kings, 1);
+			assert_eq!(position.piece(0), Piece::HumanKing);
+		}
+
+		#[test]
+		fn test_no_pieces_loses() {
+			let mut position = position(&[17], &[13], &[], Player::Human);
+			position.play(position.moves()[0]);
+
+			assert_eq!(position.outcome, Some(Outcome::Win(Player::Human)));
+		}
+
+		#[test]
+		fn test_blocked_loses() {
+			// The machine man in 4 is blocked by the human men in 8 and 13:
+			let position = position(&[8, 13, 31], &[4], &[31], Player::Machine);
+
+			assert_eq!(position.outcome, Some(Outcome::Win(Player::Human)));
+		}
+
+		#[test]
+		fn test_repetition() {
+			let mut position = position(&[0], &[31], &[0, 31], Player::Human);
+			let cycle = [(0, 4), (31, 27), (4, 0), (27, 31)].map(|(from, to)| Move::new(from).then(to, None));
+			for mv in cycle.iter().chain(&cycle) {
+				assert_eq!(position.outcome, None);
+				position.play(*mv);
+			}
+
+			assert_eq!(position.outcome, Some(Outcome::Draw));
+		}
+
+		#[test]
+		fn test_inactivity() {
+			let mut position = position(&[0], &[31], &[0, 31], Player::Human);
+			position.quiet = INACTIVITY_PLIES - 1;
+			position.play(Move::new(0).then(4, None));
+
+			assert_eq!(position.outcome, Some(Outcome::Draw));
+		}
+
+		#[test]
+		fn test_man_move_resets_inactivity() {
+			let mut position = position(&[0, 20], &[31], &[0, 31], Player::Human);
+			position.quiet = INACTIVITY_PLIES - 1;
+			position.play(Move::new(20).then(16, None));
+
+			assert_eq!(position.quiet, 0);
+			assert_eq!(position.history.len(), 1);
+			assert_eq!(position.outcome, None);
+		}
+	}
+
+	mod evaluate {
+		use super::*;
+
+		#[test]
+		fn test_start() {
+			assert_eq!(start().evaluate(), 0);
+		}
+
+		#[test]
+		fn test_material() {
+			let position = position(&[21, 22], &[9], &[], Player::Machine);
+
+			assert!(position.evaluate() < 0);
+			assert!(Position { player: Player::Human, ..position }.evaluate() > 0);
+		}
+	}
+
+	mod get_best_move {
+		use super::*;
+
+		macro_rules! gen_test {
+			($($name:ident: [$position:expr, $outcome:expr],)*) => ($(
+				#[test]
+				fn $name() {
+					let mut board = Checkers::new($position);
+					assert_eq!(board.get_best_move(6, board.position.player).map(|mv| (mv.from, mv.path().to_vec())), $outcome);
+				}
+			)*);
+		}
+
+		gen_test! {
+			test_forced_capture: [position(&[17, 30], &[13], &[], Player::Human), Some((17, vec![8]))],
+			test_multi_jump: [position(&[26], &[22, 13], &[], Player::Human), Some((26, vec![17, 8]))],
+			// Moving the man to 9 would let the machine jump it:
+			test_avoid_capture: [position(&[14], &[5], &[], Player::Human), Some((14, vec![10]))],
+			test_finished: [position(&[17], &[], &[], Player::Machine), None],
+		}
+
+		#[test]
+		fn test_other_player() {
+			let mut board = Checkers::new(position(&[17, 30], &[13], &[], Player::Machine));
+			let mv = board.get_best_move(6, Player::Human);
+
+			assert_eq!(mv.map(|mv| (mv.from, mv.path().to_vec())), Some((17, vec![8])));
+			assert_eq!(board.position.player, Player::Machine);
+		}
+
+		#[test]
+		fn test_stats() {
+			let mut board = Checkers::new(start());
+			board.get_best_move(4, board.position.player);
+
+			assert!(board.stats.nodes > 0);
+			assert!(board.stats.cutoffs > 0);
+			assert_eq!(board.stats.max_depth, 4);
+		}
+
+		#[test]
+		fn test_monte_carlo() {
+			let mut board = Checkers::new(position(&[17, 30], &[13], &[], Player::Human));
+			board
+				.js_set_engine(
+					Engine::MonteCarlo,
+					Some(MonteCarloOptions { iterations: Some(200), seed: Some(42), ..Default::default() }),
+				)
+				.unwrap();
+
+			assert_eq!(board.js_get_engine(), Engine::MonteCarlo);
+			assert_eq!(board.get_best_move(6, board.position.player).map(|mv| mv.from), Some(17));
+			assert!(board.stats.leaves > 0);
+		}
+	}
+}
@@ -1,267 +1,460 @@
 use std::{
 	cmp,
-	hint::unreachable_unchecked,
-	simd::{cmp::SimdPartialEq, u8x4, Mask, Simd},
+	simd::{cmp::SimdPartialEq, Simd},
+	sync::Arc,
+	time::Instant,
 };
 
 use napi::{bindgen_prelude::Uint8Array, Error, Result};
+use rayon::{prelude::*, ThreadPool, ThreadPoolBuilder};
+
+use crate::{
+	isize_to_usize, napi_assert,
+	search::{
+		game::{Game, Outcome},
+		mcts::{MonteCarlo, MonteCarloOptions},
+		options::{BestMoveArgument, BestMoveOptions},
+		random::Random,
+		stats::{SearchStats, Stats},
+		tt::{zobrist_keys, Bound, TranspositionTable},
+	},
+	Engine, Player, U_INVALID_INDEX,
+};
 
-use crate::{isize_to_usize, many_eq, napi_assert, Player, U_INVALID_INDEX};
-
+#[cfg_attr(feature = "tune", allow(dead_code))]
+mod hint;
+mod layout;
+#[cfg_attr(feature = "tune", allow(dead_code))]
+mod multiplayer;
+#[cfg_attr(feature = "tune", allow(dead_code))]
+mod review;
+#[cfg_attr(feature = "tune", allow(dead_code))]
+mod threats;
+mod weights;
+
+use layout::{Direction, Layout, Size};
+pub use weights::DEFAULT_WEIGHTS;
+
+/// The width of the classic board.
 pub const BOARD_WIDTH: usize = 7;
+/// The height of the classic board.
 pub const BOARD_HEIGHT: usize = 6;
 pub const BOARD_CELLS: usize = BOARD_WIDTH * BOARD_HEIGHT;
 
-const OUTCOME_HUMAN_WINS: i32 = -999_999_999;
-const OUTCOME_MACHINE_WINS: i32 = 999_999_999;
-
-const AVAILABLE_BOTTOM: [u8; BOARD_CELLS] = [
-	0b0011, 0b0011, 0b0011, 0b0011, 0b0011, 0b0011, 0b0011, //
-	0b0011, 0b0011, 0b0011, 0b0011, 0b0011, 0b0011, 0b0011, //
-	0b0011, 0b0011, 0b0011, 0b0011, 0b0011, 0b0011, 0b0011, //
-	0b0010, 0b0010, 0b0010, 0b0010, 0b0010, 0b0010, 0b0010, //
-	0b0001, 0b0001, 0b0001, 0b0001, 0b0001, 0b0001, 0b0001, //
-	0b0000, 0b0000, 0b0000, 0b0000, 0b0000, 0b0000, 0b0000,
-];
-
-const AVAILABLE_HORIZONTAL: [u8; BOARD_CELLS] = [
-	0b0011, 0b0111, 0b1011, 0b1111, 0b1110, 0b1101, 0b1100, //
-	0b0011, 0b0111, 0b1011, 0b1111, 0b1110, 0b1101, 0b1100, //
-	0b0011, 0b0111, 0b1011, 0b1111, 0b1110, 0b1101, 0b1100, //
-	0b0011, 0b0111, 0b1011, 0b1111, 0b1110, 0b1101, 0b1100, //
-	0b0011, 0b0111, 0b1011, 0b1111, 0b1110, 0b1101, 0b1100, //
-	0b0011, 0b0111, 0b1011, 0b1111, 0b1110, 0b1101, 0b1100,
-];
-
-const AVAILABLE_DIAGONAL_TL: [u8; BOARD_CELLS] = [
-	0b0011, 0b0011, 0b0011, 0b0011, 0b0010, 0b0001, 0b0000, //
-	0b0011, 0b0111, 0b0111, 0b0111, 0b0110, 0b0101, 0b0100, //
-	0b0011, 0b0111, 0b1011, 0b1011, 0b1010, 0b1001, 0b1000, //
-	0b0010, 0b0110, 0b1010, 0b1110, 0b1110, 0b1101, 0b1100, //
-	0b0001, 0b0101, 0b1001, 0b1101, 0b1101, 0b1101, 0b1100, //
-	0b0000, 0b0100, 0b1000, 0b1100, 0b1100, 0b1100, 0b1100,
-];
-
-const AVAILABLE_DIAGONAL_BL: [u8; BOARD_CELLS] = [
-	0b0000, 0b0100, 0b1000, 0b1100, 0b1100, 0b1100, 0b1100, //
-	0b0001, 0b0101, 0b1001, 0b1101, 0b1101, 0b1101, 0b1100, //
-	0b0010, 0b0110, 0b1010, 0b1110, 0b1110, 0b1101, 0b1100, //
-	0b0011, 0b0111, 0b1011, 0b1011, 0b1010, 0b1001, 0b1000, //
-	0b0011, 0b0111, 0b0111, 0b0111, 0b0110, 0b0101, 0b0100, //
-	0b0011, 0b0011, 0b0011, 0b0011, 0b0010, 0b0001, 0b0000,
-];
+/// The largest supported board, and the longest supported line.
+pub const MAX_WIDTH: usize = 10;
+pub const MAX_HEIGHT: usize = 8;
+pub const MAX_CELLS: usize = MAX_WIDTH * MAX_HEIGHT;
+pub const MAX_CONNECT: usize = 8;
+
+const _: () = assert!(BOARD_CELLS <= MAX_CELLS);
+
+/// The score of a won game from the perspective of the winner, adjusted by
+/// the remaining depth so the search prefers the fastest wins and the slowest
+/// losses.
+const OUTCOME_WIN: i32 = 999_999_999;
+
+/// The plies after which a [`Position`] of a PopOut game is drawn, as pops
+/// let a game go on forever.
+const POP_OUT_PLIES: u16 = 2 * MAX_CELLS as u16;
+
+/// The size of the transposition table, as a power of two.
+const TRANSPOSITION_TABLE_BITS: u8 = 16;
+
+/// The Zobrist keys of every cell for each player, followed by the key of the
+/// positions where [`Player::Human`] is to move.
+const ZOBRIST_KEYS: [u64; MAX_CELLS * 2 + 1] = zobrist_keys(0x5C4A_C0DE);
+const ZOBRIST_SIDE_KEY: u64 = ZOBRIST_KEYS[MAX_CELLS * 2];
+
+fn zobrist(offset: usize, player: Player) -> u64 {
+	ZOBRIST_KEYS[offset * 2 + side(player)]
+}
+
+/// Returns the index of a player in the tables kept per player.
+fn side(player: Player) -> usize {
+	debug_assert_ne!(player, Player::Unset);
+
+	player as usize - 1
+}
 
+/// The cells of a classic board.
+#[cfg(any(test, feature = "tune"))]
 pub type AiCells = [Player; BOARD_CELLS];
-pub type AiRemaining = [u8; BOARD_WIDTH];
+pub type AiRemaining = [u8; MAX_WIDTH];
+
+/// A window of up to [`MAX_CONNECT`] cells, where the lanes past the line
+/// length hold [`OUTSIDE`].
+type Window = Simd<u8, MAX_CONNECT>;
+
+/// The value of the lanes of a [`Window`] that are not part of the board, which
+/// matches neither a player nor an empty cell.
+const OUTSIDE: u8 = u8::MAX;
+
+const SCORE_PLAYER_MASK_HUMAN: Window = Window::from_array([Player::Human as u8; MAX_CONNECT]);
+const SCORE_PLAYER_MASK_MACHINE: Window = Window::from_array([Player::Machine as u8; MAX_CONNECT]);
+
+/// The parameters used by the static evaluation of a board.
+///
+/// Window weights are added once per window of as many cells as the winning
+/// line length that contains the described pieces, with the remaining cells
+/// being empty. The opponent weights are expected to be negative.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Weights {
+	/// The score given to every player piece in the center columns.
+	pub center: i32,
+	/// The score of a window with a single empty cell and the rest being player
+	/// pieces, 3 of them in the classic game.
+	pub three: i32,
+	/// The score of a window with 2 empty cells and the rest being player
+	/// pieces.
+	pub two: i32,
+	/// The score of a window with a single empty cell and the rest being
+	/// opponent pieces.
+	pub opponent_three: i32,
+	/// The score of a window with 2 empty cells and the rest being opponent
+	/// pieces.
+	pub opponent_two: i32,
+}
+
+impl Default for Weights {
+	fn default() -> Self {
+		DEFAULT_WEIGHTS
+	}
+}
+
+/// The variants of [`ConnectFour`] played on other boards.
+#[napi]
+#[repr(u8)]
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "tune", derive(Clone, Copy))]
+#[cfg_attr(feature = "tune", allow(dead_code))]
+pub enum ConnectFourVariant {
+	/// The classic 7×6 board, where 4 pieces in a row win.
+	Classic,
+	/// A 6×5 board where 3 pieces in a row win, for quick games.
+	ConnectThree,
+	/// A 9×6 board where 5 pieces in a row win, whose outside columns start
+	/// filled with alternating pieces.
+	FiveInARow,
+}
+
+/// The options of a [`ConnectFour`] board, which defaults to the classic 7×6
+/// board where 4 pieces in a row win.
+#[napi(object)]
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "tune", allow(dead_code))]
+pub struct ConnectFourOptions {
+	/// The variant that sets the default size, defaults to
+	/// `ConnectFourVariant.Classic`.
+	pub variant: Option<ConnectFourVariant>,
+	/// The amount of columns, between 3 and 10, defaults to the one of the
+	/// variant.
+	pub width: Option<u32>,
+	/// The amount of rows, between 3 and 8, defaults to the one of the
+	/// variant.
+	pub height: Option<u32>,
+	/// The amount of pieces in a row needed to win, between 3 and 8 and no
+	/// longer than the board, defaults to the one of the variant.
+	pub connect: Option<u32>,
+	/// Whether players may pop one of their own pieces out of the bottom row
+	/// instead of dropping one, defaults to `false`.
+	pub pop_out: Option<bool>,
+	/// Whether the board is a cylinder, where horizontal and diagonal lines
+	/// wrap from the last column back to the first one, defaults to `false`.
+	/// Lines cannot be longer than the width of a cylinder.
+	pub cylinder: Option<bool>,
+}
+
+#[cfg_attr(feature = "tune", allow(dead_code))]
+impl ConnectFourOptions {
+	fn size(&self) -> Size {
+		let default = match self.variant {
+			None | Some(ConnectFourVariant::Classic) => Size::CLASSIC,
+			Some(ConnectFourVariant::ConnectThree) => Size::CONNECT_THREE,
+			Some(ConnectFourVariant::FiveInARow) => Size::FIVE_IN_A_ROW,
+		};
+		let get = |value: Option<u32>, default: usize| value.map_or(default, |v| v as usize);
+
+		Size {
+			width: get(self.width, default.width),
+			height: get(self.height, default.height),
+			connect: get(self.connect, default.connect),
+		}
+	}
+
+	/// Fills the starting cells of the variant, Five-in-a-Row fills its outside
+	/// columns with alternating pieces, starting from the bottom with the
+	/// human's on the left and the machine's on the right.
+	fn fill_starting_cells(&self, size: Size, cells: &mut [Player]) {
+		if self.variant != Some(ConnectFourVariant::FiveInARow) {
+			return;
+		}
+
+		for row in 0..size.height {
+			let bottom = (size.height - 1 - row).is_multiple_of(2);
+			let (left, right) =
+				if bottom { (Player::Human, Player::Machine) } else { (Player::Machine, Player::Human) };
+			cells[row * size.width] = left;
+			cells[row * size.width + size.width - 1] = right;
+		}
+	}
+}
 
-const SCORE_PLAYER_MASK_HUMAN: Simd<u8, 4> = u8x4::from_array([Player::Human as u8; 4]);
-const SCORE_PLAYER_MASK_MACHINE: Simd<u8, 4> = u8x4::from_array([Player::Machine as u8; 4]);
+/// The kinds of moves of a [`ConnectFour`] board.
+#[napi]
+#[repr(u8)]
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "tune", derive(Clone, Copy))]
+#[cfg_attr(feature = "tune", allow(dead_code))]
+pub enum MoveKind {
+	/// Drops a piece on top of a column.
+	Drop,
+	/// Removes a piece of the player from the bottom of a column, moving the
+	/// rest of the column one row down. Only available in PopOut games.
+	Pop,
+}
 
 #[napi]
+#[derive(Clone)]
 pub struct ConnectFour {
-	cells: AiCells,
+	/// The generated tables of the board size.
+	layout: Arc<Layout>,
+	/// The cells of the board, row by row from the top, only the first
+	/// `width * height` are used.
+	cells: [Player; MAX_CELLS],
 	remaining: AiRemaining,
 	empty: u8,
-	score_player_mask: Simd<u8, 4>,
+	/// Whether pieces can be popped out of the bottom row.
+	pop_out: bool,
+	/// The player who won with the last move, [`Player::Unset`] while nobody
+	/// did.
+	winner: Player,
+	score_player_mask: Window,
+	weights: Weights,
+	mcts: Option<Box<MonteCarlo<Position>>>,
+	/// The pool the root moves are searched in, [`None`] to search them
+	/// serially in the calling thread.
+	pool: Option<Arc<ThreadPool>>,
+	/// The Zobrist key of the cells.
+	hash: u64,
+	tt: TranspositionTable<u8>,
+	/// The last two moves that caused a cutoff, per remaining depth.
+	killers: [[u8; 2]; MAX_CELLS + 1],
+	/// How much each move caused cutoffs, per player.
+	history: [[u32; 2 * MAX_WIDTH]; 2],
+	/// The counters of the last search.
+	stats: Stats,
 }
 
-/// Checks a series of pointer offsets to see if any overlapping groups of 4 are
-/// equal.
-///
-/// This macro assumes `cells` is an expression resolving to an indexable
-/// collection of [`Player`]. `cell` therefore must be a `usize`, and all
-/// `offset`s are assumed to be an `isize`.
+/// A [`ConnectFour`] board alongside the player to move, as searched by the
+/// [`MonteCarlo`] engine.
 ///
-/// Note that in debug mode, this makes assertions of `O(1.5n)` time, but it
-/// does not in release mode for optimizations. It is also block-safe.
-///
-/// # Safety
-///
-/// Undefined behaviour is caused if the pointer offsets added to the origin
-/// cell fall outside of the range [0,[`BOARD_CELLS`]), as this causes the
-/// resulting pointers to overshoot the cell collection.
-macro_rules! check_offsets {
-	($cells:expr, $cell:expr, $($offset:expr),+ $(,)?) => {
-		{
-			if cfg!(debug_assertions) {
-				use itertools::{MinMaxResult, Itertools};
-
-				let (min, max) = match [$($offset),+].iter().minmax() {
-					MinMaxResult::MinMax(a, b) => (*a, *b),
-					MinMaxResult::OneElement(a) => (*a, *a),
-					_ => std::hint::unreachable_unchecked(),
-				};
-				if ($cell as isize + min < 0) || ($cell as isize + max >= BOARD_CELLS as isize) {
-					panic!("{} + [{}, {}] offsets fall outside of cell bounds [0, {}]", $cell, min, max, BOARD_CELLS)
-				}
-				assert_ne!($cells[$cell], Player::Unset);
-			}
-
-			let ptr = $cells.as_ptr().add($cell);
-			[$($offset),+].map(|x| *ptr.offset(x)).windows(4).any(|x| many_eq!(x[0], x[1], x[2], x[3]))
-		}
-	};
+/// Moves are columns to drop a piece in, and `width + column` to pop one.
+#[derive(Clone)]
+pub struct Position {
+	board: ConnectFour,
+	player: Player,
+	outcome: Option<Outcome>,
+	/// The plies played since the search started, which only PopOut games
+	/// need to end.
+	plies: u16,
 }
 
-/// Makes an array of offsets based on a base offset and a multiplier.
-///
-/// This macro is used to generate the offsets for the various directions in the
-/// Connect Four game board.
-///
-/// # Example
-///
-/// ```rust
-/// let offsets = gen_offset_array!(BOARD_WIDTH + 1, 0);
-/// assert_eq!(offsets, [0, 8, 16, 24, 32, 40]);
-/// ```
-///
-/// Which is equivalent to:
-///
-/// ```rust
-/// let offsets = [
-///     0, //
-///     0 + (BOARD_WIDTH + 1) * 1, // BOARD_WIDTH + 1
-///     0 + (BOARD_WIDTH + 1) * 2, // BOARD_WIDTH * 2 + 2,
-///     0 + (BOARD_WIDTH + 1) * 3, // BOARD_WIDTH * 3 + 3,
-///     0 + (BOARD_WIDTH + 1) * 4, // BOARD_WIDTH * 4 + 4,
-///     0 + (BOARD_WIDTH + 1) * 5, // BOARD_WIDTH * 5 + 5,
-/// ];
-/// ```
-macro_rules! gen_offset_array {
-	($offset:expr, $base:expr) => {
-		[$base, $base + $offset * 1, $base + $offset * 2, $base + $offset * 3, $base + $offset * 4, $base + $offset * 5]
-	};
+impl PartialEq for Position {
+	fn eq(&self, other: &Self) -> bool {
+		self.board.cells == other.board.cells && self.player == other.player && self.outcome == other.outcome
+	}
 }
 
-/// Converts a 4-bit bit mask in the form of a [`Mask`] to a count of the number
-/// of bits set to 1, which is used to determine the number of empty and player
-/// pieces in a window.
-///
-/// # Remarks
-///
-/// This function does not use `.count_ones()` because it produces more
-/// instructions, leading to a slower implementation. Because the number of
-/// possible bit masks is small, this function is implemented as a match
-/// statement.
-///
-/// # Safety
-///
-/// Undefined behaviour is caused if the bitmask is not a valid 4-bit bitmask,
-/// which is guaranteed by the [`Mask`] type.
-fn bitmask_to_count(mask: Mask<i8, 4>) -> u8 {
-	match mask.to_bitmask() {
-		0b0000 => 0,
-		0b0001 | 0b0010 | 0b0100 | 0b1000 => 1,
-		0b0011 | 0b0101 | 0b0110 | 0b1001 | 0b1010 | 0b1100 => 2,
-		0b0111 | 0b1011 | 0b1101 | 0b1110 => 3,
-		0b1111 => 4,
-		_ => unsafe { unreachable_unchecked() },
+impl Game for Position {
+	type Move = usize;
+
+	fn player(&self) -> Player {
+		self.player
+	}
+
+	fn moves(&self) -> Vec<usize> {
+		if self.outcome.is_some() {
+			return Vec::new();
+		}
+
+		let width = self.board.layout.size.width;
+		let drops = (0..width).filter(|&c| self.board.available(c));
+		let pops = (0..width).filter(|&c| self.board.can_pop(c, self.player)).map(|c| width + c);
+		drops.chain(pops).collect()
+	}
+
+	fn play(&mut self, mv: usize) {
+		self.plies += 1;
+		self.outcome = match self.board.play_move(mv, self.player) {
+			Some(winner) => Some(Outcome::Win(winner)),
+			None if !self.board.has_moves(self.player.opponent()) || self.plies >= POP_OUT_PLIES => Some(Outcome::Draw),
+			None => None,
+		};
+
+		self.player = self.player.opponent();
+	}
+
+	fn outcome(&self) -> Option<Outcome> {
+		self.outcome
+	}
+
+	/// Takes an immediate win if there is one, otherwise blocks the opponent's.
+	/// Only drops are considered.
+	fn heuristic_move(&mut self, moves: &[usize], _random: &mut Random) -> Option<usize> {
+		let width = self.board.layout.size.width;
+		[self.player, self.player.opponent()]
+			.into_iter()
+			.find_map(|player| moves.iter().copied().find(|&mv| mv < width && self.board.is_winning_move(mv, player)))
 	}
 }
 
 impl ConnectFour {
-	fn new(cells: AiCells) -> Self {
-		let remaining: [u8; BOARD_WIDTH] = unsafe {
-			(0..BOARD_WIDTH)
-				.map(|x| (0..BOARD_HEIGHT).take_while(|y| cells[y * BOARD_WIDTH + x] == Player::Unset).count() as u8)
-				.collect::<Vec<_>>()
-				.try_into()
-				.unwrap_unchecked()
-		};
-		let empty = remaining.iter().sum();
+	/// Creates a classic board from its cells.
+	#[cfg(any(test, feature = "tune"))]
+	pub(crate) fn new(cells: AiCells) -> Self {
+		Self::with_size(Size::CLASSIC, &cells)
+	}
 
-		Self { cells, remaining, empty, score_player_mask: SCORE_PLAYER_MASK_HUMAN }
+	/// Creates a flat board of the given size, which must be valid, from its
+	/// `width * height` cells.
+	#[cfg(any(test, feature = "tune"))]
+	pub(crate) fn with_size(size: Size, cells: &[Player]) -> Self {
+		Self::with_layout(Layout::get(size), cells)
 	}
 
-	#[allow(clippy::too_many_arguments)]
-	fn status_row(
-		&self,
-		mask: u8,
-		cell: usize,
-		l1: isize,
-		l2: isize,
-		l3: isize,
-		r1: isize,
-		r2: isize,
-		r3: isize,
-	) -> bool {
-		match mask {
-			// 0bxx11:
-			0b0011 => unsafe { check_offsets!(self.cells, cell, 0, r1, r2, r3) },
-			0b0111 => unsafe { check_offsets!(self.cells, cell, l1, 0, r1, r2, r3) },
-			0b1011 => unsafe { check_offsets!(self.cells, cell, l2, l1, 0, r1, r2, r3) },
-			// 0b11xx:
-			0b1100 => unsafe { check_offsets!(self.cells, cell, l3, l2, l1, 0) },
-			0b1101 => unsafe { check_offsets!(self.cells, cell, l3, l2, l1, 0, r1) },
-			0b1110 => unsafe { check_offsets!(self.cells, cell, l3, l2, l1, 0, r1, r2) },
-			0b1111 => unsafe { check_offsets!(self.cells, cell, l3, l2, l1, 0, r1, r2, r3) },
+	/// Creates a board with the given layout from its `width * height` cells.
+	fn with_layout(layout: Arc<Layout>, cells: &[Player]) -> Self {
+		let size = layout.size;
+		debug_assert_eq!(cells.len(), size.cells());
 
-			// 0b01xx \ {0b0100, 0b0101} -> {0b0110, [0b0111]}:
-			0b0110 => unsafe { check_offsets!(self.cells, cell, l1, 0, r1, r2) },
-			// 0b10xx \ {0b1000} -> {0b1001, 0b1010, [0b1011]}:
-			0b1001 => unsafe { check_offsets!(self.cells, cell, l2, l1, 0, r1) },
-			0b1010 => unsafe { check_offsets!(self.cells, cell, l2, l1, 0, r1, r2) },
-			// 0bxx01 \ {0b0001, 0b0101} -> {[0b1001], [0b1101]}:
-			// 0bxx10 \ {0b0010} -> {[0b0110], [0b1010], [0b1100]}:
+		let mut board = [Player::Unset; MAX_CELLS];
+		board[..cells.len()].copy_from_slice(cells);
 
-			// Handle all other cases:
-			_ => false,
+		let mut remaining = [0; MAX_WIDTH];
+		for (x, remaining) in remaining[..size.width].iter_mut().enumerate() {
+			*remaining = (0..size.height).take_while(|y| cells[y * size.width + x] == Player::Unset).count() as u8;
+		}
+
+		let empty = remaining.iter().sum();
+		let hash = cells
+			.iter()
+			.enumerate()
+			.filter(|(_, &cell)| cell != Player::Unset)
+			.fold(0, |hash, (offset, &cell)| hash ^ zobrist(offset, cell));
+
+		Self {
+			layout,
+			cells: board,
+			remaining,
+			empty,
+			pop_out: false,
+			winner: Player::Unset,
+			score_player_mask: SCORE_PLAYER_MASK_HUMAN,
+			weights: DEFAULT_WEIGHTS,
+			mcts: None,
+			pool: None,
+			hash,
+			tt: TranspositionTable::default(),
+			killers: [[U_INVALID_INDEX as u8; 2]; MAX_CELLS + 1],
+			history: [[0; 2 * MAX_WIDTH]; 2],
+			stats: Stats::default(),
+		}
+	}
+
+	/// Creates a copy of this board without the search state.
+	fn snapshot(&self) -> Self {
+		Self {
+			layout: self.layout.clone(),
+			cells: self.cells,
+			remaining: self.remaining,
+			empty: self.empty,
+			pop_out: self.pop_out,
+			winner: self.winner,
+			score_player_mask: self.score_player_mask,
+			weights: self.weights,
+			mcts: None,
+			pool: None,
+			hash: self.hash,
+			tt: TranspositionTable::default(),
+			killers: [[U_INVALID_INDEX as u8; 2]; MAX_CELLS + 1],
+			history: [[0; 2 * MAX_WIDTH]; 2],
+			stats: Stats::default(),
 		}
 	}
 
-	fn status_vertical(&self, cell: usize) -> bool {
-		const B1: isize = BOARD_WIDTH as isize;
-		const B2: isize = B1 * 2;
-		const B3: isize = B1 * 3;
+	/// Creates a [`Position`] from a copy of this board, which is finished if
+	/// the board was already won.
+	fn position(&self, player: Player) -> Position {
+		let outcome = if self.winner != Player::Unset {
+			Some(Outcome::Win(self.winner))
+		} else {
+			(!self.has_moves(player)).then_some(Outcome::Draw)
+		};
 
-		AVAILABLE_BOTTOM[cell] == 3 && unsafe { check_offsets!(self.cells, cell, 0, B1, B2, B3) }
+		Position { board: self.snapshot(), player, outcome, plies: 0 }
 	}
 
-	fn status_horizontal(&self, cell: usize) -> bool {
-		const L1: isize = -1;
-		const L2: isize = L1 * 2;
-		const L3: isize = L1 * 3;
-		const R1: isize = 1;
-		const R2: isize = R1 * 2;
-		const R3: isize = R1 * 3;
+	/// Sets the amount of threads used by the alpha-beta search, a single
+	/// thread searches serially in the calling thread.
+	#[cfg_attr(feature = "tune", allow(dead_code))]
+	fn set_threads(&mut self, threads: usize) -> std::result::Result<(), String> {
+		self.pool = match threads {
+			1 => None,
+			_ => Some(Arc::new(ThreadPoolBuilder::new().num_threads(threads).build().map_err(|e| e.to_string())?)),
+		};
 
-		self.status_row(AVAILABLE_HORIZONTAL[cell], cell, L1, L2, L3, R1, R2, R3)
+		Ok(())
 	}
 
-	fn status_diagonal_tl(&self, cell: usize) -> bool {
-		const TL1: isize = -(BOARD_WIDTH as isize) - 1;
-		const TL2: isize = TL1 * 2;
-		const TL3: isize = TL1 * 3;
-		const BR1: isize = (BOARD_WIDTH as isize) + 1;
-		const BR2: isize = BR1 * 2;
-		const BR3: isize = BR1 * 3;
+	#[cfg_attr(feature = "tune", allow(dead_code))]
+	fn threads(&self) -> usize {
+		self.pool.as_ref().map_or(1, |pool| pool.current_num_threads())
+	}
 
-		self.status_row(AVAILABLE_DIAGONAL_TL[cell], cell, TL1, TL2, TL3, BR1, BR2, BR3)
+	#[cfg(feature = "tune")]
+	pub(crate) fn with_weights(cells: AiCells, weights: Weights) -> Self {
+		Self { weights, ..Self::new(cells) }
 	}
 
-	fn status_diagonal_bl(&self, cell: usize) -> bool {
-		const BL1: isize = (BOARD_WIDTH as isize) - 1;
-		const BL2: isize = BL1 * 2;
-		const BL3: isize = BL1 * 3;
-		const TR1: isize = -(BOARD_WIDTH as isize) + 1;
-		const TR2: isize = TR1 * 2;
-		const TR3: isize = TR1 * 3;
+	/// Counts the pieces of `player` following `cell` in a direction, backwards
+	/// or forwards by `side`, up to `reach` of them.
+	fn count_direction(&self, player: Player, cell: usize, direction: Direction, side: usize, reach: u8) -> usize {
+		let mut offset = cell;
+		for count in 0..reach as usize {
+			offset = self.layout.neighbours[offset][direction as usize][side] as usize;
+			if self.cells[offset] != player {
+				return count;
+			}
+		}
 
-		self.status_row(AVAILABLE_DIAGONAL_BL[cell], cell, BL1, BL2, BL3, TR1, TR2, TR3)
+		reach as usize
 	}
 
+	/// Checks whether the piece at `last_cell_offset` is part of a winning
+	/// line, looking only as far as the generated reach of the cell allows.
 	fn status(&self, last_cell_offset: usize) -> bool {
-		debug_assert!(last_cell_offset < BOARD_CELLS);
+		debug_assert!(last_cell_offset < self.layout.size.cells());
 		debug_assert!(self.cells[last_cell_offset] != Player::Unset);
 
-		self.status_vertical(last_cell_offset)
-			|| self.status_horizontal(last_cell_offset)
-			|| self.status_diagonal_bl(last_cell_offset)
-			|| self.status_diagonal_tl(last_cell_offset)
+		let player = self.cells[last_cell_offset];
+		let reach = &self.layout.reach[last_cell_offset];
+		Direction::ALL.into_iter().any(|direction| {
+			let (backward, forward) = reach[direction as usize];
+
+			1 + self.count_direction(player, last_cell_offset, direction, 0, backward)
+				+ self.count_direction(player, last_cell_offset, direction, 1, forward)
+				>= self.layout.size.connect
+		})
+	}
+
+	/// The cells of the board, row by row from the top.
+	pub(crate) fn cells(&self) -> &[Player] {
+		&self.cells[..self.layout.size.cells()]
 	}
 
-	fn available(&self, column: usize) -> bool {
-		debug_assert!(column < BOARD_WIDTH);
+	pub(crate) fn available(&self, column: usize) -> bool {
+		debug_assert!(column < self.layout.size.width);
 
 		self.remaining[column] > 0
 	}
@@ -269,216 +462,245 @@ impl ConnectFour {
 	fn piece_offset(&self, column: usize) -> usize {
 		debug_assert!(self.available(column));
 
-		((self.remaining[column] as usize - 1) * BOARD_WIDTH) + column
+		((self.remaining[column] as usize - 1) * self.layout.size.width) + column
 	}
 
 	fn add(&mut self, column: usize, offset: usize, player: Player) {
 		debug_assert!(self.available(column));
-		debug_assert!(offset < BOARD_CELLS);
+		debug_assert!(offset < self.layout.size.cells());
 		debug_assert!(self.cells[offset] == Player::Unset);
 
 		self.remaining[column] -= 1;
 		self.cells[offset] = player;
+		self.hash ^= zobrist(offset, player);
+	}
+
+	/// Drops a piece of `player` in `column`, returning whether it won the
+	/// game.
+	pub(crate) fn play(&mut self, column: usize, player: Player) -> bool {
+		let offset = self.piece_offset(column);
+		self.add(column, offset, player);
+		self.empty -= 1;
+		self.status(offset)
+	}
+
+	/// Checks whether dropping a piece of `player` in `column` wins the game,
+	/// leaving the board unchanged.
+	fn is_winning_move(&mut self, column: usize, player: Player) -> bool {
+		let offset = self.piece_offset(column);
+		self.add(column, offset, player);
+		let won = self.status(offset);
+		self.remove(column, offset);
+		won
 	}
 
 	fn remove(&mut self, column: usize, offset: usize) {
-		debug_assert!(column < BOARD_WIDTH);
-		debug_assert!(self.remaining[column] != BOARD_HEIGHT as u8);
-		debug_assert!(offset < BOARD_CELLS);
+		debug_assert!(column < self.layout.size.width);
+		debug_assert!(self.remaining[column] as usize != self.layout.size.height);
+		debug_assert!(offset < self.layout.size.cells());
 		debug_assert!(self.cells[offset] != Player::Unset);
 
 		self.remaining[column] += 1;
+		self.hash ^= zobrist(offset, self.cells[offset]);
 		self.cells[offset] = Player::Unset;
 	}
 
-	fn evaluate_window(&self, player: Player, window: &[u8; 4]) -> i32 {
-		debug_assert_ne!(player, Player::Unset);
-
-		const MASK_EMPTY: Simd<u8, 4> = u8x4::from_array([Player::Unset as u8; 4]);
+	/// Whether `player` can pop its piece at the bottom of `column`, which is
+	/// only possible in PopOut games.
+	pub(crate) fn can_pop(&self, column: usize, player: Player) -> bool {
+		debug_assert!(column < self.layout.size.width);
 
-		let v = u8x4::from_slice(window);
-		let empty_pieces = bitmask_to_count(v.simd_eq(MASK_EMPTY));
-		let player_pieces = bitmask_to_count(v.simd_eq(self.score_player_mask));
+		let Size { width, height, .. } = self.layout.size;
+		self.pop_out && self.cells[(height - 1) * width + column] == player
+	}
 
-		// The sum of empty and player pieces must be less than or equal to 4:
-		debug_assert!(empty_pieces + player_pieces <= 4);
-		// Winning moves will never call this function:
-		debug_assert_ne!(player_pieces, 4);
-		// Losing moves will never call this function:
-		debug_assert_ne!(player_pieces + empty_pieces, 0);
+	/// Removes the piece at the bottom of `column`, moving the rest of the
+	/// column one row down.
+	fn pop(&mut self, column: usize) {
+		debug_assert!((self.remaining[column] as usize) < self.layout.size.height);
+
+		let Size { width, height, .. } = self.layout.size;
+		let top = self.remaining[column] as usize;
+		for row in (top..height).rev() {
+			let offset = row * width + column;
+			let above = if row > top { self.cells[offset - width] } else { Player::Unset };
+
+			self.hash ^= zobrist(offset, self.cells[offset]);
+			self.cells[offset] = above;
+			if above != Player::Unset {
+				self.hash ^= zobrist(offset, above);
+			}
+		}
 
-		// The mask is a combined octal pair, where the first octal represents
-		// the number of player pieces, and the last octal represents the
-		// number of empty pieces.
-		//
-		// Since the player pieces, opponent pieces, and empty pieces are
-		// mutually exclusive and sum to 4, these are the only fields
-		// required.
-		//
-		// For example, if there are no player pieces and 1 empty piece, there
-		// must be 3 opponent pieces - a state represented as `0o0_1`.
-		//
-		// There can be up to 4 empty pieces, and up to 3 pieces for the
-		// player or the opponent, since a 4th piece would be a winning move
-		// and would not reach this function.
-		let mask: u8 = (player_pieces << 3) | empty_pieces;
+		self.remaining[column] += 1;
+	}
 
-		#[allow(clippy::manual_range_patterns)]
-		match mask {
-			// 4 player pieces: (winning move, cannot happen here)
-			0o4_0 => unsafe { unreachable_unchecked() },
+	/// Undoes [`ConnectFour::pop`], moving the pieces of `column` one row up
+	/// and placing a piece of `player` back at the bottom.
+	fn unpop(&mut self, column: usize, player: Player) {
+		debug_assert!(self.remaining[column] > 0);
 
-			// 3 player pieces:
-			0o3_1 => 100,
-			0o3_0 => 0,
+		let Size { width, height, .. } = self.layout.size;
+		let top = self.remaining[column] as usize - 1;
+		for row in top..height {
+			let offset = row * width + column;
+			let below = if row + 1 < height { self.cells[offset + width] } else { player };
 
-			// 2 player pieces:
-			0o2_2 => 10,
-			0o2_1 | 0o2_0 => 0,
+			if self.cells[offset] != Player::Unset {
+				self.hash ^= zobrist(offset, self.cells[offset]);
+			}
+			self.cells[offset] = below;
+			self.hash ^= zobrist(offset, below);
+		}
 
-			// 1 player piece:
-			0o1_0 | 0o1_1 | 0o1_2 | 0o1_3 => 0,
+		self.remaining[column] -= 1;
+	}
 
-			// 0 player pieces:
-			// - 0 empty pieces (losing move, cannot happen here)
-			0o0_0 => unsafe { unreachable_unchecked() },
-			0o0_1 => -100,
-			0o0_2 => -10,
-			0o0_3 | 0o0_4 => 0,
+	/// Returns the winner after `player` popped a piece out of `column`.
+	///
+	/// Every line a pop completes goes through the moved pieces, and may belong
+	/// to either player. When both players complete one at once, the player who
+	/// popped wins.
+	fn pop_winner(&self, column: usize, player: Player) -> Option<Player> {
+		let Size { width, height, .. } = self.layout.size;
+
+		let mut winner = None;
+		for row in self.remaining[column] as usize..height {
+			let offset = row * width + column;
+			if self.status(offset) {
+				if self.cells[offset] == player {
+					return Some(player);
+				}
 
-			// This should never happen:
-			_ => unsafe { unreachable_unchecked() },
+				winner = Some(player.opponent());
+			}
 		}
+
+		winner
 	}
 
-	#[inline(always)]
-	fn sum_windows(&self, player: Player, index: usize, offsets: &[usize], amount: usize) -> i32 {
-		debug_assert!(amount <= offsets.len());
+	/// Plays a move of `player`, either a column to drop a piece in or
+	/// `width + column` to pop one, returning the winner if the game ended.
+	pub(crate) fn play_move(&mut self, mv: usize, player: Player) -> Option<Player> {
+		let width = self.layout.size.width;
+		if mv < width {
+			return self.play(mv, player).then_some(player);
+		}
 
-		(0..amount)
-			.map(|x| unsafe { *self.cells.get_unchecked(index + offsets[x]) }.into())
-			.map_windows(|window: &[u8; 4]| self.evaluate_window(player, window))
-			.sum()
+		self.pop(mv - width);
+		self.empty += 1;
+		self.pop_winner(mv - width, player)
 	}
 
-	fn score_position_center_column(&self, player: Player) -> i32 {
-		const OFFSETS: [usize; BOARD_HEIGHT] = gen_offset_array!(BOARD_WIDTH, 3);
+	/// Whether `player` has any move left, a full board only ends a PopOut
+	/// game when the player has no piece to pop.
+	fn has_moves(&self, player: Player) -> bool {
+		self.empty > 0 || (0..self.layout.size.width).any(|c| self.can_pop(c, player))
+	}
 
-		let mut score = 0;
-		for &index in &OFFSETS {
-			if unsafe { self.cells.get_unchecked(index).eq(&player) } {
-				score += 3;
-			}
+	/// The moves of `player` in search order, drops first and pops last.
+	fn moves(&self, player: Player) -> ([usize; 2 * MAX_WIDTH], usize) {
+		let width = self.layout.size.width;
+		let columns = self.layout.columns();
+		let drops = columns.iter().copied().filter(|&c| self.available(c));
+		let pops = columns.iter().copied().filter(|&c| self.can_pop(c, player)).map(|c| width + c);
+
+		let mut moves = [0; 2 * MAX_WIDTH];
+		let mut count = 0;
+		for mv in drops.chain(pops) {
+			moves[count] = mv;
+			count += 1;
 		}
 
-		score
+		(moves, count)
 	}
 
-	fn score_position_horizontal(&self, player: Player) -> i32 {
-		const POSITIONS: [usize; BOARD_HEIGHT] = gen_offset_array!(BOARD_WIDTH, 0);
-		const OFFSETS: [usize; BOARD_WIDTH] = [0, 1, 2, 3, 4, 5, 6];
+	fn evaluate_window(&self, player: Player, window: Window) -> i32 {
+		debug_assert_ne!(player, Player::Unset);
 
-		let mut score = 0;
-		for &row in &POSITIONS {
-			score += self.sum_windows(player, row, &OFFSETS, 7);
+		const MASK_EMPTY: Window = Window::from_array([Player::Unset as u8; MAX_CONNECT]);
+
+		let connect = self.layout.size.connect as u32;
+		let empty_pieces = window.simd_eq(MASK_EMPTY).to_bitmask().count_ones();
+		let player_pieces = window.simd_eq(self.score_player_mask).to_bitmask().count_ones();
+
+		// The sum of empty and player pieces must be less than or equal to the
+		// line length:
+		debug_assert!(empty_pieces + player_pieces <= connect);
+		// Winning moves will never call this function:
+		debug_assert_ne!(player_pieces, connect);
+		// Losing moves will never call this function:
+		debug_assert_ne!(player_pieces + empty_pieces, 0);
+
+		// Since the player pieces, opponent pieces, and empty pieces are
+		// mutually exclusive and sum to the line length, the amount of empty
+		// cells and whether the rest belongs to a single player is all that is
+		// needed.
+		//
+		// For example, if there are no player pieces and 1 empty piece, the
+		// rest of the window is made of opponent pieces.
+		match empty_pieces {
+			1 if player_pieces == connect - 1 => self.weights.three,
+			1 if player_pieces == 0 => self.weights.opponent_three,
+			2 if player_pieces == connect - 2 => self.weights.two,
+			2 if player_pieces == 0 => self.weights.opponent_two,
+			_ => 0,
 		}
+	}
 
-		score
+	/// Sums the evaluation of every window of the board in `direction`.
+	fn sum_windows(&self, player: Player, direction: Direction) -> i32 {
+		let connect = self.layout.size.connect;
+
+		self.layout.windows[direction as usize]
+			.iter()
+			.map(|cells| {
+				let mut window = [OUTSIDE; MAX_CONNECT];
+				for (lane, &offset) in window[..connect].iter_mut().zip(cells) {
+					// The generated windows always fit in the board:
+					*lane = unsafe { *self.cells.get_unchecked(offset as usize) } as u8;
+				}
+
+				self.evaluate_window(player, Window::from_array(window))
+			})
+			.sum()
 	}
 
-	fn score_position_vertical(&self, player: Player) -> i32 {
-		const OFFSETS: [usize; BOARD_HEIGHT] = gen_offset_array!(BOARD_WIDTH, 0);
+	fn score_position_center_column(&self, player: Player) -> i32 {
+		// No column of a cylinder is closer to the edges than the others:
+		if self.layout.wrap {
+			return 0;
+		}
+
+		let Size { width, height, .. } = self.layout.size;
 
+		// Boards with an even width have two center columns:
 		let mut score = 0;
-		for column in 0..BOARD_WIDTH {
-			score += self.sum_windows(player, column, &OFFSETS, 6);
+		for column in (width - 1) / 2..=width / 2 {
+			for row in 0..height {
+				if self.cells[row * width + column] == player {
+					score += self.weights.center;
+				}
+			}
 		}
 
 		score
 	}
 
-	fn score_position_diagonal_tl(&self, player: Player) -> i32 {
-		const OFFSETS: [usize; BOARD_HEIGHT] = gen_offset_array!(BOARD_WIDTH + 1, 0);
-
-		let mut score = 0;
+	fn score_position_horizontal(&self, player: Player) -> i32 {
+		self.sum_windows(player, Direction::Horizontal)
+	}
 
-		// Calculate the score for 6-long diagonals:
-		// X Y _ _ _ _ _ (0..7)
-		// _ X Y _ _ _ _ (7..14)
-		// _ _ X Y _ _ _ (14..21)
-		// _ _ _ X Y _ _ (21..28)
-		// _ _ _ _ X Y _ (28..35)
-		// _ _ _ _ _ X Y (35..42)
-		for index in [0, 1] {
-			score += self.sum_windows(player, index, &OFFSETS, 6);
-		}
-
-		// Calculate the score for 5-long diagonals:
-		// _ _ X _ _ _ _ (0..7)
-		// Y _ _ X _ _ _ (7..14)
-		// _ Y _ _ X _ _ (14..21)
-		// _ _ Y _ _ X _ (21..28)
-		// _ _ _ Y _ _ X (28..35)
-		// _ _ _ _ Y _ _ (35..42)
-		for index in [2, 7] {
-			score += self.sum_windows(player, index, &OFFSETS, 5);
-		}
-
-		// Calculate the score for 4-long diagonals:
-		// _ _ _ X _ _ _ (0..7)
-		// _ _ _ _ X _ _ (7..14)
-		// Y _ _ _ _ X _ (14..21)
-		// _ Y _ _ _ _ X (21..28)
-		// _ _ Y _ _ _ _ (28..35)
-		// _ _ _ Y _ _ _ (35..42)
-		for index in [3, 14] {
-			score += self.sum_windows(player, index, &OFFSETS, 4);
-		}
-
-		// We don't need to calculate the score for 3-long diagonals, as they are
-		// never going to be able to produce a winning move.
+	fn score_position_vertical(&self, player: Player) -> i32 {
+		self.sum_windows(player, Direction::Vertical)
+	}
 
-		score
+	fn score_position_diagonal_tl(&self, player: Player) -> i32 {
+		self.sum_windows(player, Direction::DiagonalTl)
 	}
 
 	fn score_position_diagonal_tr(&self, player: Player) -> i32 {
-		const OFFSETS: [usize; BOARD_HEIGHT] = gen_offset_array!(BOARD_WIDTH - 1, 0);
-		let mut score = 0;
-
-		// Calculate the score for 6-long diagonals:
-		// _ _ _ _ _ X Y (0..7)
-		// _ _ _ _ X Y _ (7..14)
-		// _ _ _ X Y _ _ (14..21)
-		// _ _ X Y _ _ _ (21..28)
-		// _ X Y _ _ _ _ (28..35)
-		// X Y _ _ _ _ _ (35..42)
-		for index in [5, 6] {
-			score += self.sum_windows(player, index, &OFFSETS, 6);
-		}
-
-		// Calculate the score for 5-long diagonals:
-		// _ _ _ _ X _ _ (0..7)
-		// _ _ _ X _ _ Y (7..14)
-		// _ _ X _ _ Y _ (14..21)
-		// _ X _ _ Y _ _ (21..28)
-		// X _ _ Y _ _ _ (28..35)
-		// _ _ Y _ _ _ _ (35..42)
-		for index in [4, 13] {
-			score += self.sum_windows(player, index, &OFFSETS, 5);
-		}
-
-		// Calculate the score for 4-long diagonals:
-		// _ _ _ X _ _ _ (0..7)
-		// _ _ X _ _ _ _ (7..14)
-		// _ X _ _ _ _ Y (14..21)
-		// X _ _ _ _ Y _ (21..28)
-		// _ _ _ _ Y _ _ (28..35)
-		// _ _ _ Y _ _ _ (35..42)
-		for index in [3, 20] {
-			score += self.sum_windows(player, index, &OFFSETS, 4);
-		}
-
-		score
+		self.sum_windows(player, Direction::DiagonalTr)
 	}
 
 	fn score_position(&self, player: Player) -> i32 {
@@ -489,67 +711,137 @@ impl ConnectFour {
 			+ self.score_position_diagonal_tr(player)
 	}
 
-	/// Minimum is `Player::Human`
-	fn min(&mut self, last_cell_offset: usize, remaining: u8, alpha: i32, beta: i32) -> i32 {
-		if self.status(last_cell_offset) {
-			return OUTCOME_MACHINE_WINS;
-		}
-
-		if remaining == 0 {
-			self.score_player_mask = SCORE_PLAYER_MASK_HUMAN;
-			return self.score_position(Player::Human);
-		}
-
-		// Possible values for min_v are:
-		// -1 - win
-		//  0 - a tie
-		//  1 - loss
-		//
-		// We're initially setting it to 2 as worse than the worst case:
-		let mut min_v = i32::MAX;
-		let mut local_beta = beta;
+	/// Scores the board from the perspective of `player`.
+	pub(crate) fn evaluate(&mut self, player: Player) -> i32 {
+		self.score_player_mask = match player {
+			Player::Machine => SCORE_PLAYER_MASK_MACHINE,
+			_ => SCORE_PLAYER_MASK_HUMAN,
+		};
+		self.score_position(player)
+	}
 
-		for c in 0..BOARD_WIDTH {
-			if !self.available(c) {
-				continue;
+	/// Orders the moves of `player`, trying first the best move stored in the
+	/// transposition table, then immediate wins, forced blocks, killer moves,
+	/// and the rest by their history score. Ties are broken from the center
+	/// outwards, with drops before pops.
+	fn order_moves(&mut self, player: Player, remaining: u8, tt_move: Option<u8>) -> ([usize; 2 * MAX_WIDTH], usize) {
+		let width = self.layout.size.width;
+		let killers = self.killers[remaining as usize];
+		let history = self.history[side(player)];
+
+		let (mut moves, count) = self.moves(player);
+		let mut keys = [(0, 0); 2 * MAX_WIDTH];
+		for (&mv, key) in moves[..count].iter().zip(&mut keys) {
+			let class: u8 = if tt_move == Some(mv as u8) {
+				5
+			} else if mv < width && self.is_winning_move(mv, player) {
+				4
+			} else if mv < width && self.is_winning_move(mv, player.opponent()) {
+				3
+			} else if killers[0] == mv as u8 {
+				2
+			} else if killers[1] == mv as u8 {
+				1
+			} else {
+				0
+			};
+
+			*key = (class, history[mv]);
+		}
+
+		// Insertion sort, which is stable and fast for 7 elements:
+		for i in 1..count {
+			let mut j = i;
+			while j > 0 && keys[j - 1] < keys[j] {
+				keys.swap(j - 1, j);
+				moves.swap(j - 1, j);
+				j -= 1;
 			}
+		}
 
-			let offset = self.piece_offset(c);
-
-			// On the empty field player Human makes a move and calls Max
-			// That's one branch of the game tree:
-			self.add(c, offset, Player::Human);
-
-			let m = self.max(offset, remaining - 1, alpha, local_beta);
+		(moves, count)
+	}
 
-			// Setting back the field to empty:
-			self.remove(c, offset);
+	/// Remembers a move that caused a cutoff, so it is tried earlier in the
+	/// sibling nodes and the rest of the search.
+	fn record_cutoff(&mut self, player: Player, remaining: u8, mv: usize) {
+		let killers = &mut self.killers[remaining as usize];
+		if killers[0] != mv as u8 {
+			killers[1] = killers[0];
+			killers[0] = mv as u8;
+		}
 
-			// Fixing the min_v value if needed:
-			if m < min_v {
-				min_v = m;
+		let history = &mut self.history[side(player)][mv];
+		*history = history.saturating_add(remaining as u32 * remaining as u32);
+	}
 
-				local_beta = cmp::min(local_beta, min_v);
-				if alpha >= local_beta {
-					break;
+	/// Plays `mv` for `player`, searches the replies of the opponent with
+	/// `remaining` plies left, and takes the move back, scoring it from the
+	/// perspective of `player`.
+	///
+	/// The outcome of a pop is settled here, as it may complete lines of both
+	/// players anywhere in its column.
+	fn search_move(&mut self, mv: usize, player: Player, remaining: u8, alpha: i32, beta: i32) -> i32 {
+		let width = self.layout.size.width;
+		if mv < width {
+			let offset = self.piece_offset(mv);
+			self.add(mv, offset, player);
+			let value = -self.negamax(offset, player.opponent(), remaining, -beta, -alpha);
+			self.remove(mv, offset);
+			return value;
+		}
+
+		let column = mv - width;
+		self.pop(column);
+		let value = match self.pop_winner(column, player) {
+			Some(winner) => {
+				self.stats.visit(remaining);
+				self.stats.leaves += 1;
+				let score = OUTCOME_WIN + remaining as i32;
+				if winner == player {
+					score
+				} else {
+					-score
 				}
 			}
-		}
-
-		min_v
+			None => -self.negamax(U_INVALID_INDEX, player.opponent(), remaining, -beta, -alpha),
+		};
+		self.unpop(column, player);
+		value
 	}
 
-	/// Maximum is `Player::Machine`
-	fn max(&mut self, last_cell_offset: usize, remaining: u8, alpha: i32, beta: i32) -> i32 {
-		if self.status(last_cell_offset) {
-			return OUTCOME_HUMAN_WINS;
+	/// Scores the position from the perspective of `player`, who is to move.
+	///
+	/// The last move is checked for a win unless `last_cell_offset` is
+	/// [`U_INVALID_INDEX`], which follows pops as they are settled before.
+	fn negamax(&mut self, last_cell_offset: usize, player: Player, remaining: u8, alpha: i32, beta: i32) -> i32 {
+		self.stats.visit(remaining);
+
+		if last_cell_offset != U_INVALID_INDEX && self.status(last_cell_offset) {
+			self.stats.leaves += 1;
+			return -(OUTCOME_WIN + remaining as i32);
 		}
 
 		if remaining == 0 {
-			self.score_player_mask = SCORE_PLAYER_MASK_MACHINE;
-			return self.score_position(Player::Machine);
+			self.stats.leaves += 1;
+			return self.evaluate(player);
 		}
 
+		let key = match player {
+			Player::Human => self.hash ^ ZOBRIST_SIDE_KEY,
+			_ => self.hash,
+		};
+		let tt_move = match self.tt.probe(key) {
+			Some(entry) => {
+				self.stats.tt_hits += 1;
+				match entry.cutoff(remaining, alpha, beta) {
+					Some(value) => return value,
+					None => entry.mv,
+				}
+			}
+			None => None,
+		};
+
 		// Possible values for max_v are:
 		// -1 - loss
 		//  0 - a tie
@@ -558,77 +850,155 @@ impl ConnectFour {
 		// We're initially setting it to -2 as worse than the worst case:
 		let mut max_v = i32::MIN;
 		let mut local_alpha = alpha;
+		let mut best = None;
 
-		for c in 0..BOARD_WIDTH {
-			if !self.available(c) {
-				continue;
-			}
-
-			let offset = self.piece_offset(c);
-
-			// On the empty field player Machine makes a move and calls Min
-			// That's one branch of the game tree:
-			self.add(c, offset, Player::Machine);
+		let (moves, count) = self.order_moves(player, remaining, tt_move);
 
-			let m = self.min(offset, remaining - 1, local_alpha, beta);
+		// A full PopOut board without pieces to pop is a draw:
+		if count == 0 {
+			self.stats.leaves += 1;
+			return 0;
+		}
 
-			// Setting back the field to empty:
-			self.remove(c, offset);
+		for &mv in &moves[..count] {
+			// The player makes a move and the opponent replies, that's one branch of
+			// the game tree:
+			let m = self.search_move(mv, player, remaining - 1, local_alpha, beta);
 
 			// Fixing the max_v value if needed:
 			if m > max_v {
 				max_v = m;
+				best = Some(mv as u8);
 
 				local_alpha = cmp::max(local_alpha, max_v);
 				if local_alpha >= beta {
+					self.stats.cutoffs += 1;
+					self.record_cutoff(player, remaining, mv);
 					break;
 				}
 			}
 		}
 
+		self.tt.store(key, remaining, max_v, Bound::from_window(max_v, alpha, beta), best);
 		max_v
 	}
 
-	fn max_top(&mut self, remaining: u8) -> usize {
+	/// Clears the move ordering state and the counters for a search of
+	/// `remaining` plies, and allocates the transposition table if it was not
+	/// already.
+	fn prepare_search(&mut self, remaining: u8) {
+		if self.tt.is_empty() {
+			self.tt = TranspositionTable::new(TRANSPOSITION_TABLE_BITS);
+		}
+
+		self.killers = [[U_INVALID_INDEX as u8; 2]; MAX_CELLS + 1];
+		self.history = [[0; 2 * MAX_WIDTH]; 2];
+		self.stats = Stats::new(remaining);
+	}
+
+	fn max_top(&mut self, player: Player, remaining: u8) -> usize {
 		if remaining == 0 {
 			return U_INVALID_INDEX;
 		}
 
-		const DEFAULT_ALPHA: i32 = i32::MIN;
+		if let Some(pool) = self.pool.clone() {
+			return self.max_top_parallel(&pool, player, remaining);
+		}
+
+		const DEFAULT_ALPHA: i32 = -i32::MAX;
 		const DEFAULT_BETA: i32 = i32::MAX;
 
+		self.prepare_search(remaining);
+
 		let mut max_v = i32::MIN;
-		let mut column = U_INVALID_INDEX;
-		for c in 0..BOARD_WIDTH {
-			if !self.available(c) {
-				continue;
+		let mut best = U_INVALID_INDEX;
+		let (moves, count) = self.moves(player);
+		for &mv in &moves[..count] {
+			// The player makes a move and the opponent replies, that's one branch of
+			// the game tree:
+			let points = self.search_move(mv, player, remaining, DEFAULT_ALPHA, DEFAULT_BETA);
+
+			// Always keep the first available move, as the board may fill up before
+			// the search reaches the requested depth, scoring every move as the
+			// worst possible outcome:
+			if points > max_v || best == U_INVALID_INDEX {
+				max_v = points;
+				best = mv;
 			}
+		}
 
-			let offset = self.piece_offset(c);
-
-			// On the empty field player Machine makes a move and calls Min
-			// That's one branch of the game tree:
-			self.add(c, offset, Player::Machine);
-
-			let points = self.min(offset, remaining, DEFAULT_ALPHA, DEFAULT_BETA);
-
-			// Setting back the field to empty:
-			self.remove(c, offset);
+		best
+	}
 
-			if points > max_v {
+	/// Searches every root move in its own task of `pool`, each with its own
+	/// move ordering state and transposition table.
+	///
+	/// Since every root move is searched with a full window, the scores are the
+	/// same as in [`ConnectFour::max_top`], and so is the picked move.
+	fn max_top_parallel(&mut self, pool: &ThreadPool, player: Player, remaining: u8) -> usize {
+		let (moves, count) = self.moves(player);
+		let moves = &moves[..count];
+		let results: Vec<(i32, Stats)> = pool.install(|| {
+			moves
+				.par_iter()
+				.map(|&mv| {
+					let mut board = self.snapshot();
+					board.prepare_search(remaining);
+
+					let points = board.search_move(mv, player, remaining, -i32::MAX, i32::MAX);
+					(points, board.stats)
+				})
+				.collect()
+		});
+
+		self.stats = Stats::new(remaining);
+		for (_, stats) in &results {
+			self.stats.merge(stats);
+		}
+
+		// Keep the first move with the highest score, as the serial search does:
+		let mut best = U_INVALID_INDEX;
+		let mut max_v = i32::MIN;
+		for (&mv, &(points, _)) in moves.iter().zip(&results) {
+			if points > max_v || best == U_INVALID_INDEX {
 				max_v = points;
-				column = c;
+				best = mv;
 			}
 		}
 
+		best
+	}
+
+	/// Returns the best move for `player` using the selected engine, the depth
+	/// is only used by the alpha-beta engine.
+	fn search(&mut self, maximum_depth: u8, player: Player) -> usize {
+		let start = Instant::now();
+		let column = if let Some(mut mcts) = self.mcts.take() {
+			let column = mcts.search(&self.position(player)).unwrap_or(U_INVALID_INDEX);
+			self.stats = mcts.stats();
+			self.mcts = Some(mcts);
+			column
+		} else {
+			// Moves picked without searching, such as the opening one, do not visit
+			// any node:
+			self.stats = Stats::default();
+			self.get_best_move(maximum_depth, player)
+		};
+
+		self.stats.elapsed = start.elapsed();
 		column
 	}
 
-	fn get_best_move(&mut self, maximum_depth: u8) -> usize {
-		// If remaining is 42, then the board is empty.
+	pub(crate) fn get_best_move(&mut self, maximum_depth: u8, player: Player) -> usize {
+		if self.winner != Player::Unset {
+			return U_INVALID_INDEX;
+		}
+
+		// If remaining is 42 in the classic board, then the board is empty.
 		//
 		// Strategically speaking, the middle position in ConnectFour is always the
-		// best, and very often a winner move. The algorithm will always pick this.
+		// best, and very often a winner move. The algorithm will always pick this,
+		// which is the first column in search order for any board size.
 		//
 		// We have this board:
 		// 00 01 02 03 04 05 06
@@ -642,70 +1012,195 @@ impl ConnectFour {
 		//
 		// Hardcoding this is useful, on an empty board, there are 4,531,985,219,092
 		// possibilities.
-		if self.empty == 42 {
-			3
+		if self.empty as usize == self.layout.size.cells() {
+			self.layout.columns()[0]
+		} else if self.pop_out {
+			// Pops free cells up, so a PopOut game may outlast the empty cells:
+			self.max_top(player, cmp::min(MAX_CELLS as u8, maximum_depth))
 		} else {
 			// Process the best move for the AI.
-			self.max_top(cmp::min(self.empty, maximum_depth))
+			self.max_top(player, cmp::min(self.empty, maximum_depth))
 		}
 	}
 }
 
+#[cfg_attr(feature = "tune", allow(dead_code))]
 #[napi]
 impl ConnectFour {
 	#[napi(constructor)]
-	pub fn js_new(values: Option<Uint8Array>) -> Result<Self> {
+	pub fn js_new(values: Option<Uint8Array>, options: Option<ConnectFourOptions>) -> Result<Self> {
+		let options = options.unwrap_or_default();
+		let size = options.size();
+		size.validate().map_err(Error::from_reason)?;
+
+		let cylinder = options.cylinder.unwrap_or(false);
+		if cylinder && size.connect > size.width {
+			return Err(Error::from_reason("connect must not be longer than the width of a cylinder"));
+		}
+
+		let mut cells = [Player::Unset; MAX_CELLS];
 		if let Some(v) = values {
 			let input = v.to_vec();
-			if input.len() != BOARD_CELLS {
-				return Err(Error::from_reason("data must have exactly 42 numbers"));
+			if input.len() != size.cells() {
+				return Err(Error::from_reason(format!("data must have exactly {} numbers", size.cells())));
 			}
 
-			let mut cells: AiCells = [Player::Unset; BOARD_CELLS];
-			for i in 0..BOARD_CELLS {
+			for i in 0..input.len() {
 				cells[i] = Player::try_from(input[i]).map_err(Error::from_reason)?;
 			}
-
-			Ok(ConnectFour::new(cells))
 		} else {
-			Ok(Self {
-				cells: [Player::Unset; BOARD_CELLS],
-				remaining: [BOARD_HEIGHT as u8; BOARD_WIDTH],
-				empty: BOARD_CELLS as u8,
-				score_player_mask: SCORE_PLAYER_MASK_HUMAN,
-			})
+			options.fill_starting_cells(size, &mut cells);
 		}
+
+		let layout = if cylinder { Arc::new(Layout::with_wrap(size, true)) } else { Layout::get(size) };
+		Ok(ConnectFour {
+			pop_out: options.pop_out.unwrap_or(false),
+			..ConnectFour::with_layout(layout, &cells[..size.cells()])
+		})
 	}
 
 	#[napi(getter = board)]
 	pub fn js_get_board(&self) -> Uint8Array {
-		Uint8Array::new(self.cells.map(|v| v as u8).to_vec())
+		Uint8Array::new(self.cells().iter().map(|&v| v as u8).collect())
+	}
+
+	#[napi(getter = width)]
+	pub fn js_get_width(&self) -> u32 {
+		self.layout.size.width as u32
+	}
+
+	#[napi(getter = height)]
+	pub fn js_get_height(&self) -> u32 {
+		self.layout.size.height as u32
+	}
+
+	/// The amount of pieces in a row needed to win.
+	#[napi(getter = connect)]
+	pub fn js_get_connect(&self) -> u32 {
+		self.layout.size.connect as u32
+	}
+
+	/// Whether horizontal and diagonal lines wrap from the last column back to
+	/// the first one.
+	#[napi(getter = cylinder)]
+	pub fn js_get_cylinder(&self) -> bool {
+		self.layout.wrap
+	}
+
+	/// Whether players may pop their pieces out of the bottom row.
+	#[napi(getter = popOut)]
+	pub fn js_get_pop_out(&self) -> bool {
+		self.pop_out
 	}
 
 	#[napi(js_name = "available")]
 	pub fn js_available(&self, column: i32) -> Result<bool> {
-		Ok(self.available(isize_to_usize!(column, BOARD_WIDTH)?))
+		Ok(self.available(isize_to_usize!(column, self.layout.size.width)?))
+	}
+
+	/// Whether `player` can pop its piece at the bottom of `column`.
+	#[napi(js_name = "canPop")]
+	pub fn js_can_pop(&self, column: i32, player: Player) -> Result<bool> {
+		Ok(self.can_pop(isize_to_usize!(column, self.layout.size.width)?, player))
 	}
 
+	/// Whether a move won the game, or the board is full. A full PopOut board
+	/// is not finished, as pieces can still be popped.
 	#[napi(getter = finished)]
 	pub fn js_finished(&self) -> bool {
-		self.empty == 0
+		self.winner != Player::Unset || (self.empty == 0 && !self.pop_out)
+	}
+
+	/// The player who completed a line with the last move, `Player.Unset` while
+	/// nobody did.
+	#[napi(getter = winner)]
+	pub fn js_get_winner(&self) -> Player {
+		self.winner
 	}
 
+	/// Drops a piece of `player` in `column`, or pops its piece out of the
+	/// bottom of it, returning whether `player` won the game.
+	///
+	/// A pop may complete lines of both players, in which case the player who
+	/// popped wins. When it only completes lines of the opponent, the opponent
+	/// wins and `winner` tells so. Fails once the game is won.
 	#[napi(js_name = "add")]
-	pub fn js_add(&mut self, column: i32, player: Player) -> Result<bool> {
-		let c = isize_to_usize!(column, BOARD_WIDTH)?;
-		napi_assert!(self.remaining[c] > 0);
+	pub fn js_add(&mut self, column: i32, player: Player, kind: Option<MoveKind>) -> Result<bool> {
+		let c = isize_to_usize!(column, self.layout.size.width)?;
+		napi_assert!(self.winner == Player::Unset);
+
+		let mv = match kind.unwrap_or(MoveKind::Drop) {
+			MoveKind::Drop => {
+				napi_assert!(self.remaining[c] > 0);
+				c
+			}
+			MoveKind::Pop => {
+				napi_assert!(self.can_pop(c, player));
+				self.layout.size.width + c
+			}
+		};
 
-		let offset = self.piece_offset(c);
-		self.add(c, offset, player);
-		self.empty -= 1;
-		Ok(self.status(offset))
+		let winner = self.play_move(mv, player);
+		if let Some(winner) = winner {
+			self.winner = winner;
+		}
+
+		if let Some(mcts) = self.mcts.as_mut() {
+			mcts.advance(mv);
+		}
+
+		Ok(winner == Some(player))
+	}
+
+	#[napi(getter = engine)]
+	pub fn js_get_engine(&self) -> Engine {
+		if self.mcts.is_some() {
+			Engine::MonteCarlo
+		} else {
+			Engine::AlphaBeta
+		}
+	}
+
+	#[napi(js_name = "setEngine")]
+	pub fn js_set_engine(&mut self, engine: Engine, options: Option<MonteCarloOptions>) -> Result<()> {
+		self.mcts = match engine {
+			Engine::AlphaBeta => None,
+			Engine::MonteCarlo => {
+				Some(Box::new(MonteCarlo::new(options.unwrap_or_default()).map_err(Error::from_reason)?))
+			}
+		};
+
+		Ok(())
+	}
+
+	/// The amount of threads used by the alpha-beta search, defaults to 1. With
+	/// a single thread, the search is deterministic.
+	#[napi(getter = threads)]
+	pub fn js_get_threads(&self) -> u32 {
+		self.threads() as u32
 	}
 
+	#[napi(setter = threads)]
+	pub fn js_set_threads(&mut self, threads: u32) -> Result<()> {
+		napi_assert!(threads > 0);
+
+		self.set_threads(threads as usize).map_err(Error::from_reason)
+	}
+
+	/// The statistics of the last call to `getBestMove`, every counter is zero
+	/// before the first search.
+	#[napi(getter = lastSearchStats)]
+	pub fn js_get_last_search_stats(&self) -> SearchStats {
+		self.stats.into()
+	}
+
+	/// Returns the best move for the machine, or for the player given in the
+	/// options, the column to drop a piece in, or `width + column` to pop the
+	/// piece at the bottom of a column.
 	#[napi(js_name = "getBestMove")]
-	pub fn js_get_best_move(&mut self, depth: Option<i32>) -> Result<i32> {
-		Ok(self.get_best_move(depth.unwrap_or(5).try_into().unwrap()).try_into().unwrap())
+	pub fn js_get_best_move(&mut self, options: Option<BestMoveArgument>) -> Result<i32> {
+		let (depth, player) = BestMoveOptions::resolve(options, 5)?;
+		Ok(self.search(depth, player).try_into().unwrap())
 	}
 }
 
@@ -747,253 +1242,51 @@ mod tests {
 		})*);
 	}
 
-	mod new {
-		use super::*;
-
+	/// Creates a classic board with the pieces of each player in the given
+	/// cells.
+	pub(super) fn board(humans: &[usize], machines: &[usize]) -> ConnectFour {
+		let mut cells = create_cells!();
+		for &offset in humans {
+			cells[offset] = Player::Human;
+		}
+		for &offset in machines {
+			cells[offset] = Player::Machine;
+		}
+
+		ConnectFour::new(cells)
+	}
+
+	mod new {
+		use super::*;
+
 		#[test]
 		fn test_empty() {
 			let cells = create_cells!();
-			let remaining: AiRemaining = [6; 7];
+			let remaining: [u8; 7] = [6; 7];
 			let board = ConnectFour::new(cells);
 
-			assert_eq!(board.cells, cells);
-			assert_eq!(board.remaining, remaining);
+			assert_eq!(board.cells(), cells);
+			assert_eq!(board.remaining[..7], remaining);
 		}
 
 		#[test]
 		fn test_row_filled() {
 			let cells = create_cells!(35, 36, 37, 38, 39, 40, 41);
-			let remaining: AiRemaining = [5; 7];
+			let remaining: [u8; 7] = [5; 7];
 			let board = ConnectFour::new(cells);
 
-			assert_eq!(board.cells, cells);
-			assert_eq!(board.remaining, remaining);
+			assert_eq!(board.cells(), cells);
+			assert_eq!(board.remaining[..7], remaining);
 		}
 
 		#[test]
 		fn test_column_filled() {
 			let cells = create_cells!(0, 7, 14, 21, 28, 35);
-			let remaining: AiRemaining = [0, 6, 6, 6, 6, 6, 6];
+			let remaining: [u8; 7] = [0, 6, 6, 6, 6, 6, 6];
 			let board = ConnectFour::new(cells);
 
-			assert_eq!(board.cells, cells);
-			assert_eq!(board.remaining, remaining);
-		}
-	}
-
-	mod check_4 {
-		use super::super::*;
-
-		macro_rules! test_panic {
-			($($name:ident: [$cells:expr, $last_column:expr, $offsets:expr],)*) => ($(
-				#[test]
-				#[should_panic]
-				fn $name() {
-					let board = ConnectFour::new($cells);
-
-					unsafe {
-						check_offsets!(board.cells, $last_column, $offsets.0, $offsets.1, $offsets.2, $offsets.3);
-					}
-				}
-			)*);
-		}
-
-		test_panic! {
-			test_out_of_range_under: [create_cells!(0, 2, 3, 4), 0, (-1, 0, 1, 2)],
-			test_out_of_range_over: [create_cells!(38, 39, 40, 41), 39, (0, 1, 2, 3)],
-			test_invalid_offset: [create_cells!(0, 2), 1, (-1, 0, 1, 2)],
-		}
-
-		#[test]
-		fn test_fails() {
-			let board = ConnectFour::new(create_cells!(0, 2, 3, 4));
-
-			unsafe {
-				assert!(!check_offsets!(board.cells, 0, 0, 1, 2, 3));
-			}
-		}
-
-		macro_rules! test_true {
-			($($name:ident: [$last_column:expr, $offsets:expr],)*) => ($(
-				#[test]
-				fn $name() {
-					let board = ConnectFour::new(create_cells!(0, 1, 2, 3, 4));
-
-					unsafe {
-						assert!(check_offsets!(board.cells, $last_column, $offsets.0, $offsets.1, $offsets.2, $offsets.3));
-					}
-				}
-			)*);
-		}
-
-		test_true! {
-			test_match_offset_0: [0, (0, 1, 2, 3)],
-			test_match_offset_1: [1, (-1, 0, 1, 2)],
-			test_match_offset_2: [2, (-2, -1, 0, 1)],
-			test_match_offset_3: [3, (-3, -2, -1, 0)],
-		}
-	}
-
-	mod check_5 {
-		use super::super::*;
-
-		macro_rules! test_panic {
-			($($name:ident: [$cells:expr, $last_column:expr, $offsets:expr],)*) => ($(
-				#[test]
-				#[should_panic]
-				fn $name() {
-					let board = ConnectFour::new($cells);
-
-					unsafe {
-						check_offsets!(board.cells, $last_column, $offsets.0, $offsets.1, $offsets.2, $offsets.3, $offsets.4);
-					}
-				}
-			)*);
-		}
-
-		test_panic! {
-			test_out_of_range_under: [create_cells!(0, 2, 3, 4), 0, (-1, 0, 1, 2, 3)],
-			test_out_of_range_over: [create_cells!(38, 39, 40, 41), 39, (-1, 0, 1, 2, 3)],
-			test_invalid_offset: [create_cells!(0, 2, 3, 4, 5), 1, (-1, 0, 1, 2, 3)],
-		}
-
-		#[test]
-		fn test_fails() {
-			let board = ConnectFour::new(create_cells!(0, 2, 3, 4));
-
-			unsafe {
-				assert!(!check_offsets!(board.cells, 2, -1, 0, 1, 2, 3));
-			}
-		}
-
-		macro_rules! test_true {
-			($($name:ident: [$last_column:expr, $offsets:expr],)*) => {
-			$(
-				#[test]
-				fn $name() {
-					let board = ConnectFour::new(create_cells!(0, 1, 2, 3));
-
-					unsafe {
-						assert!(check_offsets!(board.cells, $last_column, $offsets.0, $offsets.1, $offsets.2, $offsets.3, $offsets.4));
-					}
-				}
-			)*
-			}
-		}
-
-		test_true! {
-			// test_match_offset_0 is omitted because horizontal offset would go beyond [0, 3]
-			test_match_offset_1: [1, (-1, 0, 1, 2, 3)],
-			test_match_offset_2: [2, (-2, -1, 0, 1, 2)],
-			test_match_offset_3: [3, (-3, -2, -1, 0, 1)],
-		}
-	}
-
-	mod check_6 {
-		use super::super::*;
-
-		macro_rules! test_panic {
-			($($name:ident: [$cells:expr, $last_column:expr, $offsets:expr],)*) => ($(
-				#[test]
-				#[should_panic]
-				fn $name() {
-					let board = ConnectFour::new($cells);
-
-					unsafe {
-						check_offsets!(board.cells, $last_column, $offsets.0, $offsets.1, $offsets.2, $offsets.3, $offsets.4, $offsets.5);
-					}
-				}
-			)*);
-		}
-
-		test_panic! {
-			test_out_of_range_under: [create_cells!(0, 2, 3, 4), 0, (-2, -1, 0, 1, 2, 3)],
-			test_out_of_range_over: [create_cells!(38, 39, 40, 41), 39, (-2, -1, 0, 1, 2, 3)],
-			test_invalid_offset: [create_cells!(0, 2, 3, 4, 5), 1, (-2, -1, 0, 1, 2, 3)],
-		}
-
-		#[test]
-		fn test_fails() {
-			let board = ConnectFour::new(create_cells!(0, 2, 3, 4));
-
-			unsafe {
-				assert!(!check_offsets!(board.cells, 2, -2, -1, 0, 1, 2, 3));
-			}
-		}
-
-		macro_rules! test_true {
-			($($name:ident: [$last_column:expr, $offsets:expr],)*) => {
-			$(
-				#[test]
-				fn $name() {
-					let board = ConnectFour::new(create_cells!(0, 1, 2, 3, 4));
-
-					unsafe {
-						assert!(check_offsets!(board.cells, $last_column, $offsets.0, $offsets.1, $offsets.2, $offsets.3, $offsets.4, $offsets.5));
-					}
-				}
-			)*
-			}
-		}
-
-		test_true! {
-			// test_match_offset_0-1 is omitted because horizontal offset would go beyond [0, 3]
-			test_match_offset_2: [2, (-2, -1, 0, 1, 2, 3)],
-			test_match_offset_3: [3, (-3, -2, -1, 0, 1, 2)],
-			test_match_offset_4: [4, (-3, -2, -1, 0, 1, 2)],
-		}
-	}
-
-	mod check_7 {
-		use super::super::*;
-
-		macro_rules! test_panic {
-			($($name:ident: [$cells:expr, $cell:expr],)*) => ($(
-				#[test]
-				#[should_panic]
-				fn $name() {
-					let board = ConnectFour::new($cells);
-
-					unsafe {
-						check_offsets!(board.cells, $cell, -3, -2, -1, 0, 1, 2, 3);
-					}
-				}
-			)*);
-		}
-
-		test_panic! {
-			test_out_of_range_under: [create_cells!(0, 2, 3, 4), 0],
-			test_out_of_range_over: [create_cells!(38, 39, 40, 41), 39],
-			test_invalid_offset: [create_cells!(0, 2, 3, 4, 5), 1],
-		}
-
-		#[test]
-		fn test_fails() {
-			let board = ConnectFour::new(create_cells!(0, 2, 3, 4));
-
-			unsafe {
-				assert!(!check_offsets!(board.cells, 3, -3, -2, -1, 0, 1, 2, 3));
-			}
-		}
-
-		macro_rules! test_true {
-			($($name:ident: [$last_column:expr],)*) => {
-			$(
-				#[test]
-				fn $name() {
-					let board = ConnectFour::new(create_cells!(0, 1, 2, 3, 4));
-
-					unsafe {
-						assert!(check_offsets!(board.cells, $last_column, -3, -2, -1, 0, 1, 2, 3));
-					}
-				}
-			)*
-			}
-		}
-
-		test_true! {
-			// The only horizontal row with 3 cells in each side is the middle, 3
-			test_match_offset_3: [3],
+			assert_eq!(board.cells(), cells);
+			assert_eq!(board.remaining[..7], remaining);
 		}
 	}
 
@@ -1271,21 +1564,21 @@ mod tests {
 			// _ _ _ _ _ _ _ (21..28)
 			// _ _ _ _ _ _ _ (28..35)
 			// _ H H H _ _ _ (35..42)
-			test_human_one: [create_cells!(36, 37, 38), 3],
+			test_human_one: [create_cells!(36, 37, 38), DEFAULT_WEIGHTS.center],
 			// _ _ _ _ _ _ _ (0..7)
 			// _ _ _ _ _ _ _ (7..14)
 			// _ _ _ _ _ _ _ (14..21)
 			// _ _ _ _ _ _ _ (21..28)
 			// _ _ _ H _ _ _ (28..35)
 			// _ H H H _ _ _ (35..42)
-			test_human_two: [create_cells!(31, 36, 37, 38), 6],
+			test_human_two: [create_cells!(31, 36, 37, 38), 2 * DEFAULT_WEIGHTS.center],
 			// _ _ _ _ _ _ _ (0..7)
 			// _ _ _ _ _ _ _ (7..14)
 			// _ _ _ _ _ _ _ (14..21)
 			// _ _ _ H _ _ _ (21..28)
 			// _ _ _ H _ _ _ (28..35)
 			// _ H H H _ _ _ (35..42)
-			test_human_three: [create_cells!(24, 31, 36, 37, 38), 9],
+			test_human_three: [create_cells!(24, 31, 36, 37, 38), 3 * DEFAULT_WEIGHTS.center],
 		}
 	}
 
@@ -1315,14 +1608,14 @@ mod tests {
 			// _ _ _ _ _ _ _ (21..28)
 			// _ _ _ _ _ _ _ (28..35)
 			// _ _ H H _ _ _ (35..42)
-			test_human_two: [create_cells!(37, 38), 30],
+			test_human_two: [create_cells!(37, 38), 3 * DEFAULT_WEIGHTS.two],
 			// _ _ _ _ _ _ _ (0..7)
 			// _ _ _ _ _ _ _ (7..14)
 			// _ _ _ _ _ _ _ (14..21)
 			// _ _ _ _ _ _ _ (21..28)
 			// _ _ _ _ _ _ _ (28..35)
 			// _ H H H _ _ _ (35..42)
-			test_human_three: [create_cells!(36, 37, 38), 210],
+			test_human_three: [create_cells!(36, 37, 38), 2 * DEFAULT_WEIGHTS.three + DEFAULT_WEIGHTS.two],
 		}
 	}
 
@@ -1352,87 +1645,239 @@ mod tests {
 			// _ _ _ _ _ _ _ (21..28)
 			// _ _ H _ _ _ _ (28..35)
 			// _ _ H _ _ _ _ (35..42)
-			test_human_two: [create_cells!(30, 37), 10],
+			test_human_two: [create_cells!(30, 37), DEFAULT_WEIGHTS.two],
 			// _ _ _ _ _ _ _ (0..7)
 			// _ _ _ _ _ _ _ (7..14)
 			// _ _ _ _ _ _ _ (14..21)
 			// _ _ H _ _ _ _ (21..28)
 			// _ _ H _ _ _ _ (28..35)
 			// _ _ H _ _ _ _ (35..42)
-			test_human_three: [create_cells!(23, 30, 37), 110],
+			test_human_three: [create_cells!(23, 30, 37), DEFAULT_WEIGHTS.three + DEFAULT_WEIGHTS.two],
 		}
 	}
 
-	mod min {
+	mod negamax {
 		use super::super::*;
 
 		macro_rules! test_panic {
-			($($name:ident: [$cells:expr, $offset:expr, $remaining:expr, $alpha:expr, $beta:expr],)*) => ($(
+			($($name:ident: [$cells:expr, $offset:expr, $player:expr, $remaining:expr, $alpha:expr, $beta:expr],)*) => ($(
 				#[test]
 				#[should_panic]
 				fn $name() {
 					let mut board = ConnectFour::new($cells);
-					board.min($offset, $remaining, $alpha, $beta);
+					board.negamax($offset, $player, $remaining, $alpha, $beta);
 				}
 			)*);
 		}
 
 		test_panic! {
-			test_out_of_range_over: [create_cells!(), 42, 42, 0, 0],
-			test_invalid_offset: [create_cells!(0), 1, 1, 0, 0],
+			test_out_of_range_over: [create_cells!(), 42, Player::Human, 42, 0, 0],
+			test_invalid_offset: [create_cells!(0), 1, Player::Human, 1, 0, 0],
+			test_machine_out_of_range_over: [create_cells!(), 42, Player::Machine, 42, 0, 0],
+			test_machine_invalid_offset: [create_cells!(0), 1, Player::Machine, 1, 0, 0],
 		}
 
 		macro_rules! gen_test {
-			($($name:ident: [$cells:expr, $outcome:expr, $offset:expr, $remaining:expr, $alpha:expr, $beta:expr],)*) => ($(
+			($($name:ident: [$cells:expr, $outcome:expr, $offset:expr, $player:expr, $remaining:expr, $alpha:expr, $beta:expr],)*) => ($(
 				#[test]
 				fn $name() {
 					let mut board = ConnectFour::new($cells);
-					let min = board.min($offset, $remaining, $alpha, $beta);
+					let value = board.negamax($offset, $player, $remaining, $alpha, $beta);
 
-					assert_eq!(min, $outcome);
+					assert_eq!(value, $outcome);
 				}
 			)*);
 		}
 
 		gen_test! {
-			test_machine_wins: [create_cells!(0, 1, 2, 3), OUTCOME_MACHINE_WINS, 0, 42, 0, 0],
-			test_draw: [create_cells!(0), 0, 0, 0, 0, 0],
+			// The last move completed a line of the opponent, whoever it was:
+			test_human_loses: [create_cells!(0, 1, 2, 3), -(OUTCOME_WIN + 42), 0, Player::Human, 42, 0, 0],
+			test_machine_loses: [create_cells!(0, 1, 2, 3), -(OUTCOME_WIN + 42), 0, Player::Machine, 42, 0, 0],
+			test_draw: [create_cells!(0), 0, 0, Player::Human, 0, 0, 0],
 		}
 	}
 
-	mod max {
+	mod hash {
 		use super::super::*;
 
-		macro_rules! test_panic {
-			($($name:ident: [$cells:expr, $offset:expr, $remaining:expr, $alpha:expr, $beta:expr],)*) => ($(
+		#[test]
+		fn test_matches_incremental_updates() {
+			let mut board = ConnectFour::new(create_cells!());
+			board.add(3, 38, Player::Human);
+			board.add(3, 31, Player::Machine);
+
+			let mut cells = create_cells!(38);
+			cells[31] = Player::Machine;
+			assert_eq!(board.hash, ConnectFour::new(cells).hash);
+
+			board.remove(3, 31);
+			board.remove(3, 38);
+			assert_eq!(board.hash, 0);
+		}
+
+		#[test]
+		fn test_depends_on_player() {
+			let mut cells = create_cells!();
+			cells[38] = Player::Machine;
+
+			assert_ne!(ConnectFour::new(create_cells!(38)).hash, ConnectFour::new(cells).hash);
+		}
+	}
+
+	mod order_moves {
+		use super::super::*;
+
+		macro_rules! gen_test {
+			($($name:ident: [$cells:expr, $tt_move:expr, $outcome:expr],)*) => ($(
 				#[test]
-				#[should_panic]
 				fn $name() {
 					let mut board = ConnectFour::new($cells);
-					board.max($offset, $remaining, $alpha, $beta);
+					let (columns, count) = board.order_moves(Player::Machine, 1, $tt_move);
+
+					assert_eq!(&columns[..count], &$outcome);
 				}
 			)*);
 		}
 
-		test_panic! {
-			test_out_of_range_over: [create_cells!(), 42, 42, 0, 0],
-			test_invalid_offset: [create_cells!(0), 1, 1, 0, 0],
+		gen_test! {
+			test_center_first: [create_cells!(), None, [3, 2, 4, 1, 5, 0, 6]],
+			test_skips_full_columns: [create_cells!(3, 10, 17, 24, 31, 38), None, [2, 4, 1, 5, 0, 6]],
+			test_tt_move_first: [create_cells!(), Some(6), [6, 3, 2, 4, 1, 5, 0]],
+			// _ _ _ _ _ _ _ (0..7)
+			// _ _ _ _ _ _ _ (7..14)
+			// _ _ _ _ _ _ _ (14..21)
+			// _ _ _ _ _ _ _ (21..28)
+			// _ _ _ _ _ _ _ (28..35)
+			// H H H v _ _ _ (35..42)
+			test_block_first: [create_cells!(35, 36, 37), None, [3, 2, 4, 1, 5, 0, 6]],
+			// _ _ _ _ _ _ _ (0..7)
+			// _ _ _ _ _ _ _ (7..14)
+			// _ _ _ _ _ _ _ (14..21)
+			// _ _ _ _ _ _ _ (21..28)
+			// _ _ _ _ _ _ _ (28..35)
+			// _ _ _ v H H H (35..42)
+			test_tt_move_before_block: [create_cells!(39, 40, 41), Some(5), [5, 3, 2, 4, 1, 0, 6]],
+		}
+
+		#[test]
+		fn test_win_before_block() {
+			// _ _ _ _ _ _ _ (0..7)
+			// _ _ _ _ _ _ _ (7..14)
+			// _ _ _ _ _ _ v (14..21)
+			// _ _ _ _ _ _ M (21..28)
+			// _ _ _ _ _ _ M (28..35)
+			// H H H v _ _ M (35..42)
+			let mut cells = create_cells!(35, 36, 37);
+			for offset in [27, 34, 41] {
+				cells[offset] = Player::Machine;
+			}
+
+			let mut board = ConnectFour::new(cells);
+			let (columns, count) = board.order_moves(Player::Machine, 1, None);
+			assert_eq!(&columns[..count], &[6, 3, 2, 4, 1, 5, 0]);
+
+			let (columns, count) = board.order_moves(Player::Human, 1, None);
+			assert_eq!(&columns[..count], &[3, 6, 2, 4, 1, 5, 0]);
+		}
+
+		#[test]
+		fn test_killers_and_history() {
+			let mut board = ConnectFour::new(create_cells!());
+			board.prepare_search(2);
+			board.record_cutoff(Player::Machine, 2, 0);
+			board.record_cutoff(Player::Machine, 2, 6);
+			board.record_cutoff(Player::Machine, 1, 5);
+
+			let (columns, count) = board.order_moves(Player::Machine, 2, None);
+			assert_eq!(&columns[..count], &[6, 0, 5, 3, 2, 4, 1]);
+
+			// Killers are kept per depth, and history per player:
+			let (columns, count) = board.order_moves(Player::Human, 3, None);
+			assert_eq!(&columns[..count], &[3, 2, 4, 1, 5, 0, 6]);
+		}
+	}
+
+	mod pruning {
+		use super::super::*;
+
+		/// A reference negamax search in column order, without any move
+		/// ordering nor transposition table, which counts its nodes.
+		fn reference(
+			board: &mut ConnectFour,
+			last_cell_offset: usize,
+			remaining: u8,
+			mut alpha: i32,
+			beta: i32,
+			player: Player,
+			nodes: &mut u64,
+		) -> i32 {
+			*nodes += 1;
+
+			if board.status(last_cell_offset) {
+				return -(OUTCOME_WIN + remaining as i32);
+			}
+
+			if remaining == 0 {
+				return board.evaluate(player);
+			}
+
+			let mut best = i32::MIN;
+			for c in 0..BOARD_WIDTH {
+				if !board.available(c) {
+					continue;
+				}
+
+				let offset = board.piece_offset(c);
+				board.add(c, offset, player);
+				let m = -reference(board, offset, remaining - 1, -beta, -alpha, player.opponent(), nodes);
+				board.remove(c, offset);
+
+				if m > best {
+					best = m;
+					alpha = cmp::max(alpha, best);
+				}
+
+				if alpha >= beta {
+					break;
+				}
+			}
+
+			best
 		}
 
 		macro_rules! gen_test {
-			($($name:ident: [$cells:expr, $outcome:expr, $offset:expr, $remaining:expr, $alpha:expr, $beta:expr],)*) => ($(
+			($($name:ident: [$cells:expr, $depth:expr],)*) => ($(
 				#[test]
 				fn $name() {
 					let mut board = ConnectFour::new($cells);
-					let max = board.max($offset, $remaining, $alpha, $beta);
+					board.prepare_search($depth);
 
-					assert_eq!(max, $outcome);
+					let mut nodes = 0;
+					let mut ordered_nodes = 0;
+					for c in 0..BOARD_WIDTH {
+						let offset = board.piece_offset(c);
+						board.add(c, offset, Player::Machine);
+
+						let mut copy = board.snapshot();
+						let expected = reference(&mut copy, offset, $depth, -i32::MAX, i32::MAX, Player::Human, &mut nodes);
+
+						board.stats.nodes = 0;
+						assert_eq!(board.negamax(offset, Player::Human, $depth, -i32::MAX, i32::MAX), expected);
+						ordered_nodes += board.stats.nodes;
+
+						board.remove(c, offset);
+					}
+
+					assert!(ordered_nodes * 2 < nodes, "{ordered_nodes} nodes with ordering, {nodes} without");
 				}
 			)*);
 		}
 
 		gen_test! {
-			test_human_wins: [create_cells!(0, 1, 2, 3), OUTCOME_HUMAN_WINS, 0, 42, 0, 0],
+			test_empty: [create_cells!(), 5],
+			test_horizontal_threat: [create_cells!(35, 36, 37), 5],
+			test_center_column: [create_cells!(24, 31, 38), 5],
+			test_diagonal_threat: [create_cells!(18, 24, 30), 5],
 		}
 	}
 
@@ -1444,7 +1889,7 @@ mod tests {
 				#[test]
 				fn $name() {
 					let mut board = ConnectFour::new($cells);
-					let max = board.max_top(5);
+					let max = board.max_top(Player::Machine, 5);
 
 					assert_eq!(max, $outcome);
 				}
@@ -1481,5 +1926,559 @@ mod tests {
 			// _ v _ _ _ _ _ (35..42)
 			test_stop_bl_tr_winning_move: [create_cells!(18, 24, 30), 1],
 		}
+
+		#[test]
+		fn test_last_available_column() {
+			// _ H M M H H M (0..7)
+			// _ M H H M M H (7..14)
+			// H H M M H H M (14..21)
+			// M M H H M M H (21..28)
+			// H H M M H H M (28..35)
+			// M M H H M M H (35..42)
+			let mut cells = create_cells!();
+			for (index, cell) in cells.iter_mut().enumerate() {
+				let (row, column) = (index / BOARD_WIDTH, index % BOARD_WIDTH);
+				*cell = if (column / 2 + row) % 2 == 0 { Player::Human } else { Player::Machine };
+			}
+			cells[0] = Player::Unset;
+			cells[7] = Player::Unset;
+
+			let mut board = ConnectFour::new(cells);
+			assert_eq!(board.max_top(Player::Machine, 5), 0);
+		}
+
+		#[test]
+		fn test_human_wins() {
+			// _ _ _ _ _ _ _ (28..35)
+			// H H H v M M _ (35..42)
+			let mut cells = create_cells!(35, 36, 37);
+			(cells[39], cells[40]) = (Player::Machine, Player::Machine);
+
+			let mut board = ConnectFour::new(cells);
+			assert_eq!(board.max_top(Player::Human, 5), 3);
+		}
+
+		#[test]
+		fn test_human_stops_winning_move() {
+			// _ _ _ _ _ _ _ (28..35)
+			// M M M v H H _ (35..42)
+			let mut cells = create_cells!(39, 40);
+			(cells[35], cells[36], cells[37]) = (Player::Machine, Player::Machine, Player::Machine);
+
+			let mut board = ConnectFour::new(cells);
+			for depth in [4, 5] {
+				assert_eq!(board.max_top(Player::Human, depth), 3);
+			}
+		}
+	}
+
+	mod max_top_parallel {
+		use super::super::*;
+
+		macro_rules! gen_test {
+			($($name:ident: [$cells:expr, $outcome:expr],)*) => ($(
+				#[test]
+				fn $name() {
+					let mut board = ConnectFour::new($cells);
+					let serial = board.max_top(Player::Machine, 5);
+
+					board.set_threads(4).unwrap();
+					assert_eq!(board.threads(), 4);
+					assert_eq!(board.max_top(Player::Machine, 5), serial);
+					assert_eq!(serial, $outcome);
+				}
+			)*);
+		}
+
+		gen_test! {
+			test_empty: [create_cells!(), 3],
+			test_stop_horizontal_winning_move: [create_cells!(35, 36, 37), 3],
+			test_stop_vertical_winning_move: [create_cells!(21, 28, 35), 0],
+			test_stop_tl_br_winning_move: [create_cells!(15, 23, 31), 4],
+			test_stop_bl_tr_winning_move: [create_cells!(18, 24, 30), 1],
+		}
+
+		#[test]
+		fn test_single_thread_is_serial() {
+			let mut board = ConnectFour::new(create_cells!());
+			board.set_threads(4).unwrap();
+			board.set_threads(1).unwrap();
+
+			assert!(board.pool.is_none());
+			assert_eq!(board.threads(), 1);
+		}
+	}
+
+	mod monte_carlo {
+		use super::super::*;
+		use crate::search::mcts::Playout;
+
+		macro_rules! gen_test {
+			($($name:ident: [$cells:expr, $playout:expr, $outcome:expr],)*) => ($(
+				#[test]
+				fn $name() {
+					let board = ConnectFour::new($cells);
+					let mut mcts = MonteCarlo::new(MonteCarloOptions {
+						iterations: Some(5_000),
+						playout: Some($playout),
+						seed: Some(42),
+						..Default::default()
+					})
+					.unwrap();
+
+					assert_eq!(mcts.search(&board.position(Player::Machine)), Some($outcome));
+				}
+			)*);
+		}
+
+		gen_test! {
+			// _ _ _ _ _ _ _ (0..7)
+			// _ _ _ _ _ _ _ (7..14)
+			// _ _ _ _ _ _ _ (14..21)
+			// _ _ _ _ _ _ _ (21..28)
+			// _ _ _ _ _ _ _ (28..35)
+			// H H H v _ _ _ (35..42)
+			test_random_stop_horizontal_winning_move: [create_cells!(35, 36, 37), Playout::Random, 3],
+			test_heuristic_stop_horizontal_winning_move: [create_cells!(35, 36, 37), Playout::Heuristic, 3],
+			// _ _ _ _ _ _ _ (0..7)
+			// _ _ _ _ _ _ _ (7..14)
+			// v _ _ _ _ _ _ (14..21)
+			// H _ _ _ _ _ _ (21..28)
+			// H _ _ _ _ _ _ (28..35)
+			// H _ _ _ _ _ _ (35..42)
+			test_random_stop_vertical_winning_move: [create_cells!(21, 28, 35), Playout::Random, 0],
+			test_heuristic_stop_vertical_winning_move: [create_cells!(21, 28, 35), Playout::Heuristic, 0],
+		}
+
+		#[test]
+		fn test_finished() {
+			let mut board = ConnectFour::new(create_cells!());
+			board
+				.js_set_engine(Engine::MonteCarlo, Some(MonteCarloOptions { seed: Some(42), ..Default::default() }))
+				.unwrap();
+			board.empty = 0;
+
+			assert_eq!(board.search(5, Player::Machine), U_INVALID_INDEX);
+		}
+
+		#[test]
+		fn test_won() {
+			let mut board = ConnectFour::new(create_cells!(35, 36, 37, 38));
+			board.js_set_engine(Engine::MonteCarlo, None).unwrap();
+			board.winner = Player::Human;
+
+			assert_eq!(board.position(Player::Machine).outcome, Some(Outcome::Win(Player::Human)));
+			assert_eq!(board.search(5, Player::Machine), U_INVALID_INDEX);
+
+			board.js_set_engine(Engine::AlphaBeta, None).unwrap();
+			assert_eq!(board.search(5, Player::Machine), U_INVALID_INDEX);
+		}
+
+		#[test]
+		fn test_set_engine() {
+			let mut board = ConnectFour::new(create_cells!());
+			assert_eq!(board.js_get_engine(), Engine::AlphaBeta);
+
+			board.js_set_engine(Engine::MonteCarlo, None).unwrap();
+			assert_eq!(board.js_get_engine(), Engine::MonteCarlo);
+
+			board.js_set_engine(Engine::AlphaBeta, None).unwrap();
+			assert_eq!(board.js_get_engine(), Engine::AlphaBeta);
+		}
+	}
+
+	mod stats {
+		use super::super::*;
+
+		#[test]
+		fn test_opening_move() {
+			let mut board = ConnectFour::new(create_cells!());
+
+			assert_eq!(board.search(5, Player::Machine), 3);
+			assert_eq!(board.stats.nodes, 0);
+		}
+
+		#[test]
+		fn test_alpha_beta() {
+			let mut board = ConnectFour::new(create_cells!(35, 36, 37));
+			assert_eq!(board.search(4, Player::Machine), 3);
+
+			let stats = board.stats;
+			assert!(stats.leaves > 0 && stats.leaves < stats.nodes);
+			assert!(stats.cutoffs > 0);
+			assert!(stats.tt_hits > 0);
+			// The root move and the 4 plies searched after it:
+			assert_eq!(stats.max_depth, 5);
+		}
+
+		#[test]
+		fn test_parallel() {
+			let mut board = ConnectFour::new(create_cells!(35, 36, 37));
+			board.search(4, Player::Machine);
+			let serial = board.stats;
+
+			board.set_threads(2).unwrap();
+			board.search(4, Player::Machine);
+			assert!(board.stats.nodes > 0);
+			assert_eq!(board.stats.max_depth, serial.max_depth);
+		}
+
+		#[test]
+		fn test_monte_carlo() {
+			let mut board = ConnectFour::new(create_cells!(35, 36, 37));
+			board
+				.js_set_engine(
+					Engine::MonteCarlo,
+					Some(MonteCarloOptions { iterations: Some(1_000), seed: Some(42), ..Default::default() }),
+				)
+				.unwrap();
+			board.search(5, Player::Machine);
+
+			assert_eq!(board.stats.leaves, 1_000);
+			assert_eq!(board.stats.cutoffs, 0);
+			assert!(board.stats.max_depth > 0);
+		}
+	}
+
+	mod sizes {
+		use super::super::*;
+
+		/// Creates a board of the given size with pieces of the human in
+		/// `cells`.
+		fn board(width: usize, height: usize, connect: usize, cells: &[usize]) -> ConnectFour {
+			let size = Size { width, height, connect };
+			let mut values = vec![Player::Unset; size.cells()];
+			for &cell in cells {
+				values[cell] = Player::Human;
+			}
+
+			ConnectFour::with_size(size, &values)
+		}
+
+		#[test]
+		fn test_options() {
+			assert_eq!(ConnectFourOptions::default().size(), Size::CLASSIC);
+
+			let options = ConnectFourOptions { width: Some(10), height: Some(8), ..Default::default() };
+			assert_eq!(options.size(), Size { width: 10, height: 8, connect: 4 });
+		}
+
+		#[test]
+		fn test_variants() {
+			let options = |variant| ConnectFourOptions { variant: Some(variant), ..Default::default() };
+			assert_eq!(options(ConnectFourVariant::Classic).size(), Size::CLASSIC);
+			assert_eq!(options(ConnectFourVariant::ConnectThree).size(), Size { width: 6, height: 5, connect: 3 });
+			assert_eq!(options(ConnectFourVariant::FiveInARow).size(), Size { width: 9, height: 6, connect: 5 });
+
+			// Explicit sizes take precedence over the ones of the variant:
+			let options = ConnectFourOptions { connect: Some(4), ..options(ConnectFourVariant::FiveInARow) };
+			assert_eq!(options.size(), Size { width: 9, height: 6, connect: 4 });
+		}
+
+		#[test]
+		fn test_five_in_a_row_cells() {
+			let options = ConnectFourOptions { variant: Some(ConnectFourVariant::FiveInARow), ..Default::default() };
+			let size = options.size();
+			let mut cells = vec![Player::Unset; size.cells()];
+			options.fill_starting_cells(size, &mut cells);
+
+			let (h, m) = (Player::Human, Player::Machine);
+			for row in 0..size.height {
+				let expected = if row % 2 == 0 { (m, h) } else { (h, m) };
+				assert_eq!((cells[row * 9], cells[row * 9 + 8]), expected);
+				assert!(cells[row * 9 + 1..row * 9 + 8].iter().all(|&cell| cell == Player::Unset));
+			}
+
+			let board = ConnectFour::with_size(size, &cells);
+			assert!(!board.available(0) && !board.available(8));
+			assert_eq!(board.empty, 42);
+
+			let mut cells = vec![Player::Unset; 30];
+			ConnectFourOptions { variant: Some(ConnectFourVariant::ConnectThree), ..Default::default() }
+				.fill_starting_cells(Size::CONNECT_THREE, &mut cells);
+			assert!(cells.iter().all(|&cell| cell == Player::Unset));
+		}
+
+		#[test]
+		fn test_new() {
+			let board = board(6, 5, 4, &[24, 25]);
+
+			assert_eq!(board.cells().len(), 30);
+			assert_eq!(board.remaining[..6], [4, 4, 5, 5, 5, 5]);
+			assert_eq!(board.empty, 28);
+		}
+
+		macro_rules! gen_status_test {
+			($($name:ident: [$width:expr, $height:expr, $connect:expr, $cells:expr, $last:expr, $outcome:expr],)*) => ($(
+				#[test]
+				fn $name() {
+					assert_eq!(board($width, $height, $connect, &$cells).status($last), $outcome);
+				}
+			)*);
+		}
+
+		gen_status_test! {
+			test_connect_3: [6, 5, 3, [24, 25, 26], 25, true],
+			test_connect_5_four: [10, 8, 5, [70, 71, 72, 73], 73, false],
+			test_connect_5_horizontal: [10, 8, 5, [70, 71, 72, 73, 74], 72, true],
+			test_connect_5_vertical: [10, 8, 5, [39, 49, 59, 69, 79], 39, true],
+			test_connect_5_diagonal: [10, 8, 5, [30, 41, 52, 63, 74], 30, true],
+			// The end of a row does not continue in the next one:
+			test_no_wrap_around: [8, 7, 4, [37, 38, 39, 40], 39, false],
+		}
+
+		macro_rules! gen_best_move_test {
+			($($name:ident: [$width:expr, $height:expr, $connect:expr, $cells:expr, $outcome:expr],)*) => ($(
+				#[test]
+				fn $name() {
+					assert_eq!(board($width, $height, $connect, &$cells).get_best_move(4, Player::Machine), $outcome);
+				}
+			)*);
+		}
+
+		gen_best_move_test! {
+			test_empty_even_width: [6, 5, 4, [], 2],
+			test_empty_10x8: [10, 8, 4, [], 4],
+			test_block_6x5: [6, 5, 4, [24, 25, 26], 3],
+			test_block_9x7: [9, 7, 4, [60, 61, 62], 5],
+			test_block_10x8_connect_5: [10, 8, 5, [76, 77, 78, 79], 5],
+			test_block_connect_3: [6, 5, 3, [28, 29], 3],
+		}
+
+		#[test]
+		fn test_five_in_a_row_block_with_outside_column() {
+			// M _ _ _ _ _ _ _ H (36..45)
+			// H H H H _ _ _ _ M (45..54)
+			let options = ConnectFourOptions { variant: Some(ConnectFourVariant::FiveInARow), ..Default::default() };
+			let size = options.size();
+			let mut cells = vec![Player::Unset; size.cells()];
+			options.fill_starting_cells(size, &mut cells);
+			for offset in [46, 47, 48] {
+				cells[offset] = Player::Human;
+			}
+
+			assert_eq!(ConnectFour::with_size(size, &cells).get_best_move(4, Player::Machine), 4);
+		}
+	}
+
+	mod pop_out {
+		use super::super::*;
+
+		/// Creates a classic PopOut board with pieces of the human in `humans`
+		/// and of the machine in `machines`.
+		fn board(humans: &[usize], machines: &[usize]) -> ConnectFour {
+			let mut cells = create_cells!();
+			for &offset in humans {
+				cells[offset] = Player::Human;
+			}
+			for &offset in machines {
+				cells[offset] = Player::Machine;
+			}
+
+			ConnectFour { pop_out: true, ..ConnectFour::new(cells) }
+		}
+
+		#[test]
+		fn test_can_pop() {
+			let mut board = board(&[38], &[31, 39]);
+			assert!(board.can_pop(3, Player::Human));
+			assert!(!board.can_pop(3, Player::Machine));
+			assert!(board.can_pop(4, Player::Machine));
+			assert!(!board.can_pop(0, Player::Human));
+
+			board.pop_out = false;
+			assert!(!board.can_pop(3, Player::Human));
+		}
+
+		#[test]
+		fn test_pop_and_unpop() {
+			// _ _ _ M _ _ _ (21..28)
+			// _ _ _ M _ _ _ (28..35)
+			// _ _ _ H _ _ _ (35..42)
+			let mut board = board(&[38], &[24, 31]);
+			let (cells, hash) = (board.cells, board.hash);
+
+			board.pop(3);
+			let mut expected = create_cells!();
+			expected[31] = Player::Machine;
+			expected[38] = Player::Machine;
+			assert_eq!(board.cells(), expected);
+			assert_eq!(board.remaining[3], 4);
+			assert_eq!(board.hash, ConnectFour::new(expected).hash);
+
+			board.unpop(3, Player::Human);
+			assert_eq!(board.cells, cells);
+			assert_eq!(board.remaining[3], 3);
+			assert_eq!(board.hash, hash);
+		}
+
+		#[test]
+		fn test_pop_last_piece() {
+			let mut board = board(&[38], &[]);
+			assert_eq!(board.play_move(BOARD_WIDTH + 3, Player::Human), None);
+			assert_eq!(board.cells(), create_cells!());
+			assert_eq!(board.empty, 42);
+			assert_eq!(board.hash, 0);
+		}
+
+		// _ _ _ _ _ _ _ (21..28)
+		// H M M M _ _ _ (28..35)
+		// M H M H _ _ _ (35..42)
+		#[test]
+		fn test_pop_wins() {
+			let mut board = board(&[28, 36, 38], &[21, 29, 30, 31, 35, 37]);
+			assert_eq!(board.play_move(BOARD_WIDTH, Player::Machine), Some(Player::Machine));
+		}
+
+		// _ _ _ _ _ _ _ (21..28)
+		// H _ _ _ _ _ _ (28..35)
+		// M H H H _ _ _ (35..42)
+		#[test]
+		fn test_pop_wins_opponent() {
+			let mut board = board(&[28, 36, 37, 38], &[35]);
+			assert_eq!(board.play_move(BOARD_WIDTH, Player::Machine), Some(Player::Human));
+		}
+
+		#[test]
+		fn test_add_pop_wins_opponent() {
+			let mut board = board(&[28, 36, 37, 38], &[35]);
+
+			assert!(!board.js_add(0, Player::Machine, Some(MoveKind::Pop)).unwrap());
+			assert_eq!(board.js_get_winner(), Player::Human);
+			assert!(board.js_finished());
+		}
+
+		// M _ _ _ _ _ _ (21..28)
+		// H M M M _ _ _ (28..35)
+		// M H H H _ _ _ (35..42)
+		#[test]
+		fn test_pop_wins_both() {
+			let mut board = board(&[28, 36, 37, 38], &[21, 29, 30, 31, 35]);
+			assert_eq!(board.play_move(BOARD_WIDTH, Player::Machine), Some(Player::Machine));
+		}
+
+		#[test]
+		fn test_order_moves() {
+			let mut board = board(&[38], &[37, 39]);
+			let (moves, count) = board.order_moves(Player::Machine, 1, None);
+			assert_eq!(&moves[..count], &[3, 2, 4, 1, 5, 0, 6, 9, 11]);
+
+			let (moves, count) = board.order_moves(Player::Human, 1, Some(10));
+			assert_eq!(&moves[..count], &[10, 3, 2, 4, 1, 5, 0, 6]);
+		}
+
+		#[test]
+		fn test_full_board_has_moves() {
+			let mut cells = create_cells!();
+			for (index, cell) in cells.iter_mut().enumerate() {
+				let (row, column) = (index / BOARD_WIDTH, index % BOARD_WIDTH);
+				*cell = if (column / 2 + row) % 2 == 0 { Player::Human } else { Player::Machine };
+			}
+
+			let board = ConnectFour { pop_out: true, ..ConnectFour::new(cells) };
+			assert!(board.has_moves(Player::Machine));
+			assert_eq!(board.position(Player::Machine).moves(), [7, 8, 11, 12]);
+			assert!(!board.js_finished());
+
+			let board = ConnectFour::new(cells);
+			assert!(!board.has_moves(Player::Machine));
+			assert!(board.js_finished());
+		}
+
+		macro_rules! gen_best_move_test {
+			($($name:ident: [$humans:expr, $machines:expr, $outcome:expr],)*) => ($(
+				#[test]
+				fn $name() {
+					// A single reply, as deeper searches also find slower wins:
+					assert_eq!(board(&$humans, &$machines).get_best_move(1, Player::Machine), $outcome);
+				}
+			)*);
+		}
+
+		gen_best_move_test! {
+			// _ _ _ _ _ _ _ (21..28)
+			// H M M M _ _ _ (28..35)
+			// M H M H _ _ _ (35..42)
+			test_takes_winning_pop: [[28, 36, 38], [21, 29, 30, 31, 35, 37], BOARD_WIDTH],
+			// _ _ _ H _ _ _ (21..28)
+			// _ _ _ H _ _ _ (28..35)
+			// _ M M H M _ _ (35..42)
+			test_drops_to_block: [[24, 31, 38], [36, 37, 39], 3],
+		}
+
+		#[test]
+		fn test_monte_carlo_takes_winning_pop() {
+			let board = board(&[28, 36, 38], &[21, 29, 30, 31, 35, 37]);
+			let mut mcts =
+				MonteCarlo::new(MonteCarloOptions { iterations: Some(5_000), seed: Some(42), ..Default::default() })
+					.unwrap();
+
+			assert_eq!(mcts.search(&board.position(Player::Machine)), Some(BOARD_WIDTH));
+		}
+	}
+
+	mod cylinder {
+		use super::super::*;
+
+		/// Creates a classic cylinder with pieces of the human in `humans` and
+		/// of the machine in `machines`.
+		fn board(humans: &[usize], machines: &[usize]) -> ConnectFour {
+			let mut cells = create_cells!();
+			for &offset in humans {
+				cells[offset] = Player::Human;
+			}
+			for &offset in machines {
+				cells[offset] = Player::Machine;
+			}
+
+			ConnectFour::with_layout(Arc::new(Layout::with_wrap(Size::CLASSIC, true)), &cells)
+		}
+
+		macro_rules! gen_status_test {
+			($($name:ident: [$cells:expr, $last:expr, $outcome:expr],)*) => ($(
+				#[test]
+				fn $name() {
+					assert_eq!(board(&$cells, &[]).status($last), $outcome);
+					assert!(!ConnectFour::new(board(&$cells, &[]).cells().try_into().unwrap()).status($last));
+				}
+			)*);
+		}
+
+		gen_status_test! {
+			// _ _ _ _ _ _ _ (28..35)
+			// H H _ _ _ H H (35..42)
+			test_horizontal: [[35, 36, 40, 41], 35, true],
+			// _ _ _ _ _ H _ (14..21)
+			// _ _ _ _ _ _ H (21..28)
+			// H _ _ _ _ _ _ (28..35)
+			// _ H _ _ _ _ _ (35..42)
+			test_diagonal_tl: [[19, 27, 28, 36], 27, true],
+			// _ H _ _ _ _ _ (14..21)
+			// H _ _ _ _ _ _ (21..28)
+			// _ _ _ _ _ _ H (28..35)
+			// _ _ _ _ _ H _ (35..42)
+			test_diagonal_tr: [[15, 21, 34, 40], 21, true],
+			// _ _ _ _ _ _ _ (28..35)
+			// H H _ _ _ _ H (35..42)
+			test_three: [[35, 36, 41], 41, false],
+		}
+
+		#[test]
+		fn test_no_center_column() {
+			let mut board = board(&[24, 31, 38], &[]);
+			assert_eq!(board.score_position_center_column(Player::Human), 0);
+			assert_eq!(
+				board.evaluate(Player::Human),
+				ConnectFour::new(board.cells().try_into().unwrap()).evaluate(Player::Human)
+					- 3 * DEFAULT_WEIGHTS.center
+			);
+		}
+
+		#[test]
+		fn test_block_across_the_edge() {
+			// _ _ _ _ _ _ _ (28..35)
+			// H H M _ _ v H (35..42)
+			assert_eq!(board(&[35, 36, 41], &[37]).get_best_move(4, Player::Machine), 5);
+		}
 	}
 }
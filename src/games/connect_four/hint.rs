@@ -0,0 +1,259 @@
+use napi::{bindgen_prelude::Either, Error, Result};
+
+use super::ConnectFour;
+use crate::{napi_assert, search::options::BestMoveOptions, Player, U_INVALID_INDEX};
+
+/// What a hint of [`ConnectFour`] is about.
+#[napi]
+#[repr(u8)]
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "tune", derive(Clone, Copy))]
+pub enum HintKind {
+	/// Neither player can complete a line with their next move, or there is no
+	/// move left.
+	None,
+	/// The player can complete a line with their next move.
+	Win,
+	/// The opponent can complete a line with their next move.
+	Block,
+	/// The columns around the best move found by the search.
+	Region,
+	/// The best move leaves the player two lines to complete, and the opponent
+	/// can only block one of them.
+	DoubleThreat,
+	/// The best move found by the search, without a more specific reason.
+	Move,
+}
+
+/// A hint of [`ConnectFour`] for the player to move.
+#[napi(object)]
+#[derive(Debug, PartialEq, Eq)]
+pub struct ConnectFourHint {
+	pub kind: HintKind,
+	/// The columns the hint is about, numbered from 0 like in the rest of the
+	/// API.
+	pub columns: Vec<u32>,
+	/// The best move, as returned by `getBestMove`, only given by the hints of
+	/// level 3.
+	pub best_move: Option<u32>,
+	/// The hint as a sentence for the player, which numbers the columns from 1
+	/// as they are shown to them.
+	pub message: String,
+}
+
+impl ConnectFourHint {
+	fn new(kind: HintKind, columns: &[usize], best_move: Option<usize>, message: String) -> Self {
+		Self {
+			kind,
+			columns: columns.iter().map(|&c| c as u32).collect(),
+			best_move: best_move.map(|mv| mv as u32),
+			message,
+		}
+	}
+}
+
+/// Lists the columns for a message, numbered from 1, such as `2, 3 and 5`.
+fn list(columns: &[usize]) -> String {
+	let names: Vec<String> = columns.iter().map(|c| (c + 1).to_string()).collect();
+	match names.split_last() {
+		Some((last, [])) => last.clone(),
+		Some((last, rest)) => format!("{} and {last}", rest.join(", ")),
+		None => String::new(),
+	}
+}
+
+impl ConnectFour {
+	/// The columns where `player` completes a line by dropping a piece, from
+	/// left to right.
+	fn winning_columns(&mut self, player: Player) -> Vec<usize> {
+		(0..self.layout.size.width).filter(|&c| self.available(c) && self.is_winning_move(c, player)).collect()
+	}
+
+	/// Whether dropping a piece of `player` in `column` leaves it two columns
+	/// to complete a line in, while the opponent has none.
+	fn sets_up_double_threat(&mut self, column: usize, player: Player) -> bool {
+		let offset = self.piece_offset(column);
+		self.add(column, offset, player);
+		let double = self.winning_columns(player.opponent()).is_empty() && self.winning_columns(player).len() > 1;
+		self.remove(column, offset);
+		double
+	}
+
+	/// Returns a hint of `level` for `player`, searching `depth` plies deep
+	/// for the levels past 1.
+	///
+	/// The lines either player completes with their next move are found with
+	/// [`ConnectFour::status`] and take precedence at every level, as the
+	/// move is forced.
+	pub(crate) fn hint(&mut self, level: u8, player: Player, depth: u8) -> ConnectFourHint {
+		let wins = self.winning_columns(player);
+		if let Some(&column) = wins.first() {
+			let best_move = (level == 3).then_some(column);
+			return ConnectFourHint::new(
+				HintKind::Win,
+				&wins,
+				best_move,
+				format!("you can win in column {}", column + 1),
+			);
+		}
+
+		let threats = self.winning_columns(player.opponent());
+		if let Some(&column) = threats.first() {
+			let best_move = (level == 3).then_some(column);
+			let message = match threats.len() {
+				1 => format!("you must block column {}", column + 1),
+				_ => format!("you can only block one of columns {}", list(&threats)),
+			};
+			return ConnectFourHint::new(HintKind::Block, &threats, best_move, message);
+		}
+
+		if level == 1 {
+			return ConnectFourHint::new(HintKind::None, &[], None, "there is no immediate threat".into());
+		}
+
+		let mv = self.search(depth, player);
+		if mv == U_INVALID_INDEX {
+			return ConnectFourHint::new(HintKind::None, &[], None, "there is no move left".into());
+		}
+
+		let width = self.layout.size.width;
+		let column = mv % width;
+		if level == 2 {
+			let columns: Vec<usize> = (column.saturating_sub(1)..=(column + 1).min(width - 1)).collect();
+			let message = format!("look around columns {} to {}", columns[0] + 1, columns[columns.len() - 1] + 1);
+			return ConnectFourHint::new(HintKind::Region, &columns, None, message);
+		}
+
+		if mv < width && self.sets_up_double_threat(column, player) {
+			let message = format!("column {} sets up a double threat", column + 1);
+			return ConnectFourHint::new(HintKind::DoubleThreat, &[column], Some(mv), message);
+		}
+
+		let message = if mv < width {
+			format!("column {} is the strongest move found", column + 1)
+		} else {
+			format!("popping your piece out of column {} is the strongest move found", column + 1)
+		};
+		ConnectFourHint::new(HintKind::Move, &[column], Some(mv), message)
+	}
+}
+
+#[napi]
+impl ConnectFour {
+	/// Returns a hint for the player given in the options, which defaults to
+	/// `Player.Human`: level 1 warns about the lines either player can complete
+	/// with their next move, level 2 also points to the columns around the
+	/// best move, and level 3 gives the best move with its reason. Levels 2
+	/// and 3 search as `getBestMove` does, and update `lastSearchStats`.
+	#[napi(js_name = "getHint")]
+	pub fn js_get_hint(&mut self, level: i32, options: Option<BestMoveOptions>) -> Result<ConnectFourHint> {
+		napi_assert!((1..=3).contains(&level));
+		napi_assert!(!self.js_finished());
+
+		// Hints are meant for the human players:
+		let options = options.unwrap_or_default();
+		let options = BestMoveOptions { player: Some(options.player.unwrap_or(Player::Human)), ..options };
+		let (depth, player) = BestMoveOptions::resolve(Some(Either::B(options)), 5)?;
+
+		Ok(self.hint(level as u8, player, depth))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::games::connect_four::tests::board;
+
+	mod list {
+		use super::*;
+
+		#[test]
+		fn test_columns() {
+			assert_eq!(list(&[4]), "5");
+			assert_eq!(list(&[2, 4]), "3 and 5");
+			assert_eq!(list(&[1, 2, 4]), "2, 3 and 5");
+		}
+	}
+
+	mod hint {
+		use super::*;
+
+		macro_rules! gen_test {
+			($($name:ident: [$board:expr, $level:expr, $kind:expr, $columns:expr, $best_move:expr, $message:expr],)*) => ($(
+				#[test]
+				fn $name() {
+					let hint = $board.hint($level, Player::Human, 5);
+
+					assert_eq!(hint.kind, $kind);
+					assert_eq!(hint.columns, $columns);
+					assert_eq!(hint.best_move, $best_move);
+					assert_eq!(hint.message, $message);
+				}
+			)*);
+		}
+
+		gen_test! {
+			// _ _ _ _ _ _ _ (28..35)
+			// H H H _ M M _ (35..42)
+			test_win: [board(&[35, 36, 37], &[39, 40]), 1, HintKind::Win, vec![3], None, "you can win in column 4"],
+			test_win_move: [board(&[35, 36, 37], &[39, 40]), 3, HintKind::Win, vec![3], Some(3), "you can win in column 4"],
+			// _ _ H _ _ _ _ (28..35)
+			// _ _ H M M M _ (35..42)
+			test_block: [board(&[37, 30], &[38, 39, 40]), 1, HintKind::Block, vec![6], None, "you must block column 7"],
+			// _ H _ _ _ _ _ (28..35)
+			// _ H _ M M M _ (35..42)
+			test_block_both: [
+				board(&[36, 29], &[38, 39, 40]),
+				2,
+				HintKind::Block,
+				vec![2, 6],
+				None,
+				"you can only block one of columns 3 and 7"
+			],
+			test_no_threat: [board(&[38], &[31]), 1, HintKind::None, vec![], None, "there is no immediate threat"],
+			// _ _ _ M _ _ _ (28..35)
+			// _ _ _ H H _ M (35..42)
+			test_double_threat: [
+				board(&[38, 39], &[31, 41]),
+				3,
+				HintKind::DoubleThreat,
+				vec![2],
+				Some(2),
+				"column 3 sets up a double threat"
+			],
+		}
+
+		#[test]
+		fn test_region() {
+			let mut board = board(&[38], &[31]);
+			let best = board.get_best_move(5, Player::Human);
+			let hint = board.hint(2, Player::Human, 5);
+
+			assert_eq!(hint.kind, HintKind::Region);
+			assert!(hint.columns.contains(&(best as u32)));
+			assert_eq!(hint.columns.len(), 3);
+			assert_eq!(hint.best_move, None);
+		}
+
+		#[test]
+		fn test_move() {
+			let mut board = board(&[38], &[31]);
+			let best = board.get_best_move(5, Player::Human);
+			let hint = board.hint(3, Player::Human, 5);
+
+			assert_eq!(hint.kind, HintKind::Move);
+			assert_eq!(hint.columns, vec![best as u32]);
+			assert_eq!(hint.best_move, Some(best as u32));
+			assert_eq!(hint.message, format!("column {} is the strongest move found", best + 1));
+		}
+
+		#[test]
+		fn test_machine() {
+			// The threats are the same, from the other side:
+			let hint = board(&[38, 39, 40], &[37, 30]).hint(1, Player::Machine, 5);
+
+			assert_eq!(hint.kind, HintKind::Block);
+			assert_eq!(hint.columns, vec![6]);
+		}
+	}
+}
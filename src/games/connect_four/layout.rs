@@ -0,0 +1,331 @@
+use std::sync::{Arc, LazyLock};
+
+use super::{BOARD_HEIGHT, BOARD_WIDTH, MAX_CELLS, MAX_CONNECT, MAX_HEIGHT, MAX_WIDTH};
+
+/// The smallest supported width, height and line length.
+pub const MIN_SIZE: usize = 3;
+
+/// The directions a line can take, by the offset between two consecutive
+/// cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(usize)]
+pub enum Direction {
+	/// From left to right.
+	Horizontal,
+	/// From top to bottom.
+	Vertical,
+	/// From the top left to the bottom right.
+	DiagonalTl,
+	/// From the top right to the bottom left.
+	DiagonalTr,
+}
+
+impl Direction {
+	pub const ALL: [Direction; 4] =
+		[Direction::Horizontal, Direction::Vertical, Direction::DiagonalTl, Direction::DiagonalTr];
+
+	/// The horizontal and vertical movement of a step in this direction.
+	fn delta(self) -> (isize, isize) {
+		match self {
+			Direction::Horizontal => (1, 0),
+			Direction::Vertical => (0, 1),
+			Direction::DiagonalTl => (1, 1),
+			Direction::DiagonalTr => (-1, 1),
+		}
+	}
+}
+
+/// The dimensions of a board, and the length of the lines that win the game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Size {
+	pub width: usize,
+	pub height: usize,
+	pub connect: usize,
+}
+
+impl Size {
+	/// The classic 7×6 board, where 4 pieces in a row win.
+	pub const CLASSIC: Size = Size { width: BOARD_WIDTH, height: BOARD_HEIGHT, connect: 4 };
+
+	/// The 6×5 board of Connect-Three, where 3 pieces in a row win.
+	#[cfg_attr(feature = "tune", allow(dead_code))]
+	pub const CONNECT_THREE: Size = Size { width: 6, height: 5, connect: 3 };
+
+	/// The 9×6 board of Five-in-a-Row, the classic board with an extra column
+	/// on each side, where 5 pieces in a row win.
+	#[cfg_attr(feature = "tune", allow(dead_code))]
+	pub const FIVE_IN_A_ROW: Size = Size { width: 9, height: 6, connect: 5 };
+
+	/// Checks that boards of this size are supported.
+	pub fn validate(&self) -> Result<(), String> {
+		if !(MIN_SIZE..=MAX_WIDTH).contains(&self.width) {
+			return Err(format!("width must be between {MIN_SIZE} and {MAX_WIDTH}"));
+		}
+
+		if !(MIN_SIZE..=MAX_HEIGHT).contains(&self.height) {
+			return Err(format!("height must be between {MIN_SIZE} and {MAX_HEIGHT}"));
+		}
+
+		let max_connect = MAX_CONNECT.min(self.width.max(self.height));
+		if !(MIN_SIZE..=max_connect).contains(&self.connect) {
+			return Err(format!("connect must be between {MIN_SIZE} and {max_connect}"));
+		}
+
+		Ok(())
+	}
+
+	pub fn cells(&self) -> usize {
+		self.width * self.height
+	}
+}
+
+/// The cells of a window, where the lanes past the line length are unused.
+pub type WindowCells = [u8; MAX_CONNECT];
+
+/// The tables of a board size, generated once and shared by every board of
+/// that size.
+#[derive(Debug)]
+pub struct Layout {
+	pub size: Size,
+	/// Whether the board is a cylinder, where horizontal and diagonal lines
+	/// wrap from the last column back to the first one.
+	pub wrap: bool,
+	/// The order in which the columns are searched when no heuristic prefers
+	/// one, from the center outwards, as the central columns take part in more
+	/// lines.
+	pub column_order: [usize; MAX_WIDTH],
+	/// The previous and the next cell of a line through every cell, per
+	/// direction, only meaningful within the reach of the cell.
+	pub neighbours: [[[u8; 2]; 4]; MAX_CELLS],
+	/// The amount of cells a line can extend backwards and forwards from each
+	/// cell, per direction, capped to one less than the line length.
+	pub reach: [[(u8, u8); 4]; MAX_CELLS],
+	/// The cells of every window of `connect` cells, per direction.
+	pub windows: [Vec<WindowCells>; 4],
+}
+
+static CLASSIC: LazyLock<Arc<Layout>> = LazyLock::new(|| Arc::new(Layout::new(Size::CLASSIC)));
+
+impl Layout {
+	/// Generates the tables of a flat board of the given size, which must be
+	/// valid.
+	pub fn new(size: Size) -> Self {
+		Self::with_wrap(size, false)
+	}
+
+	/// Generates the tables of a board of the given size, which must be valid,
+	/// and no wider than its line length when it wraps around.
+	pub fn with_wrap(size: Size, wrap: bool) -> Self {
+		debug_assert!(size.validate().is_ok());
+		debug_assert!(!wrap || size.connect <= size.width);
+
+		// Sort the columns by their distance to the center, starting from its
+		// left side:
+		let mut column_order: [usize; MAX_WIDTH] = std::array::from_fn(|i| i);
+		column_order[..size.width].sort_by_key(|&c| ((2 * c).abs_diff(size.width - 1), c));
+
+		let (width, height) = (size.width as isize, size.height as isize);
+
+		// The cell `k` steps away from `(x, y)` in a direction, if it is on the
+		// board:
+		let step = |x: isize, y: isize, direction: Direction, k: isize| {
+			let (dx, dy) = direction.delta();
+			let (x, y) = (x + k * dx, y + k * dy);
+			let x = if wrap { x.rem_euclid(width) } else { x };
+			((0..width).contains(&x) && (0..height).contains(&y)).then(|| (y * width + x) as u8)
+		};
+
+		let mut neighbours = [[[u8::MAX; 2]; 4]; MAX_CELLS];
+		let mut reach = [[(0, 0); 4]; MAX_CELLS];
+		let mut windows = [Vec::new(), Vec::new(), Vec::new(), Vec::new()];
+		for cell in 0..size.cells() {
+			let (x, y) = ((cell % size.width) as isize, (cell / size.width) as isize);
+			for direction in Direction::ALL {
+				let extent = |sign: isize| {
+					(1..size.connect as isize).take_while(|k| step(x, y, direction, sign * k).is_some()).count() as u8
+				};
+
+				let (backward, forward) = (extent(-1), extent(1));
+				neighbours[cell][direction as usize] =
+					[-1, 1].map(|sign| step(x, y, direction, sign).unwrap_or(u8::MAX));
+				reach[cell][direction as usize] = (backward, forward);
+
+				// A horizontal window as wide as a cylinder is the same from every
+				// cell of the row:
+				let repeated = wrap && direction == Direction::Horizontal && size.connect == size.width && x > 0;
+				if forward as usize == size.connect - 1 && !repeated {
+					let mut window = [u8::MAX; MAX_CONNECT];
+					for (k, lane) in window[..size.connect].iter_mut().enumerate() {
+						*lane = step(x, y, direction, k as isize).unwrap();
+					}
+					windows[direction as usize].push(window);
+				}
+			}
+		}
+
+		Self { size, wrap, column_order, neighbours, reach, windows }
+	}
+
+	/// Returns the layout of the given size, sharing the one of the classic
+	/// board.
+	pub fn get(size: Size) -> Arc<Self> {
+		if size == Size::CLASSIC {
+			CLASSIC.clone()
+		} else {
+			Arc::new(Self::new(size))
+		}
+	}
+
+	/// The columns in search order.
+	pub fn columns(&self) -> &[usize] {
+		&self.column_order[..self.size.width]
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// The tables of the classic board as they were written by hand, with the
+	/// backward reach in the upper and the forward reach in the lower 2 bits.
+	const AVAILABLE_BOTTOM: [u8; 42] = [
+		0b0011, 0b0011, 0b0011, 0b0011, 0b0011, 0b0011, 0b0011, //
+		0b0011, 0b0011, 0b0011, 0b0011, 0b0011, 0b0011, 0b0011, //
+		0b0011, 0b0011, 0b0011, 0b0011, 0b0011, 0b0011, 0b0011, //
+		0b0010, 0b0010, 0b0010, 0b0010, 0b0010, 0b0010, 0b0010, //
+		0b0001, 0b0001, 0b0001, 0b0001, 0b0001, 0b0001, 0b0001, //
+		0b0000, 0b0000, 0b0000, 0b0000, 0b0000, 0b0000, 0b0000,
+	];
+
+	const AVAILABLE_HORIZONTAL: [u8; 42] = [
+		0b0011, 0b0111, 0b1011, 0b1111, 0b1110, 0b1101, 0b1100, //
+		0b0011, 0b0111, 0b1011, 0b1111, 0b1110, 0b1101, 0b1100, //
+		0b0011, 0b0111, 0b1011, 0b1111, 0b1110, 0b1101, 0b1100, //
+		0b0011, 0b0111, 0b1011, 0b1111, 0b1110, 0b1101, 0b1100, //
+		0b0011, 0b0111, 0b1011, 0b1111, 0b1110, 0b1101, 0b1100, //
+		0b0011, 0b0111, 0b1011, 0b1111, 0b1110, 0b1101, 0b1100,
+	];
+
+	const AVAILABLE_DIAGONAL_TL: [u8; 42] = [
+		0b0011, 0b0011, 0b0011, 0b0011, 0b0010, 0b0001, 0b0000, //
+		0b0011, 0b0111, 0b0111, 0b0111, 0b0110, 0b0101, 0b0100, //
+		0b0011, 0b0111, 0b1011, 0b1011, 0b1010, 0b1001, 0b1000, //
+		0b0010, 0b0110, 0b1010, 0b1110, 0b1110, 0b1101, 0b1100, //
+		0b0001, 0b0101, 0b1001, 0b1101, 0b1101, 0b1101, 0b1100, //
+		0b0000, 0b0100, 0b1000, 0b1100, 0b1100, 0b1100, 0b1100,
+	];
+
+	const AVAILABLE_DIAGONAL_BL: [u8; 42] = [
+		0b0000, 0b0100, 0b1000, 0b1100, 0b1100, 0b1100, 0b1100, //
+		0b0001, 0b0101, 0b1001, 0b1101, 0b1101, 0b1101, 0b1100, //
+		0b0010, 0b0110, 0b1010, 0b1110, 0b1110, 0b1101, 0b1100, //
+		0b0011, 0b0111, 0b1011, 0b1011, 0b1010, 0b1001, 0b1000, //
+		0b0011, 0b0111, 0b0111, 0b0111, 0b0110, 0b0101, 0b0100, //
+		0b0011, 0b0011, 0b0011, 0b0011, 0b0010, 0b0001, 0b0000,
+	];
+
+	fn mask(layout: &Layout, cell: usize, direction: Direction) -> u8 {
+		let (backward, forward) = layout.reach[cell][direction as usize];
+		backward << 2 | forward
+	}
+
+	macro_rules! gen_reach_test {
+		($($name:ident: [$direction:expr, $expected:expr, $map:expr],)*) => ($(
+			#[test]
+			fn $name() {
+				let layout = Layout::get(Size::CLASSIC);
+				let map: fn(u8) -> u8 = $map;
+
+				for cell in 0..42 {
+					assert_eq!(map(mask(&layout, cell, $direction)), $expected[cell], "cell {cell}");
+				}
+			}
+		)*);
+	}
+
+	gen_reach_test! {
+		// The handwritten table only tracked the cells below:
+		test_classic_vertical: [Direction::Vertical, AVAILABLE_BOTTOM, |m| m & 0b11],
+		test_classic_horizontal: [Direction::Horizontal, AVAILABLE_HORIZONTAL, |m| m],
+		test_classic_diagonal_tl: [Direction::DiagonalTl, AVAILABLE_DIAGONAL_TL, |m| m],
+		// The handwritten table went from the bottom left to the top right:
+		test_classic_diagonal_tr: [Direction::DiagonalTr, AVAILABLE_DIAGONAL_BL, |m| (m & 0b11) << 2 | m >> 2],
+	}
+
+	#[test]
+	fn test_column_order() {
+		assert_eq!(Layout::new(Size::CLASSIC).columns(), &[3, 2, 4, 1, 5, 0, 6]);
+		assert_eq!(Layout::new(Size { width: 6, height: 5, connect: 4 }).columns(), &[2, 3, 1, 4, 0, 5]);
+		assert_eq!(Layout::new(Size { width: 10, height: 8, connect: 4 }).columns(), &[4, 5, 3, 6, 2, 7, 1, 8, 0, 9]);
+	}
+
+	macro_rules! gen_windows_test {
+		($($name:ident: [$size:expr, $outcome:expr],)*) => ($(
+			#[test]
+			fn $name() {
+				let layout = Layout::new($size);
+				assert_eq!(layout.windows.each_ref().map(|w| w.len()), $outcome);
+			}
+		)*);
+	}
+
+	gen_windows_test! {
+		test_windows_classic: [Size::CLASSIC, [24, 21, 12, 12]],
+		test_windows_6x5: [Size { width: 6, height: 5, connect: 4 }, [15, 12, 6, 6]],
+		test_windows_10x8_connect_5: [Size { width: 10, height: 8, connect: 5 }, [48, 40, 24, 24]],
+		test_windows_too_long_to_fit: [Size { width: 3, height: 8, connect: 5 }, [0, 12, 0, 0]],
+	}
+
+	#[test]
+	fn test_cylinder_reach() {
+		let layout = Layout::with_wrap(Size::CLASSIC, true);
+		for cell in 0..42 {
+			assert_eq!(layout.reach[cell][Direction::Horizontal as usize], (3, 3), "cell {cell}");
+			// Only the rows bound the diagonals:
+			let (backward, forward) = layout.reach[cell][Direction::DiagonalTl as usize];
+			assert_eq!((backward, forward), ((cell / 7).min(3) as u8, (5 - cell / 7).min(3) as u8), "cell {cell}");
+		}
+
+		assert_eq!(layout.reach[38][Direction::Vertical as usize], Layout::new(Size::CLASSIC).reach[38][1]);
+	}
+
+	#[test]
+	fn test_cylinder_neighbours() {
+		let layout = Layout::with_wrap(Size::CLASSIC, true);
+		assert_eq!(layout.neighbours[6][Direction::Horizontal as usize], [5, 0]);
+		assert_eq!(layout.neighbours[35][Direction::Horizontal as usize], [41, 36]);
+		assert_eq!(layout.neighbours[13][Direction::DiagonalTl as usize], [5, 14]);
+		assert_eq!(layout.neighbours[14][Direction::DiagonalTr as usize], [8, 27]);
+
+		let layout = Layout::new(Size::CLASSIC);
+		assert_eq!(layout.neighbours[13][Direction::Horizontal as usize], [12, u8::MAX]);
+	}
+
+	#[test]
+	fn test_cylinder_windows() {
+		let layout = Layout::with_wrap(Size::CLASSIC, true);
+		assert_eq!(layout.windows.each_ref().map(|w| w.len()), [42, 21, 21, 21]);
+		assert_eq!(layout.windows[Direction::Horizontal as usize][5][..4], [5, 6, 0, 1]);
+
+		// A row as wide as the line only holds one window:
+		let layout = Layout::with_wrap(Size { width: 4, height: 4, connect: 4 }, true);
+		assert_eq!(layout.windows.each_ref().map(|w| w.len()), [4, 4, 4, 4]);
+	}
+
+	macro_rules! gen_validate_test {
+		($($name:ident: [$size:expr, $outcome:expr],)*) => ($(
+			#[test]
+			fn $name() {
+				assert_eq!($size.validate(), $outcome);
+			}
+		)*);
+	}
+
+	gen_validate_test! {
+		test_validate_classic: [Size::CLASSIC, Ok(())],
+		test_validate_largest: [Size { width: 10, height: 8, connect: 8 }, Ok(())],
+		test_validate_too_wide: [Size { width: 11, height: 6, connect: 4 }, Err("width must be between 3 and 10".to_string())],
+		test_validate_too_short: [Size { width: 7, height: 2, connect: 4 }, Err("height must be between 3 and 8".to_string())],
+		test_validate_connect_too_long: [Size { width: 5, height: 4, connect: 6 }, Err("connect must be between 3 and 5".to_string())],
+	}
+}
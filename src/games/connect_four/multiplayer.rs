@@ -0,0 +1,697 @@
+use std::{cmp, sync::Arc, time::Instant};
+
+use napi::{bindgen_prelude::Uint8Array, Error, Result};
+
+use super::{
+	layout::{Direction, Layout, Size},
+	AiRemaining, MAX_CELLS, MAX_CONNECT, MAX_WIDTH,
+};
+use crate::{
+	isize_to_usize, napi_assert,
+	search::{
+		options::parse_depth,
+		stats::{SearchStats, Stats},
+	},
+	U_INVALID_INDEX,
+};
+
+/// The most seats of a game.
+pub const MAX_SEATS: usize = 4;
+
+/// The seat of the empty cells, and of the winner while nobody won.
+const NO_SEAT: u8 = 0;
+
+/// The score of a won game, adjusted by the remaining depth so the search
+/// prefers the fastest wins and the slowest losses.
+const OUTCOME_WIN: i64 = 1_000_000_000;
+
+/// The score of a window holding `n` pieces of a single seat and no piece of
+/// the others, by `n`, up to the longest lines.
+const WINDOW_WEIGHTS: [i64; MAX_CONNECT + 1] = [0, 1, 10, 100, 1_000, 10_000, 100_000, 1_000_000, 10_000_000];
+
+/// The scores of a position, per seat, where the first one is unused.
+type Scores = [i64; MAX_SEATS + 1];
+
+/// How the search expects the other seats to play.
+#[napi]
+#[repr(u8)]
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "tune", derive(Clone, Copy))]
+pub enum MultiplayerSearch {
+	/// Every other seat plays against the searching one, as if they were a
+	/// single opponent, which allows alpha-beta pruning.
+	Paranoid,
+	/// Every seat plays for itself, maximizing its own score (max-n). Slower,
+	/// as nothing is pruned, but less pessimistic.
+	MaxN,
+}
+
+/// The options of a [`MultiplayerConnectFour`] board.
+#[napi(object)]
+#[derive(Debug, Default)]
+pub struct MultiplayerConnectFourOptions {
+	/// The amount of seats, 3 or 4, defaults to 3.
+	pub players: Option<u32>,
+	/// The amount of columns, between 3 and 10, defaults to 9 for 3 players
+	/// and 10 for 4.
+	pub width: Option<u32>,
+	/// The amount of rows, between 3 and 8, defaults to 7 for 3 players and 8
+	/// for 4.
+	pub height: Option<u32>,
+	/// The amount of pieces in a row needed to win, between 3 and 8 and no
+	/// longer than the board, defaults to 4.
+	pub connect: Option<u32>,
+	/// The seat to move, defaults to 1.
+	pub turn: Option<u32>,
+	/// How the other seats are expected to play, defaults to
+	/// `MultiplayerSearch.Paranoid`.
+	pub search: Option<MultiplayerSearch>,
+}
+
+impl MultiplayerConnectFourOptions {
+	fn size(&self, seats: usize) -> Size {
+		let (width, height) = if seats == MAX_SEATS { (10, 8) } else { (9, 7) };
+		let get = |value: Option<u32>, default: usize| value.map_or(default, |v| v as usize);
+
+		Size { width: get(self.width, width), height: get(self.height, height), connect: get(self.connect, 4) }
+	}
+}
+
+/// Connect Four for 3 or 4 players, who take turns by seat, from 1 to the
+/// amount of players. The first one with a line wins.
+///
+/// Cells hold the seat of their piece, or 0 when empty, and are numbered like
+/// the ones of `ConnectFour`.
+#[napi]
+pub struct MultiplayerConnectFour {
+	/// The generated tables of the board size.
+	layout: Arc<Layout>,
+	/// The seat of every cell, row by row from the top, only the first
+	/// `width * height` are used.
+	cells: [u8; MAX_CELLS],
+	remaining: AiRemaining,
+	empty: u8,
+	seats: u8,
+	/// The seat to move.
+	turn: u8,
+	/// The seat who won with the last move, [`NO_SEAT`] while nobody did.
+	winner: u8,
+	search: MultiplayerSearch,
+	/// The counters of the last search.
+	stats: Stats,
+}
+
+impl MultiplayerConnectFour {
+	/// Creates a board of the given size, which must be valid, from the seats
+	/// of its `width * height` cells, won by the seat of the first line found
+	/// if any.
+	pub fn new(size: Size, seats: u8, cells: &[u8], turn: u8) -> Self {
+		debug_assert!(size.validate().is_ok());
+		debug_assert_eq!(cells.len(), size.cells());
+		debug_assert!((1..=seats).contains(&turn));
+
+		let mut board = [NO_SEAT; MAX_CELLS];
+		board[..cells.len()].copy_from_slice(cells);
+
+		let mut remaining = [0; MAX_WIDTH];
+		for (x, remaining) in remaining[..size.width].iter_mut().enumerate() {
+			*remaining = (0..size.height).take_while(|y| cells[y * size.width + x] == NO_SEAT).count() as u8;
+		}
+
+		let layout = Layout::get(size);
+		let winner = layout
+			.windows
+			.iter()
+			.flatten()
+			.find_map(|window| {
+				let seat = board[window[0] as usize];
+				let line = window[..size.connect].iter().all(|&offset| board[offset as usize] == seat);
+				(seat != NO_SEAT && line).then_some(seat)
+			})
+			.unwrap_or(NO_SEAT);
+
+		Self {
+			layout,
+			cells: board,
+			remaining,
+			empty: remaining.iter().sum(),
+			seats,
+			turn,
+			winner,
+			search: MultiplayerSearch::Paranoid,
+			stats: Stats::default(),
+		}
+	}
+
+	/// Returns the seat moving after `seat`.
+	fn next(&self, seat: u8) -> u8 {
+		seat % self.seats + 1
+	}
+
+	fn available(&self, column: usize) -> bool {
+		debug_assert!(column < self.layout.size.width);
+
+		self.remaining[column] > 0
+	}
+
+	fn piece_offset(&self, column: usize) -> usize {
+		debug_assert!(self.available(column));
+
+		((self.remaining[column] as usize - 1) * self.layout.size.width) + column
+	}
+
+	fn add(&mut self, column: usize, seat: u8) -> usize {
+		let offset = self.piece_offset(column);
+		self.remaining[column] -= 1;
+		self.empty -= 1;
+		self.cells[offset] = seat;
+		offset
+	}
+
+	fn remove(&mut self, column: usize) {
+		self.remaining[column] += 1;
+		self.empty += 1;
+
+		let offset = self.piece_offset(column);
+		debug_assert_ne!(self.cells[offset], NO_SEAT);
+		self.cells[offset] = NO_SEAT;
+	}
+
+	/// Counts the pieces of `seat` following `cell` in a direction, backwards
+	/// or forwards by `side`, up to `reach` of them.
+	fn count_direction(&self, seat: u8, cell: usize, direction: Direction, side: usize, reach: u8) -> usize {
+		let mut offset = cell;
+		for count in 0..reach as usize {
+			offset = self.layout.neighbours[offset][direction as usize][side] as usize;
+			if self.cells[offset] != seat {
+				return count;
+			}
+		}
+
+		reach as usize
+	}
+
+	/// Checks whether the piece at `offset` is part of a winning line.
+	fn status(&self, offset: usize) -> bool {
+		debug_assert_ne!(self.cells[offset], NO_SEAT);
+
+		let seat = self.cells[offset];
+		let reach = &self.layout.reach[offset];
+		Direction::ALL.into_iter().any(|direction| {
+			let (backward, forward) = reach[direction as usize];
+
+			1 + self.count_direction(seat, offset, direction, 0, backward)
+				+ self.count_direction(seat, offset, direction, 1, forward)
+				>= self.layout.size.connect
+		})
+	}
+
+	/// Checks whether dropping a piece of `seat` in `column` wins the game,
+	/// leaving the board unchanged.
+	fn is_winning_move(&mut self, column: usize, seat: u8) -> bool {
+		let offset = self.add(column, seat);
+		let won = self.status(offset);
+		self.remove(column);
+		won
+	}
+
+	/// Drops a piece of the seat to move in `column` and passes the turn,
+	/// returning whether it won the game.
+	fn play(&mut self, column: usize) -> bool {
+		let offset = self.add(column, self.turn);
+		let won = self.status(offset);
+		if won {
+			self.winner = self.turn;
+		}
+
+		self.turn = self.next(self.turn);
+		won
+	}
+
+	fn finished(&self) -> bool {
+		self.winner != NO_SEAT || self.empty == 0
+	}
+
+	/// Scores the board for every seat, adding up the windows only its pieces
+	/// take part in, minus the ones of the other seats.
+	fn evaluate(&self) -> Scores {
+		let mut own = [0; MAX_SEATS + 1];
+		let connect = self.layout.size.connect;
+		for windows in &self.layout.windows {
+			for cells in windows {
+				let mut owner = NO_SEAT;
+				let mut count = 0;
+				for &offset in &cells[..connect] {
+					match self.cells[offset as usize] {
+						NO_SEAT => {}
+						seat if owner == NO_SEAT || owner == seat => {
+							owner = seat;
+							count += 1;
+						}
+						// A window shared by two seats is a line for neither:
+						_ => {
+							count = 0;
+							break;
+						}
+					}
+				}
+
+				own[owner as usize] += WINDOW_WEIGHTS[count];
+			}
+		}
+
+		let total: i64 = own[1..].iter().sum();
+		let mut scores = [0; MAX_SEATS + 1];
+		for seat in 1..=self.seats as usize {
+			scores[seat] = 2 * own[seat] - total;
+		}
+
+		scores
+	}
+
+	/// Returns the scores of a game won by `seat`.
+	fn won_by(seat: u8, remaining: u8) -> Scores {
+		let score = OUTCOME_WIN + remaining as i64;
+		let mut scores = [-score; MAX_SEATS + 1];
+		scores[seat as usize] = score;
+		scores
+	}
+
+	/// Orders the available columns for `seat`, trying immediate wins first,
+	/// then the rest from the center outwards.
+	fn order_moves(&mut self, seat: u8) -> ([usize; MAX_WIDTH], usize) {
+		let mut moves = [0; MAX_WIDTH];
+		let mut count = 0;
+		for &column in self.layout.columns() {
+			if self.available(column) {
+				moves[count] = column;
+				count += 1;
+			}
+		}
+
+		if let Some(i) = (0..count).find(|&i| self.is_winning_move(moves[i], seat)) {
+			moves[..=i].rotate_right(1);
+		}
+
+		(moves, count)
+	}
+
+	/// Scores the position for `root` once `seat` moved last to `offset`, where
+	/// `root` maximizes its score and every other seat minimizes it.
+	fn paranoid(&mut self, root: u8, seat: u8, offset: usize, remaining: u8, alpha: i64, beta: i64) -> i64 {
+		self.stats.visit(remaining);
+
+		if self.status(offset) {
+			self.stats.leaves += 1;
+			return Self::won_by(seat, remaining)[root as usize];
+		}
+
+		if self.empty == 0 {
+			self.stats.leaves += 1;
+			return 0;
+		}
+
+		if remaining == 0 {
+			self.stats.leaves += 1;
+			return self.evaluate()[root as usize];
+		}
+
+		let mover = self.next(seat);
+		let (mut alpha, mut beta) = (alpha, beta);
+		let mut best = if mover == root { i64::MIN } else { i64::MAX };
+
+		let (moves, count) = self.order_moves(mover);
+		for &column in &moves[..count] {
+			let offset = self.add(column, mover);
+			let value = self.paranoid(root, mover, offset, remaining - 1, alpha, beta);
+			self.remove(column);
+
+			if mover == root {
+				best = cmp::max(best, value);
+				alpha = cmp::max(alpha, best);
+			} else {
+				best = cmp::min(best, value);
+				beta = cmp::min(beta, best);
+			}
+
+			if alpha >= beta {
+				self.stats.cutoffs += 1;
+				break;
+			}
+		}
+
+		best
+	}
+
+	/// Scores the position for every seat once `seat` moved last to `offset`,
+	/// where every seat picks the move with the highest score for itself.
+	fn max_n(&mut self, seat: u8, offset: usize, remaining: u8) -> Scores {
+		self.stats.visit(remaining);
+
+		if self.status(offset) {
+			self.stats.leaves += 1;
+			return Self::won_by(seat, remaining);
+		}
+
+		if self.empty == 0 {
+			self.stats.leaves += 1;
+			return [0; MAX_SEATS + 1];
+		}
+
+		if remaining == 0 {
+			self.stats.leaves += 1;
+			return self.evaluate();
+		}
+
+		let mover = self.next(seat);
+		let mut best: Option<Scores> = None;
+
+		let (moves, count) = self.order_moves(mover);
+		for &column in &moves[..count] {
+			let offset = self.add(column, mover);
+			let scores = self.max_n(mover, offset, remaining - 1);
+			self.remove(column);
+
+			if best.is_none_or(|best| scores[mover as usize] > best[mover as usize]) {
+				best = Some(scores);
+			}
+		}
+
+		best.unwrap()
+	}
+
+	fn max_top(&mut self, remaining: u8) -> usize {
+		if remaining == 0 {
+			return U_INVALID_INDEX;
+		}
+
+		// The root is not visited, its children are the first ply:
+		self.stats = Stats::new(remaining - 1);
+
+		let root = self.turn;
+		let mut max_v = i64::MIN;
+		let mut best = U_INVALID_INDEX;
+		let (moves, count) = self.order_moves(root);
+		for &column in &moves[..count] {
+			let offset = self.add(column, root);
+			let points = match self.search {
+				MultiplayerSearch::Paranoid => self.paranoid(root, root, offset, remaining - 1, max_v, i64::MAX),
+				MultiplayerSearch::MaxN => self.max_n(root, offset, remaining - 1)[root as usize],
+			};
+			self.remove(column);
+
+			if points > max_v || best == U_INVALID_INDEX {
+				max_v = points;
+				best = column;
+			}
+		}
+
+		best
+	}
+
+	/// Returns the best column for the seat to move, searching `maximum_depth`
+	/// plies deep, or [`U_INVALID_INDEX`] if the game is finished.
+	pub fn get_best_move(&mut self, maximum_depth: u8) -> usize {
+		let start = Instant::now();
+
+		let column = if self.finished() {
+			self.stats = Stats::default();
+			U_INVALID_INDEX
+		} else {
+			self.max_top(cmp::min(self.empty, maximum_depth))
+		};
+
+		self.stats.elapsed = start.elapsed();
+		column
+	}
+}
+
+#[napi]
+impl MultiplayerConnectFour {
+	/// Creates a board from the seats of its cells, which defaults to an empty
+	/// one.
+	#[napi(constructor)]
+	pub fn js_new(values: Option<Uint8Array>, options: Option<MultiplayerConnectFourOptions>) -> Result<Self> {
+		let options = options.unwrap_or_default();
+		let seats = options.players.unwrap_or(3) as usize;
+		if !(3..=MAX_SEATS).contains(&seats) {
+			return Err(Error::from_reason(format!("players must be between 3 and {MAX_SEATS}")));
+		}
+
+		let size = options.size(seats);
+		size.validate().map_err(Error::from_reason)?;
+
+		let turn = options.turn.unwrap_or(1) as usize;
+		if !(1..=seats).contains(&turn) {
+			return Err(Error::from_reason(format!("turn must be between 1 and {seats}")));
+		}
+
+		let mut cells = vec![NO_SEAT; size.cells()];
+		if let Some(v) = values {
+			let input = v.to_vec();
+			if input.len() != size.cells() {
+				return Err(Error::from_reason(format!("data must have exactly {} numbers", size.cells())));
+			}
+
+			if input.iter().any(|&seat| seat as usize > seats) {
+				return Err(Error::from_reason(format!("data must only hold seats between 0 and {seats}")));
+			}
+			cells = input;
+		}
+
+		Ok(MultiplayerConnectFour {
+			search: options.search.unwrap_or(MultiplayerSearch::Paranoid),
+			..MultiplayerConnectFour::new(size, seats as u8, &cells, turn as u8)
+		})
+	}
+
+	#[napi(getter = board)]
+	pub fn js_get_board(&self) -> Uint8Array {
+		Uint8Array::new(self.cells[..self.layout.size.cells()].to_vec())
+	}
+
+	#[napi(getter = width)]
+	pub fn js_get_width(&self) -> u32 {
+		self.layout.size.width as u32
+	}
+
+	#[napi(getter = height)]
+	pub fn js_get_height(&self) -> u32 {
+		self.layout.size.height as u32
+	}
+
+	/// The amount of pieces in a row needed to win.
+	#[napi(getter = connect)]
+	pub fn js_get_connect(&self) -> u32 {
+		self.layout.size.connect as u32
+	}
+
+	/// The amount of seats.
+	#[napi(getter = players)]
+	pub fn js_get_players(&self) -> u32 {
+		self.seats as u32
+	}
+
+	/// The seat to move.
+	#[napi(getter = turn)]
+	pub fn js_get_turn(&self) -> u32 {
+		self.turn as u32
+	}
+
+	#[napi(js_name = "available")]
+	pub fn js_available(&self, column: i32) -> Result<bool> {
+		Ok(self.available(isize_to_usize!(column, self.layout.size.width)?))
+	}
+
+	#[napi(getter = finished)]
+	pub fn js_finished(&self) -> bool {
+		self.finished()
+	}
+
+	/// The seat who completed a line, 0 while nobody did.
+	#[napi(getter = winner)]
+	pub fn js_get_winner(&self) -> u32 {
+		self.winner as u32
+	}
+
+	/// Drops a piece of the seat to move in `column`, returning whether it won
+	/// the game.
+	#[napi(js_name = "add")]
+	pub fn js_add(&mut self, column: i32) -> Result<bool> {
+		let c = isize_to_usize!(column, self.layout.size.width)?;
+		napi_assert!(!self.finished());
+		napi_assert!(self.available(c));
+
+		Ok(self.play(c))
+	}
+
+	/// The statistics of the last call to `getBestMove`, every counter is zero
+	/// before the first search.
+	#[napi(getter = lastSearchStats)]
+	pub fn js_get_last_search_stats(&self) -> SearchStats {
+		self.stats.into()
+	}
+
+	/// Returns the best column for the seat to move, or `INVALID_INDEX` if the
+	/// game is finished.
+	#[napi(js_name = "getBestMove")]
+	pub fn js_get_best_move(&mut self, depth: Option<i32>) -> Result<i32> {
+		Ok(self.get_best_move(parse_depth(depth, 5)?) as i32)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::search::random::Random;
+
+	const THREE_PLAYERS: Size = Size { width: 9, height: 7, connect: 4 };
+
+	/// Creates a 9×7 board for 3 seats with the pieces of every seat in the
+	/// given cells.
+	fn board(seats: &[&[usize]], turn: u8) -> MultiplayerConnectFour {
+		let mut cells = [NO_SEAT; 63];
+		for (seat, pieces) in seats.iter().enumerate() {
+			for &cell in *pieces {
+				cells[cell] = seat as u8 + 1;
+			}
+		}
+
+		MultiplayerConnectFour::new(THREE_PLAYERS, 3, &cells, turn)
+	}
+
+	mod play {
+		use super::*;
+
+		#[test]
+		fn test_turns() {
+			let mut game = board(&[], 1);
+			for (column, seat) in [(4, 1), (4, 2), (4, 3), (4, 1)] {
+				assert_eq!(game.turn, seat);
+				assert!(!game.play(column));
+			}
+
+			assert_eq!(game.turn, 2);
+			assert_eq!(game.empty, 59);
+			assert_eq!([31, 40, 49, 58].map(|c| game.cells[c]), [1, 3, 2, 1]);
+		}
+
+		#[test]
+		fn test_win() {
+			// The third seat completes the bottom row:
+			let mut game = board(&[&[45], &[46], &[54, 55, 56]], 3);
+
+			assert!(game.play(3));
+			assert_eq!(game.winner, 3);
+			assert!(game.finished());
+		}
+
+		#[test]
+		fn test_won_board() {
+			let game = board(&[&[54, 55, 56, 57], &[45, 46], &[47, 48]], 3);
+
+			assert_eq!(game.winner, 1);
+			assert!(game.finished());
+		}
+
+		#[test]
+		fn test_four_seats() {
+			let mut game = MultiplayerConnectFour::new(Size { width: 10, height: 8, connect: 4 }, 4, &[0; 80], 4);
+			game.play(0);
+
+			assert_eq!(game.turn, 1);
+		}
+	}
+
+	mod evaluate {
+		use super::*;
+
+		#[test]
+		fn test_empty() {
+			assert_eq!(board(&[], 1).evaluate(), [0; MAX_SEATS + 1]);
+		}
+
+		#[test]
+		fn test_shared_windows() {
+			// A piece in the bottom left corner is part of 3 windows, one of which
+			// it shares with the piece next to it, which is part of 4:
+			let scores = board(&[&[54], &[55]], 1).evaluate();
+
+			assert_eq!(scores[1..4], [2 - 3, 3 - 2, -(2 + 3)]);
+		}
+
+		#[test]
+		fn test_longest_line() {
+			// The bottom row is a single window of 8 pieces of the first seat:
+			let mut cells = [NO_SEAT; 24];
+			cells[16..].fill(1);
+			let game = MultiplayerConnectFour::new(Size { width: 8, height: 3, connect: 8 }, 3, &cells, 2);
+
+			assert_eq!(game.winner, 1);
+			assert_eq!(game.evaluate()[1..4], [WINDOW_WEIGHTS[8], -WINDOW_WEIGHTS[8], -WINDOW_WEIGHTS[8]]);
+		}
+	}
+
+	mod get_best_move {
+		use super::*;
+
+		macro_rules! gen_test {
+			($($name:ident: [$search:expr, $board:expr, $outcome:expr],)*) => ($(
+				#[test]
+				fn $name() {
+					let mut game = MultiplayerConnectFour { search: $search, ..$board };
+					assert_eq!(game.get_best_move(4), $outcome);
+				}
+			)*);
+		}
+
+		gen_test! {
+			test_paranoid_win: [MultiplayerSearch::Paranoid, board(&[&[54, 55, 56], &[45, 46], &[]], 1), 3],
+			test_max_n_win: [MultiplayerSearch::MaxN, board(&[&[54, 55, 56], &[46], &[]], 1), 3],
+			// The second seat would complete the bottom row next:
+			test_paranoid_block: [MultiplayerSearch::Paranoid, board(&[&[45], &[54, 55, 56], &[46]], 1), 3],
+			test_max_n_block: [MultiplayerSearch::MaxN, board(&[&[45], &[54, 55, 56], &[46]], 1), 3],
+			// The third seat does not count on the first one to block the second:
+			test_next_seat_blocks: [MultiplayerSearch::Paranoid, board(&[&[45], &[54, 55, 56], &[46]], 3), 3],
+		}
+
+		#[test]
+		fn test_finished() {
+			let mut game = board(&[&[54, 55, 56, 57], &[45, 46], &[47, 48]], 3);
+			assert_eq!(game.get_best_move(4), U_INVALID_INDEX);
+		}
+
+		#[test]
+		fn test_paranoid_prunes() {
+			let mut paranoid = board(&[&[58], &[49], &[40]], 1);
+			let mut max_n =
+				MultiplayerConnectFour { search: MultiplayerSearch::MaxN, ..board(&[&[58], &[49], &[40]], 1) };
+			paranoid.get_best_move(4);
+			max_n.get_best_move(4);
+
+			assert!(paranoid.stats.cutoffs > 0);
+			assert_eq!(max_n.stats.cutoffs, 0);
+			assert!(paranoid.stats.nodes < max_n.stats.nodes);
+			assert_eq!(paranoid.stats.max_depth, 4);
+		}
+
+		#[test]
+		fn test_wins_against_random() {
+			let mut random = Random::new(5);
+			for search in [MultiplayerSearch::Paranoid, MultiplayerSearch::MaxN] {
+				let mut game = MultiplayerConnectFour { search, ..board(&[], 1) };
+				while !game.finished() {
+					let column = if game.turn == 1 {
+						game.get_best_move(3)
+					} else {
+						let columns: Vec<usize> = (0..9).filter(|&c| game.available(c)).collect();
+						random.pick(&columns)
+					};
+					game.play(column);
+				}
+
+				assert_eq!(game.winner, 1);
+			}
+		}
+	}
+}
@@ -0,0 +1,284 @@
+use std::cmp;
+
+use napi::{Error, Result};
+
+use super::{ConnectFour, MAX_CELLS, OUTCOME_WIN};
+use crate::{napi_assert, search::options::parse_depth, Player};
+
+/// The score losses up to which a move is labelled good, an inaccuracy and a
+/// mistake, and a blunder past them. A line missing a single piece scores 100
+/// with the default weights.
+const GOOD_LOSS: i64 = 50;
+const INACCURACY_LOSS: i64 = 150;
+const MISTAKE_LOSS: i64 = 400;
+
+/// How a move of a reviewed game compares to the best one.
+#[napi]
+#[repr(u8)]
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "tune", derive(Clone, Copy))]
+pub enum MoveLabel {
+	/// The move scores as well as the best one, which includes every move of
+	/// a position that is lost anyway, however soon it loses.
+	Best,
+	/// The move loses a little of the score of the best one.
+	Good,
+	/// The move loses a noticeable part of the score of the best one, or
+	/// still forces a win, but slower than the best one.
+	Inaccuracy,
+	/// The move loses a large part of the score of the best one.
+	Mistake,
+	/// The move loses much of the score of the best one, or lets the opponent
+	/// force a win the best move avoided.
+	Blunder,
+	/// The best move forced a win, and the move does not.
+	MissedWin,
+}
+
+/// The options of `reviewGame`.
+#[napi(object)]
+#[derive(Debug, Default)]
+pub struct ReviewOptions {
+	/// The player who made the first move, defaults to `Player.Human`.
+	pub first: Option<Player>,
+	/// How many plies deep to search every position, defaults to 5.
+	pub depth: Option<i32>,
+}
+
+/// The review of a move of a game, as returned by `reviewGame`.
+#[napi(object)]
+#[derive(Debug, PartialEq, Eq)]
+pub struct MoveReview {
+	/// The player who made the move.
+	pub player: Player,
+	/// The move that was played, encoded like the ones of `getBestMove`.
+	pub played: u32,
+	/// The best move found by the search.
+	pub best: u32,
+	/// How much of the score of the best move the played one lost, from the
+	/// perspective of the player. Decisive outcomes score ±999999999, plus
+	/// the plies left to search when they happen, so faster wins score more.
+	pub loss: u32,
+	pub label: MoveLabel,
+}
+
+/// Labels a move scoring `played` where the best one scored `best`, both from
+/// the perspective of the player who moved.
+fn label(best: i32, played: i32) -> MoveLabel {
+	if played >= best || best <= -OUTCOME_WIN {
+		return MoveLabel::Best;
+	}
+
+	if best >= OUTCOME_WIN {
+		return if played >= OUTCOME_WIN { MoveLabel::Inaccuracy } else { MoveLabel::MissedWin };
+	}
+
+	if played <= -OUTCOME_WIN {
+		return MoveLabel::Blunder;
+	}
+
+	let loss = best as i64 - played as i64;
+	if loss <= GOOD_LOSS {
+		MoveLabel::Good
+	} else if loss <= INACCURACY_LOSS {
+		MoveLabel::Inaccuracy
+	} else if loss <= MISTAKE_LOSS {
+		MoveLabel::Mistake
+	} else {
+		MoveLabel::Blunder
+	}
+}
+
+impl ConnectFour {
+	/// Scores every move of `player` searching `maximum_depth` plies deep,
+	/// returning the moves with their scores, in search order.
+	fn score_moves(&mut self, player: Player, maximum_depth: u8) -> Vec<(usize, i32)> {
+		// Pops free cells up, so a PopOut game may outlast the empty cells:
+		let remaining =
+			if self.pop_out { cmp::min(MAX_CELLS as u8, maximum_depth) } else { cmp::min(self.empty, maximum_depth) };
+		self.prepare_search(remaining);
+
+		let (moves, count) = self.moves(player);
+		moves[..count].iter().map(|&mv| (mv, self.search_move(mv, player, remaining, -i32::MAX, i32::MAX))).collect()
+	}
+
+	/// Replays `moves` from this board, players taking turns from `first`,
+	/// and reviews every one of them against the best move found searching
+	/// `depth` plies deep.
+	///
+	/// Fails with the index of the first move that is not legal, or that is
+	/// played after the game ended.
+	pub(crate) fn review(
+		&self,
+		moves: &[usize],
+		first: Player,
+		depth: u8,
+	) -> std::result::Result<Vec<MoveReview>, String> {
+		let width = self.layout.size.width;
+		let mut board = self.snapshot();
+		let mut player = first;
+		let mut finished = board.winner != Player::Unset || !board.has_moves(player);
+
+		let mut reviews = Vec::with_capacity(moves.len());
+		for (index, &mv) in moves.iter().enumerate() {
+			if finished {
+				return Err(format!("move {index} was played after the game ended"));
+			}
+
+			let legal =
+				if mv < width { board.available(mv) } else { mv < 2 * width && board.can_pop(mv - width, player) };
+			if !legal {
+				return Err(format!("move {index} is not legal"));
+			}
+
+			let scores = board.score_moves(player, depth);
+			let &(best, best_score) =
+				scores.iter().fold(&scores[0], |max, score| if score.1 > max.1 { score } else { max });
+			let &(_, played_score) = scores.iter().find(|&&(m, _)| m == mv).unwrap();
+
+			reviews.push(MoveReview {
+				player,
+				played: mv as u32,
+				best: best as u32,
+				loss: (best_score as i64 - played_score as i64).max(0) as u32,
+				label: label(best_score, played_score),
+			});
+
+			board.winner = board.play_move(mv, player).unwrap_or(Player::Unset);
+			player = player.opponent();
+			finished = board.winner != Player::Unset || !board.has_moves(player);
+		}
+
+		Ok(reviews)
+	}
+}
+
+#[napi]
+impl ConnectFour {
+	/// Replays `moves` from this board, which is left unchanged, and reviews
+	/// every one of them against the best move found by the alpha-beta
+	/// search, for both players. The moves are encoded like the ones of
+	/// `getBestMove`, and the players take turns.
+	#[napi(js_name = "reviewGame")]
+	pub fn js_review_game(&self, moves: Vec<u32>, options: Option<ReviewOptions>) -> Result<Vec<MoveReview>> {
+		let options = options.unwrap_or_default();
+		let first = options.first.unwrap_or(Player::Human);
+		napi_assert!(first != Player::Unset);
+
+		let depth = parse_depth(options.depth, 5)?;
+		let moves: Vec<usize> = moves.into_iter().map(|mv| mv as usize).collect();
+		self.review(&moves, first, depth).map_err(Error::from_reason)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::games::connect_four::BOARD_CELLS;
+
+	fn empty() -> ConnectFour {
+		ConnectFour::new([Player::Unset; BOARD_CELLS])
+	}
+
+	mod label {
+		use super::*;
+
+		macro_rules! gen_test {
+			($($name:ident: [$best:expr, $played:expr, $outcome:expr],)*) => ($(
+				#[test]
+				fn $name() {
+					assert_eq!(label($best, $played), $outcome);
+				}
+			)*);
+		}
+
+		gen_test! {
+			test_best: [120, 120, MoveLabel::Best],
+			test_lost_anyway: [-OUTCOME_WIN, -OUTCOME_WIN, MoveLabel::Best],
+			test_lost_sooner: [-(OUTCOME_WIN + 1), -(OUTCOME_WIN + 3), MoveLabel::Best],
+			test_good: [120, 90, MoveLabel::Good],
+			test_inaccuracy: [120, 0, MoveLabel::Inaccuracy],
+			test_mistake: [120, -200, MoveLabel::Mistake],
+			test_blunder: [120, -400, MoveLabel::Blunder],
+			test_lets_opponent_win: [-30, -OUTCOME_WIN, MoveLabel::Blunder],
+			test_missed_win: [OUTCOME_WIN, 300, MoveLabel::MissedWin],
+			test_slower_win: [OUTCOME_WIN + 4, OUTCOME_WIN + 2, MoveLabel::Inaccuracy],
+		}
+	}
+
+	mod review {
+		use super::*;
+
+		#[test]
+		fn test_opening() {
+			let reviews = empty().review(&[3], Player::Human, 5).unwrap();
+
+			assert_eq!(reviews.len(), 1);
+			assert_eq!((reviews[0].player, reviews[0].played, reviews[0].best), (Player::Human, 3, 3));
+			assert_eq!((reviews[0].loss, reviews[0].label), (0, MoveLabel::Best));
+		}
+
+		#[test]
+		fn test_missed_win() {
+			// _ _ _ _ _ _ _ (28..35)
+			// M M M H H H _ (35..42), the human plays 1 instead of winning at 6
+			let reviews = empty().review(&[3, 2, 4, 1, 5, 0, 1], Player::Human, 5).unwrap();
+
+			assert_eq!(reviews[6].player, Player::Human);
+			assert_eq!((reviews[6].best, reviews[6].label), (6, MoveLabel::MissedWin));
+		}
+
+		#[test]
+		fn test_delayed_win() {
+			// _ _ _ M M _ _ (28..35)
+			// M _ _ H H H _ (35..42), the human plays 1, and still wins at 2 or 6
+			let reviews = empty().review(&[3, 3, 4, 4, 5, 0, 1], Player::Human, 5).unwrap();
+
+			// Every move of the machine loses to the double threat:
+			assert_eq!(reviews[5].label, MoveLabel::Best);
+
+			// But the human passed up the win at 2 or 6 for a slower one:
+			assert_eq!(reviews[6].best, 2);
+			assert_eq!(reviews[6].label, MoveLabel::Inaccuracy);
+			assert!(reviews[6].loss > 0);
+		}
+
+		#[test]
+		fn test_blunder() {
+			// _ _ _ _ _ _ _ (28..35)
+			// _ M M H H H _ (35..42), the machine plays 0 instead of blocking 6
+			let reviews = empty().review(&[3, 2, 4, 1, 5, 0], Player::Human, 5).unwrap();
+
+			assert_eq!(reviews[5].player, Player::Machine);
+			assert_eq!((reviews[5].best, reviews[5].label), (6, MoveLabel::Blunder));
+		}
+
+		#[test]
+		fn test_machine_first() {
+			let reviews = empty().review(&[3, 3], Player::Machine, 3).unwrap();
+
+			assert_eq!(reviews[0].player, Player::Machine);
+			assert_eq!(reviews[1].player, Player::Human);
+		}
+
+		#[test]
+		fn test_board_unchanged() {
+			let board = empty();
+			board.review(&[3, 3, 4], Player::Human, 3).unwrap();
+
+			assert!(board.cells().iter().all(|&c| c == Player::Unset));
+		}
+
+		#[test]
+		fn test_illegal_move() {
+			assert_eq!(empty().review(&[7], Player::Human, 3), Err("move 0 is not legal".into()));
+			assert_eq!(empty().review(&[0; 7], Player::Human, 3), Err("move 6 is not legal".into()));
+		}
+
+		#[test]
+		fn test_move_after_win() {
+			let moves = [3, 0, 3, 0, 3, 0, 3, 0];
+			assert_eq!(empty().review(&moves, Player::Human, 3), Err("move 7 was played after the game ended".into()));
+		}
+	}
+}
@@ -0,0 +1,354 @@
+use std::simd::cmp::SimdPartialEq;
+
+use napi::{Error, Result};
+
+use super::{layout::Direction, ConnectFour, Window, MAX_CONNECT, OUTSIDE};
+use crate::{napi_assert, Player};
+
+/// An empty cell that completes a line of a player, as found by
+/// `analyzeThreats`.
+#[napi(object)]
+#[derive(Debug, PartialEq, Eq)]
+pub struct Threat {
+	pub cell: u32,
+	pub column: u32,
+	/// The row of the cell counted from the bottom, starting at 1, whose
+	/// parity decides which player the threat is good for.
+	pub row: u32,
+	/// Whether a piece can be dropped on the cell right away, otherwise the
+	/// threat waits for the column to fill up to it.
+	pub playable: bool,
+}
+
+/// Two threats of a player the opponent cannot both block, as found by
+/// `analyzeThreats`. Three threats playable right away make three of them, one
+/// per pair.
+#[napi(object)]
+#[derive(Debug, PartialEq, Eq)]
+pub struct DoubleThreat {
+	/// The cells of the threats, the lowest first.
+	pub cells: Vec<u32>,
+	/// Whether the threats are on top of each other in a column, so blocking
+	/// the lower one lets the player complete the upper one. Otherwise, they
+	/// are both playable right away.
+	pub stacked: bool,
+}
+
+/// The threats of a player, as found by `analyzeThreats`.
+#[napi(object)]
+#[derive(Debug, PartialEq, Eq)]
+pub struct PlayerThreats {
+	/// The threats of the player, by cell.
+	pub threats: Vec<Threat>,
+	/// The amount of threats in an odd row, which the first player wants.
+	pub odd: u32,
+	/// The amount of threats in an even row, which the second player wants.
+	pub even: u32,
+	/// Every pair of threats that makes a double threat.
+	pub double_threats: Vec<DoubleThreat>,
+}
+
+/// The threats of both players of a [`ConnectFour`] board.
+#[napi(object)]
+#[derive(Debug, PartialEq, Eq)]
+pub struct ThreatAnalysis {
+	pub human: PlayerThreats,
+	pub machine: PlayerThreats,
+	/// The player who controls the zugzwang, and gets to complete their
+	/// threats once the board fills up. `Player.Unset` in PopOut games and on
+	/// boards of an odd height, where the parity rules do not hold.
+	pub zugzwang: Player,
+}
+
+impl ConnectFour {
+	/// The empty cells that complete a line of `player`, as a bit per cell,
+	/// from the windows holding a single empty cell and pieces of `player`
+	/// otherwise.
+	pub(crate) fn threats(&self, player: Player) -> u128 {
+		debug_assert_ne!(player, Player::Unset);
+
+		const MASK_EMPTY: Window = Window::from_array([Player::Unset as u8; MAX_CONNECT]);
+
+		let connect = self.layout.size.connect;
+		let mask = Window::splat(player as u8);
+
+		let mut threats = 0;
+		for direction in Direction::ALL {
+			for cells in &self.layout.windows[direction as usize] {
+				let mut window = [OUTSIDE; MAX_CONNECT];
+				for (lane, &offset) in window[..connect].iter_mut().zip(cells) {
+					*lane = self.cells[offset as usize] as u8;
+				}
+
+				let window = Window::from_array(window);
+				let empty = window.simd_eq(MASK_EMPTY).to_bitmask();
+				if empty.count_ones() == 1 && window.simd_eq(mask).to_bitmask().count_ones() as usize == connect - 1 {
+					threats |= 1 << cells[empty.trailing_zeros() as usize];
+				}
+			}
+		}
+
+		threats
+	}
+
+	/// Lists the threats of `player`, with their parity and the double threats
+	/// they make.
+	fn player_threats(&self, player: Player) -> PlayerThreats {
+		let width = self.layout.size.width;
+		let height = self.layout.size.height;
+		let cells = self.threats(player);
+
+		let threats: Vec<Threat> = (0..self.layout.size.cells())
+			.filter(|&offset| cells & 1 << offset != 0)
+			.map(|offset| {
+				let (row, column) = (offset / width, offset % width);
+				Threat {
+					cell: offset as u32,
+					column: column as u32,
+					row: (height - row) as u32,
+					playable: self.remaining[column] as usize == row + 1,
+				}
+			})
+			.collect();
+
+		// Every two threats playable right away make a double threat, the lowest
+		// one first:
+		let mut double_threats = Vec::new();
+		let playable: Vec<&Threat> = threats.iter().filter(|t| t.playable).collect();
+		for (index, a) in playable.iter().enumerate() {
+			for b in &playable[index + 1..] {
+				let (low, high) = if (b.row, b.column) < (a.row, a.column) { (b, a) } else { (a, b) };
+				double_threats.push(DoubleThreat { cells: vec![low.cell, high.cell], stacked: false });
+			}
+		}
+
+		for threat in &threats {
+			let cell = threat.cell as usize;
+			if cell >= width && cells & 1 << (cell - width) != 0 {
+				double_threats.push(DoubleThreat { cells: vec![cell as u32, (cell - width) as u32], stacked: true });
+			}
+		}
+		double_threats.sort_by_key(|double| (double.stacked, double.cells[0], double.cells[1]));
+
+		let odd = threats.iter().filter(|t| t.row % 2 == 1).count() as u32;
+		PlayerThreats { even: threats.len() as u32 - odd, odd, threats, double_threats }
+	}
+
+	/// Returns the player who controls the zugzwang when `first` moved first,
+	/// by the simplified parity rules of the boards of an even height: the
+	/// first player controls it with an odd threat that has no threat of the
+	/// second player below it in its column, and the second player otherwise.
+	fn zugzwang(&self, first: Player, threats: &PlayerThreats, opponent_threats: &PlayerThreats) -> Player {
+		if self.pop_out || self.layout.size.height % 2 == 1 {
+			return Player::Unset;
+		}
+
+		let controls = threats
+			.threats
+			.iter()
+			.filter(|t| t.row % 2 == 1)
+			.any(|t| !opponent_threats.threats.iter().any(|o| o.column == t.column && o.row < t.row));
+
+		if controls {
+			first
+		} else {
+			first.opponent()
+		}
+	}
+
+	/// Analyses the threats of both players, `first` being the player who
+	/// moved first.
+	pub(crate) fn analyze_threats(&self, first: Player) -> ThreatAnalysis {
+		let human = self.player_threats(Player::Human);
+		let machine = self.player_threats(Player::Machine);
+		let zugzwang = match first {
+			Player::Human => self.zugzwang(first, &human, &machine),
+			_ => self.zugzwang(first, &machine, &human),
+		};
+
+		ThreatAnalysis { human, machine, zugzwang }
+	}
+}
+
+#[napi]
+impl ConnectFour {
+	/// Lists the threats of both players: the empty cells that complete one of
+	/// their lines, whether they are in an odd or an even row, the double
+	/// threats they make, and who controls the zugzwang. `first` is the player
+	/// who moved first, which defaults to `Player.Human`.
+	#[napi(js_name = "analyzeThreats")]
+	pub fn js_analyze_threats(&self, first: Option<Player>) -> Result<ThreatAnalysis> {
+		let first = first.unwrap_or(Player::Human);
+		napi_assert!(first != Player::Unset);
+
+		Ok(self.analyze_threats(first))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::games::connect_four::tests::board;
+
+	fn cells(threats: &PlayerThreats) -> Vec<u32> {
+		threats.threats.iter().map(|t| t.cell).collect()
+	}
+
+	mod threats {
+		use super::*;
+
+		#[test]
+		fn test_empty() {
+			let board = board(&[], &[]);
+
+			assert_eq!(board.threats(Player::Human), 0);
+			assert_eq!(board.threats(Player::Machine), 0);
+		}
+
+		#[test]
+		fn test_horizontal() {
+			// _ _ _ _ _ _ _ (28..35)
+			// H H H _ M _ _ (35..42)
+			let board = board(&[35, 36, 37], &[39]);
+
+			assert_eq!(board.threats(Player::Human), 1 << 38);
+			assert_eq!(board.threats(Player::Machine), 0);
+		}
+
+		#[test]
+		fn test_both_ends() {
+			// _ _ _ _ _ _ _ (28..35)
+			// _ H H H _ _ _ (35..42)
+			let board = board(&[36, 37, 38], &[]);
+
+			assert_eq!(board.threats(Player::Human), 1 << 35 | 1 << 39);
+		}
+
+		#[test]
+		fn test_gap() {
+			// _ _ _ _ _ _ _ (28..35)
+			// H _ H H _ _ _ (35..42)
+			let board = board(&[35, 37, 38], &[]);
+
+			assert_eq!(board.threats(Player::Human), 1 << 36);
+		}
+
+		#[test]
+		fn test_vertical() {
+			let board = board(&[24, 31, 38], &[]);
+
+			assert_eq!(board.threats(Player::Human), 1 << 17);
+		}
+	}
+
+	mod analyze_threats {
+		use super::*;
+
+		#[test]
+		fn test_playable() {
+			let analysis = board(&[35, 36, 37], &[39]).analyze_threats(Player::Human);
+
+			assert_eq!(analysis.human.threats, vec![Threat { cell: 38, column: 3, row: 1, playable: true }]);
+			assert_eq!((analysis.human.odd, analysis.human.even), (1, 0));
+			assert!(analysis.machine.threats.is_empty());
+		}
+
+		#[test]
+		fn test_future() {
+			// _ _ _ _ _ _ _ (21..28)
+			// H H H _ _ _ _ (28..35)
+			// M M M _ H _ _ (35..42)
+			let analysis = board(&[28, 29, 30, 39], &[35, 36, 37]).analyze_threats(Player::Human);
+
+			assert_eq!(analysis.human.threats, vec![Threat { cell: 31, column: 3, row: 2, playable: false }]);
+			assert_eq!(analysis.machine.threats, vec![Threat { cell: 38, column: 3, row: 1, playable: true }]);
+			assert_eq!((analysis.human.odd, analysis.human.even), (0, 1));
+		}
+
+		#[test]
+		fn test_playable_double_threat() {
+			// _ _ _ _ _ _ _ (28..35)
+			// _ H H H _ _ _ (35..42)
+			let analysis = board(&[36, 37, 38], &[]).analyze_threats(Player::Human);
+
+			assert_eq!(analysis.human.double_threats, vec![DoubleThreat { cells: vec![35, 39], stacked: false }]);
+		}
+
+		#[test]
+		fn test_three_playable_threats() {
+			// _ _ _ _ _ _ _ (14..21)
+			// _ _ _ _ _ _ H (21..28)
+			// _ _ _ _ _ _ H (28..35)
+			// _ H H H _ _ H (35..42)
+			let analysis = board(&[36, 37, 38, 27, 34, 41], &[]).analyze_threats(Player::Human);
+
+			assert_eq!(cells(&analysis.human), vec![20, 35, 39]);
+			assert_eq!(
+				analysis.human.double_threats,
+				vec![
+					DoubleThreat { cells: vec![35, 20], stacked: false },
+					DoubleThreat { cells: vec![35, 39], stacked: false },
+					DoubleThreat { cells: vec![39, 20], stacked: false },
+				]
+			);
+		}
+
+		#[test]
+		fn test_stacked_double_threat() {
+			// _ _ _ _ _ _ _ (14..21)
+			// H H H _ _ _ _ (21..28)
+			// H H H _ _ _ _ (28..35)
+			// M M M _ _ M M (35..42)
+			let analysis = board(&[21, 22, 23, 28, 29, 30], &[35, 36, 37, 40, 41]).analyze_threats(Player::Human);
+
+			assert_eq!(cells(&analysis.human), vec![24, 31]);
+			assert_eq!(analysis.human.double_threats, vec![DoubleThreat { cells: vec![31, 24], stacked: true }]);
+		}
+
+		#[test]
+		fn test_zugzwang_second_player_by_default() {
+			assert_eq!(board(&[], &[]).analyze_threats(Player::Human).zugzwang, Player::Machine);
+			assert_eq!(board(&[], &[]).analyze_threats(Player::Machine).zugzwang, Player::Human);
+		}
+
+		#[test]
+		fn test_zugzwang_odd_threat() {
+			// _ _ _ _ _ _ _ (14..21)
+			// H H H _ _ _ _ (21..28)
+			// H M H _ _ _ _ (28..35)
+			// M H M _ _ _ _ (35..42)
+			let board = board(&[21, 22, 23, 28, 30, 36], &[29, 35, 37]);
+
+			// The human threat at 24 is in the third row, which the first player
+			// wants:
+			let analysis = board.analyze_threats(Player::Human);
+			assert_eq!(analysis.human.threats, vec![Threat { cell: 24, column: 3, row: 3, playable: false }]);
+			assert_eq!(analysis.zugzwang, Player::Human);
+
+			// When the machine moved first the threat plays no part: the machine
+			// has no odd threat, so the human controls it as the second player.
+			assert_eq!(board.analyze_threats(Player::Machine).zugzwang, Player::Human);
+		}
+
+		#[test]
+		fn test_zugzwang_threat_below() {
+			// _ _ _ _ _ _ _ (14..21)
+			// H H H _ _ _ _ (21..28)
+			// H M H _ _ _ _ (28..35)
+			// M H M _ M M M (35..42)
+			let board = board(&[21, 22, 23, 28, 30, 36], &[29, 35, 37, 39, 40, 41]);
+
+			// The machine completes its line at 38 before the column reaches 24:
+			let analysis = board.analyze_threats(Player::Human);
+			assert_eq!(cells(&analysis.machine), vec![38]);
+			assert_eq!(analysis.zugzwang, Player::Machine);
+		}
+
+		#[test]
+		fn test_zugzwang_pop_out() {
+			let board = ConnectFour { pop_out: true, ..board(&[21, 22, 23, 28, 30, 36], &[29, 35, 37]) };
+
+			assert_eq!(board.analyze_threats(Player::Human).zugzwang, Player::Unset);
+		}
+	}
+}
@@ -0,0 +1,9 @@
+// This file is generated by the evaluation tuner, do not edit it manually.
+//
+// To regenerate it, run the `tune` binary with the `tune` feature enabled:
+// cargo run --release --features tune --bin tune -- --output <this file>
+
+use super::Weights;
+
+/// The weights compiled in as the default evaluation of [`super::ConnectFour`].
+pub const DEFAULT_WEIGHTS: Weights = Weights { center: 14, three: 96, two: 10, opponent_three: -98, opponent_two: -13 };
@@ -0,0 +1,890 @@
+use std::time::Instant;
+
+use napi::{bindgen_prelude::Uint8Array, Error, Result};
+
+use crate::{
+	isize_to_usize, napi_assert,
+	search::{
+		alpha_beta::{AlphaBeta, Evaluate},
+		game::{Game, Outcome},
+		mcts::{MonteCarlo, MonteCarloOptions},
+		options::{BestMoveArgument, BestMoveOptions},
+		random::Random,
+		stats::{SearchStats, Stats},
+	},
+	Engine, Player, U_INVALID_INDEX,
+};
+
+/// The range of boxes per row and per column, so every edge fits in a `u128`.
+const MIN_SIZE: u32 = 1;
+const MAX_SIZE: u32 = 7;
+
+/// The evaluation weight of every box, and of being on the right side of the
+/// long chain rule before the board splits into chains.
+const BOX_WEIGHT: i32 = 8;
+const PARITY_WEIGHT: i32 = 12;
+
+/// The amount of boxes a chain needs so that its owner can keep control by
+/// declining its last two boxes.
+const LONG_CHAIN: u8 = 3;
+
+/// The options of a [`DotsAndBoxes`] board.
+#[napi(object)]
+#[derive(Debug, Default)]
+pub struct DotsAndBoxesOptions {
+	/// The amount of boxes per row, between 1 and 7, defaults to 3.
+	pub width: Option<u32>,
+	/// The amount of boxes per column, between 1 and 7, defaults to 3.
+	pub height: Option<u32>,
+	/// The player to move, defaults to the human.
+	pub turn: Option<Player>,
+}
+
+/// A group of unclaimed boxes linked by their undrawn edges, once every box
+/// has at most two of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Component {
+	size: u8,
+	/// Whether the boxes form a cycle, which costs four boxes to decline
+	/// instead of two.
+	looped: bool,
+	/// The amount of boxes that can be taken right away.
+	capturable: u8,
+	/// The edge opening the component with the least help to the opponent:
+	/// the middle of a pair of boxes, otherwise an end of the chain.
+	opening: u8,
+}
+
+/// A board alongside the player to move, as searched by both engines.
+///
+/// The horizontal edges come first, row by row from the top, followed by the
+/// vertical edges, row by row from the left.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Position {
+	width: usize,
+	height: usize,
+	edges: u128,
+	human: u64,
+	machine: u64,
+	player: Player,
+}
+
+impl Position {
+	fn new(width: usize, height: usize, player: Player) -> Self {
+		Self { width, height, edges: 0, human: 0, machine: 0, player }
+	}
+
+	/// Returns a copy of this position with `player` to move.
+	fn with_player(&self, player: Player) -> Self {
+		Self { player, ..self.clone() }
+	}
+
+	fn horizontal_edges(&self) -> usize {
+		(self.height + 1) * self.width
+	}
+
+	fn edge_count(&self) -> usize {
+		self.horizontal_edges() + self.height * (self.width + 1)
+	}
+
+	fn box_count(&self) -> usize {
+		self.width * self.height
+	}
+
+	fn drawn(&self, edge: usize) -> bool {
+		self.edges & (1 << edge) != 0
+	}
+
+	fn available(&self, edge: usize) -> bool {
+		edge < self.edge_count() && !self.drawn(edge) && self.outcome().is_none()
+	}
+
+	/// Returns the top, bottom, left, and right edges of a box.
+	fn box_edges(&self, b: usize) -> [usize; 4] {
+		let (row, column) = (b / self.width, b % self.width);
+		let left = self.horizontal_edges() + row * (self.width + 1) + column;
+		[b, b + self.width, left, left + 1]
+	}
+
+	/// Returns the boxes on both sides of an edge, [`None`] standing for the
+	/// outside of the board.
+	fn edge_boxes(&self, edge: usize) -> [Option<usize>; 2] {
+		let horizontal = self.horizontal_edges();
+		if edge < horizontal {
+			let row = edge / self.width;
+			[(row > 0).then(|| edge - self.width), (row < self.height).then_some(edge)]
+		} else {
+			let (row, column) = ((edge - horizontal) / (self.width + 1), (edge - horizontal) % (self.width + 1));
+			let b = row * self.width + column;
+			[(column > 0).then(|| b - 1), (column < self.width).then_some(b)]
+		}
+	}
+
+	/// Returns the box across `edge` from `b`.
+	fn neighbour(&self, b: usize, edge: usize) -> Option<usize> {
+		self.edge_boxes(edge).into_iter().flatten().find(|&other| other != b)
+	}
+
+	/// Returns the undrawn edges of a box.
+	fn open_edges(&self, b: usize) -> impl Iterator<Item = usize> + '_ {
+		self.box_edges(b).into_iter().filter(|&edge| !self.drawn(edge))
+	}
+
+	fn open_sides(&self, b: usize) -> usize {
+		self.open_edges(b).count()
+	}
+
+	fn claimed(&self) -> u64 {
+		self.human | self.machine
+	}
+
+	pub fn score(&self, player: Player) -> u32 {
+		match player {
+			Player::Human => self.human.count_ones(),
+			Player::Machine => self.machine.count_ones(),
+			Player::Unset => 0,
+		}
+	}
+
+	fn owner(&self, b: usize) -> Player {
+		if self.human & (1 << b) != 0 {
+			Player::Human
+		} else if self.machine & (1 << b) != 0 {
+			Player::Machine
+		} else {
+			Player::Unset
+		}
+	}
+
+	/// Whether drawing `edge` completes a box.
+	fn captures(&self, edge: usize) -> bool {
+		self.edge_boxes(edge).into_iter().flatten().any(|b| self.open_sides(b) == 1)
+	}
+
+	/// Whether drawing `edge` leaves no box with three sides to the opponent.
+	fn safe(&self, edge: usize) -> bool {
+		self.edge_boxes(edge).into_iter().flatten().all(|b| self.open_sides(b) != 2)
+	}
+
+	/// Returns the edge declining the last boxes of the chain ending at the
+	/// capturable box `b`, leaving them to the opponent in a single move so the
+	/// player keeps control: the far edge of a pair, or the middle edge of the
+	/// last four boxes of a loop.
+	fn decline(&self, b: usize) -> Option<usize> {
+		let edge = self.open_edges(b).next()?;
+		let next = self.neighbour(b, edge).filter(|&next| self.open_sides(next) == 2)?;
+		let far = self.open_edges(next).find(|&other| other != edge)?;
+		match self.neighbour(next, far) {
+			None => Some(far),
+			Some(after) if self.open_sides(after) > 2 => Some(far),
+			Some(after) if self.open_sides(after) == 2 => {
+				let last = self.open_edges(after).find(|&other| other != far)?;
+				self.neighbour(after, last).filter(|&end| self.open_sides(end) == 1 && end != b).map(|_| far)
+			}
+			Some(_) => None,
+		}
+	}
+
+	/// Splits the unclaimed boxes into chains and loops, or returns [`None`] if
+	/// a box still has three or four undrawn edges, joining several of them.
+	fn components(&self) -> Option<Vec<Component>> {
+		let unclaimed = (0..self.box_count()).filter(|&b| self.claimed() & (1 << b) == 0);
+		if unclaimed.clone().any(|b| self.open_sides(b) > 2) {
+			return None;
+		}
+
+		let mut components = Vec::new();
+		let mut visited = self.claimed();
+		for start in unclaimed {
+			if visited & (1 << start) != 0 {
+				continue;
+			}
+
+			let mut component = Component { size: 0, looped: true, capturable: 0, opening: 0 };
+			let (mut ground, mut inner) = (None, None);
+			let mut stack = vec![start];
+			visited |= 1 << start;
+			while let Some(b) = stack.pop() {
+				component.size += 1;
+				if self.open_sides(b) == 1 {
+					component.capturable += 1;
+					component.looped = false;
+				}
+
+				for edge in self.open_edges(b) {
+					match self.neighbour(b, edge) {
+						None => {
+							component.looped = false;
+							ground.get_or_insert(edge);
+						}
+						Some(next) => {
+							inner.get_or_insert(edge);
+							if visited & (1 << next) == 0 {
+								visited |= 1 << next;
+								stack.push(next);
+							}
+						}
+					}
+				}
+			}
+
+			let opening = if component.size == 2 { inner } else { ground.or(inner) };
+			component.opening = opening.unwrap_or_default() as u8;
+			components.push(component);
+		}
+
+		Some(components)
+	}
+
+	/// Returns the amount of long chains formed by the boxes with exactly two
+	/// undrawn edges, which are joined by the other ones.
+	fn long_chains(&self) -> i32 {
+		let mut chains = 0;
+		let mut visited = self.claimed();
+		for start in 0..self.box_count() {
+			if visited & (1 << start) != 0 || self.open_sides(start) != 2 {
+				continue;
+			}
+
+			let mut size = 0;
+			let mut stack = vec![start];
+			visited |= 1 << start;
+			while let Some(b) = stack.pop() {
+				size += 1;
+				for edge in self.open_edges(b) {
+					if let Some(next) = self.neighbour(b, edge) {
+						if visited & (1 << next) == 0 && self.open_sides(next) == 2 {
+							visited |= 1 << next;
+							stack.push(next);
+						}
+					}
+				}
+			}
+
+			if size >= LONG_CHAIN {
+				chains += 1;
+			}
+		}
+
+		chains
+	}
+}
+
+/// Returns the net amount of boxes the player to move wins from the remaining
+/// chains and loops, assuming both players take every box offered unless
+/// declining the last ones keeps control.
+fn endgame(components: &[Component]) -> i32 {
+	let opened = components.iter().filter(|c| c.capturable > 0);
+	let taken: i32 = opened.clone().map(|c| c.size as i32).sum();
+	let cost = opened
+		.filter_map(|c| match c.capturable {
+			1 if c.size >= 2 => Some(4),
+			2 if c.size >= 4 => Some(8),
+			_ => None,
+		})
+		.min();
+
+	let mut chains = components.iter().filter(|c| c.capturable == 0 && !c.looped).map(|c| c.size).collect::<Vec<_>>();
+	let mut loops = components.iter().filter(|c| c.capturable == 0 && c.looped).map(|c| c.size).collect::<Vec<_>>();
+	chains.sort_unstable();
+	loops.sort_unstable();
+
+	let value = opening_value(&chains, &loops);
+	match cost {
+		Some(cost) if !chains.is_empty() || !loops.is_empty() => (taken + value).max(taken - cost - value),
+		_ => taken + value,
+	}
+}
+
+/// Returns the net amount of boxes won by the player who has to open one of
+/// the chains or loops, which are sorted by size. The player opens the
+/// smallest chain or the smallest loop, and the opponent either takes every
+/// box and opens the next one, or keeps control by declining the last two
+/// boxes of a long chain or the last four of a loop.
+fn opening_value(chains: &[u8], loops: &[u8]) -> i32 {
+	// values[i][j] is the value with chains[i..] and loops[j..] left:
+	let mut values = vec![vec![0; loops.len() + 1]; chains.len() + 1];
+	for i in (0..=chains.len()).rev() {
+		for j in (0..=loops.len()).rev() {
+			let mut options = Vec::with_capacity(2);
+			if i < chains.len() {
+				let (size, rest) = (chains[i] as i32, values[i + 1][j]);
+				let declined = if chains[i] >= LONG_CHAIN { size - 4 - rest } else { i32::MIN };
+				options.push(-(size + rest).max(declined));
+			}
+
+			if j < loops.len() {
+				let (size, rest) = (loops[j] as i32, values[i][j + 1]);
+				options.push(-(size + rest).max(size - 8 - rest));
+			}
+
+			values[i][j] = options.into_iter().max().unwrap_or_default();
+		}
+	}
+
+	values[0][0]
+}
+
+impl Game for Position {
+	type Move = u8;
+
+	fn player(&self) -> Player {
+		self.player
+	}
+
+	/// Captures are played first, and are the only moves alongside declining
+	/// the end of a chain. Once the board is split into chains and loops, a
+	/// single opening is tried for every distinct one. Otherwise, boxes are
+	/// only given away once every edge does, the evaluation of the long chain
+	/// rule standing for the sacrifices fighting for control.
+	fn moves(&self) -> Vec<u8> {
+		if self.outcome().is_some() {
+			return Vec::new();
+		}
+
+		let edges = (0..self.edge_count()).filter(|&edge| !self.drawn(edge));
+		let mut moves = edges.clone().filter(|&edge| self.captures(edge)).map(|edge| edge as u8).collect::<Vec<_>>();
+		if !moves.is_empty() {
+			for b in (0..self.box_count()).filter(|&b| self.claimed() & (1 << b) == 0 && self.open_sides(b) == 1) {
+				if let Some(edge) = self.decline(b) {
+					if !moves.contains(&(edge as u8)) {
+						moves.push(edge as u8);
+					}
+				}
+			}
+
+			return moves;
+		}
+
+		if let Some(components) = self.components() {
+			let mut seen = Vec::new();
+			for component in components {
+				if !seen.contains(&(component.size, component.looped)) {
+					seen.push((component.size, component.looped));
+					moves.push(component.opening);
+				}
+			}
+
+			return moves;
+		}
+
+		let safe = edges.clone().filter(|&edge| self.safe(edge)).map(|edge| edge as u8).collect::<Vec<_>>();
+		if safe.is_empty() {
+			edges.map(|edge| edge as u8).collect()
+		} else {
+			safe
+		}
+	}
+
+	/// Draws an edge, claiming every box it completes for the player to move,
+	/// who then moves again.
+	fn play(&mut self, edge: u8) {
+		let edge = edge as usize;
+		self.edges |= 1 << edge;
+
+		let mut completed = false;
+		for b in self.edge_boxes(edge).into_iter().flatten() {
+			if self.open_sides(b) == 0 {
+				match self.player {
+					Player::Human => self.human |= 1 << b,
+					_ => self.machine |= 1 << b,
+				}
+				completed = true;
+			}
+		}
+
+		if !completed {
+			self.player = self.player.opponent();
+		}
+	}
+
+	fn outcome(&self) -> Option<Outcome> {
+		if self.claimed().count_ones() as usize != self.box_count() {
+			return None;
+		}
+
+		Some(match self.human.count_ones().cmp(&self.machine.count_ones()) {
+			std::cmp::Ordering::Greater => Outcome::Win(Player::Human),
+			std::cmp::Ordering::Less => Outcome::Win(Player::Machine),
+			std::cmp::Ordering::Equal => Outcome::Draw,
+		})
+	}
+
+	/// Takes a box if there is one, otherwise draws a random safe edge.
+	fn heuristic_move(&mut self, moves: &[u8], random: &mut Random) -> Option<u8> {
+		if let Some(&edge) = moves.iter().find(|&&edge| self.captures(edge as usize)) {
+			return Some(edge);
+		}
+
+		let safe = moves.iter().copied().filter(|&edge| self.safe(edge as usize)).collect::<Vec<_>>();
+		(!safe.is_empty()).then(|| random.pick(&safe))
+	}
+}
+
+impl Evaluate for Position {
+	/// Scores the boxes taken, and the boxes left once the board is split into
+	/// chains and loops. Before that, follows the long chain rule: the player
+	/// who moves after the last turn change takes the last chain, and there
+	/// is one turn change per drawn edge that does not complete a box, minus
+	/// the double-crosses declining all but the last long chain.
+	fn evaluate(&self) -> i32 {
+		let opponent = self.player.opponent();
+		let boxes = BOX_WEIGHT * (self.score(self.player) as i32 - self.score(opponent) as i32);
+		if let Some(components) = self.components() {
+			return boxes + BOX_WEIGHT * endgame(&components);
+		}
+
+		let undrawn = (self.edge_count() - self.edges.count_ones() as usize) as i32;
+		let unclaimed = (self.box_count() - self.claimed().count_ones() as usize) as i32;
+		let turns = undrawn - unclaimed + (self.long_chains() - 1).max(0);
+		if turns % 2 == 0 {
+			boxes + PARITY_WEIGHT
+		} else {
+			boxes - PARITY_WEIGHT
+		}
+	}
+}
+
+/// Dots and Boxes, where players take turns drawing an edge between two
+/// adjacent dots, and completing a box claims it and grants another move. The
+/// player with the most boxes wins once every edge is drawn.
+#[napi]
+pub struct DotsAndBoxes {
+	position: Position,
+	mcts: Option<Box<MonteCarlo<Position>>>,
+	/// The counters of the last search.
+	stats: Stats,
+}
+
+impl DotsAndBoxes {
+	pub fn new(position: Position) -> Self {
+		Self { position, mcts: None, stats: Stats::default() }
+	}
+
+	/// Returns the best edge for `player` using the selected engine, the depth
+	/// is only used by the alpha-beta engine. The search hands `player` the
+	/// turn if it is not theirs.
+	pub fn get_best_move(&mut self, maximum_depth: u8, player: Player) -> usize {
+		let start = Instant::now();
+
+		let handed;
+		let position = if player == self.position.player {
+			&self.position
+		} else {
+			handed = self.position.with_player(player);
+			&handed
+		};
+
+		let edge = if let Some(mut mcts) = self.mcts.take() {
+			let edge = mcts.search(position);
+			self.stats = mcts.stats();
+			self.mcts = Some(mcts);
+			edge
+		} else {
+			let mut search = AlphaBeta::default();
+			let edge = search.search(position, maximum_depth);
+			self.stats = search.stats();
+			edge
+		};
+
+		self.stats.elapsed = start.elapsed();
+		edge.map_or(U_INVALID_INDEX, usize::from)
+	}
+}
+
+#[napi]
+impl DotsAndBoxes {
+	/// Creates a board from a bitmap of its drawn edges and the owner of every
+	/// box, row by row, which defaults to an empty board. A box is owned
+	/// exactly when its four edges are drawn.
+	#[napi(constructor)]
+	pub fn js_new(
+		edges: Option<Uint8Array>,
+		boxes: Option<Uint8Array>,
+		options: Option<DotsAndBoxesOptions>,
+	) -> Result<Self> {
+		let options = options.unwrap_or_default();
+		let (width, height) = (options.width.unwrap_or(3), options.height.unwrap_or(3));
+		if !(MIN_SIZE..=MAX_SIZE).contains(&width) || !(MIN_SIZE..=MAX_SIZE).contains(&height) {
+			return Err(Error::from_reason(format!("width and height must be between {MIN_SIZE} and {MAX_SIZE}")));
+		}
+
+		let turn = options.turn.unwrap_or(Player::Human);
+		napi_assert!(turn != Player::Unset);
+
+		let mut position = Position::new(width as usize, height as usize, turn);
+		if let Some(v) = edges {
+			let input = v.to_vec();
+			if input.len() != position.edge_count().div_ceil(8) {
+				return Err(Error::from_reason(format!(
+					"edges must have exactly {} numbers",
+					position.edge_count().div_ceil(8)
+				)));
+			}
+
+			position.edges = input.iter().rev().fold(0, |edges, &byte| edges << 8 | byte as u128);
+			napi_assert!(position.edges >> position.edge_count() == 0);
+		}
+
+		if let Some(v) = boxes {
+			let input = v.to_vec();
+			if input.len() != position.box_count() {
+				return Err(Error::from_reason(format!("boxes must have exactly {} numbers", position.box_count())));
+			}
+
+			for (b, &value) in input.iter().enumerate() {
+				match Player::try_from(value).map_err(Error::from_reason)? {
+					Player::Human => position.human |= 1 << b,
+					Player::Machine => position.machine |= 1 << b,
+					Player::Unset => {}
+				}
+			}
+		}
+
+		for b in 0..position.box_count() {
+			if (position.open_sides(b) == 0) != (position.owner(b) != Player::Unset) {
+				return Err(Error::from_reason("boxes must be owned exactly when their four edges are drawn"));
+			}
+		}
+
+		Ok(DotsAndBoxes::new(position))
+	}
+
+	/// The amount of boxes per row.
+	#[napi(getter = width)]
+	pub fn js_get_width(&self) -> u32 {
+		self.position.width as u32
+	}
+
+	/// The amount of boxes per column.
+	#[napi(getter = height)]
+	pub fn js_get_height(&self) -> u32 {
+		self.position.height as u32
+	}
+
+	/// The bitmap of the drawn edges, the edge `i` being the bit `i % 8` of the
+	/// byte `i / 8`. The horizontal edges come first, row by row from the
+	/// top, followed by the vertical edges, row by row from the left.
+	#[napi(getter = edges)]
+	pub fn js_get_edges(&self) -> Uint8Array {
+		let bytes = self.position.edge_count().div_ceil(8);
+		Uint8Array::new(self.position.edges.to_le_bytes()[..bytes].to_vec())
+	}
+
+	/// The owner of every box, row by row, `Player.Unset` if it is not
+	/// complete.
+	#[napi(getter = boxes)]
+	pub fn js_get_boxes(&self) -> Uint8Array {
+		Uint8Array::new((0..self.position.box_count()).map(|b| self.position.owner(b).into()).collect())
+	}
+
+	/// The player to move, which moves again after completing a box.
+	#[napi(getter = turn)]
+	pub fn js_get_turn(&self) -> Player {
+		self.position.player
+	}
+
+	/// The amount of boxes owned by `player`.
+	#[napi(js_name = "score")]
+	pub fn js_score(&self, player: Player) -> u32 {
+		self.position.score(player)
+	}
+
+	#[napi(js_name = "available")]
+	pub fn js_available(&self, edge: i32) -> Result<bool> {
+		Ok(self.position.available(isize_to_usize!(edge, self.position.edge_count())?))
+	}
+
+	#[napi(getter = finished)]
+	pub fn js_finished(&self) -> bool {
+		self.position.outcome().is_some()
+	}
+
+	/// The player who won, `Player.Unset` while the game is ongoing or if it
+	/// was drawn.
+	#[napi(getter = winner)]
+	pub fn js_get_winner(&self) -> Player {
+		match self.position.outcome() {
+			Some(Outcome::Win(player)) => player,
+			_ => Player::Unset,
+		}
+	}
+
+	/// Draws `edge` for the player to move, returning whether it won the game.
+	#[napi(js_name = "add")]
+	pub fn js_add(&mut self, edge: i32) -> Result<bool> {
+		let e = isize_to_usize!(edge, self.position.edge_count())?;
+		napi_assert!(self.position.available(e));
+
+		let player = self.position.player;
+		self.position.play(e as u8);
+		if let Some(mcts) = self.mcts.as_mut() {
+			mcts.advance(e as u8);
+		}
+
+		Ok(self.position.outcome() == Some(Outcome::Win(player)))
+	}
+
+	#[napi(getter = engine)]
+	pub fn js_get_engine(&self) -> Engine {
+		if self.mcts.is_some() {
+			Engine::MonteCarlo
+		} else {
+			Engine::AlphaBeta
+		}
+	}
+
+	#[napi(js_name = "setEngine")]
+	pub fn js_set_engine(&mut self, engine: Engine, options: Option<MonteCarloOptions>) -> Result<()> {
+		self.mcts = match engine {
+			Engine::AlphaBeta => None,
+			Engine::MonteCarlo => {
+				Some(Box::new(MonteCarlo::new(options.unwrap_or_default()).map_err(Error::from_reason)?))
+			}
+		};
+
+		Ok(())
+	}
+
+	/// The statistics of the last call to `getBestMove`, every counter is zero
+	/// before the first search.
+	#[napi(getter = lastSearchStats)]
+	pub fn js_get_last_search_stats(&self) -> SearchStats {
+		self.stats.into()
+	}
+
+	/// Returns the best edge for the player to move, or for the player given
+	/// in the options, or `INVALID_INDEX` if the game is finished. Every drawn
+	/// edge counts as a ply, extra moves included.
+	#[napi(js_name = "getBestMove")]
+	pub fn js_get_best_move(&mut self, options: Option<BestMoveArgument>) -> Result<i32> {
+		let (depth, player) = BestMoveOptions::resolve_or(options, 4, self.position.player)?;
+		Ok(self.get_best_move(depth, player) as i32)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn position(width: usize, height: usize, edges: &[u8]) -> Position {
+		let mut position = Position::new(width, height, Player::Human);
+		for &edge in edges {
+			position.edges |= 1 << edge;
+		}
+
+		// Any completed box is the human's:
+		for b in 0..position.box_count() {
+			if position.open_sides(b) == 0 {
+				position.human |= 1 << b;
+			}
+		}
+
+		position
+	}
+
+	// A 3×2 board whose boxes 2 and 5 form a pair which box 2 can be taken
+	// from, and whose boxes 0, 1, 4, and 3 form a closed chain:
+	const PAIR_AND_CHAIN: [u8; 10] = [0, 1, 2, 3, 6, 7, 8, 11, 12, 15];
+
+	mod edges {
+		use super::*;
+
+		#[test]
+		fn test_box_edges() {
+			let position = position(3, 2, &[]);
+
+			assert_eq!(position.edge_count(), 17);
+			assert_eq!(position.box_edges(0), [0, 3, 9, 10]);
+			assert_eq!(position.box_edges(4), [4, 7, 14, 15]);
+		}
+
+		#[test]
+		fn test_edge_boxes() {
+			let position = position(3, 2, &[]);
+
+			assert_eq!(position.edge_boxes(1), [None, Some(1)]);
+			assert_eq!(position.edge_boxes(4), [Some(1), Some(4)]);
+			assert_eq!(position.edge_boxes(8), [Some(5), None]);
+			assert_eq!(position.edge_boxes(9), [None, Some(0)]);
+			assert_eq!(position.edge_boxes(14), [Some(3), Some(4)]);
+			assert_eq!(position.edge_boxes(16), [Some(5), None]);
+		}
+	}
+
+	mod play {
+		use super::*;
+
+		#[test]
+		fn test_pass_turn() {
+			let mut position = position(2, 1, &[]);
+			position.play(5);
+
+			assert!(position.drawn(5));
+			assert_eq!(position.player, Player::Machine);
+		}
+
+		#[test]
+		fn test_extra_move() {
+			let mut position = position(2, 1, &[0, 2, 4]);
+			position.play(5);
+
+			assert_eq!(position.owner(0), Player::Human);
+			assert_eq!(position.owner(1), Player::Unset);
+			assert_eq!(position.player, Player::Human);
+		}
+
+		#[test]
+		fn test_double_cross() {
+			let mut position = Position { player: Player::Machine, ..position(2, 1, &[0, 1, 2, 3, 4, 6]) };
+			position.play(5);
+
+			assert_eq!(position.score(Player::Machine), 2);
+			assert_eq!(position.player, Player::Machine);
+			assert_eq!(position.outcome(), Some(Outcome::Win(Player::Machine)));
+			assert_eq!(position.moves(), vec![]);
+		}
+	}
+
+	mod moves {
+		use super::*;
+
+		#[test]
+		fn test_safe_only() {
+			assert_eq!(position(2, 1, &[0, 2]).moves(), vec![1, 3, 6]);
+		}
+
+		#[test]
+		fn test_no_safe_edge() {
+			// The center box joins the others, so the board is not split yet:
+			let position = position(3, 3, &[1, 3, 5, 6, 7, 8, 9, 13, 15, 22]);
+
+			assert_eq!(position.components(), None);
+			assert_eq!(position.moves(), vec![0, 2, 4, 10, 11, 12, 14, 16, 17, 18, 19, 20, 21, 23]);
+		}
+
+		#[test]
+		fn test_captures_only() {
+			assert_eq!(position(2, 1, &[0, 2, 4]).moves(), vec![5]);
+		}
+
+		#[test]
+		fn test_decline() {
+			assert_eq!(position(3, 2, &PAIR_AND_CHAIN).moves(), vec![5, 16]);
+		}
+
+		#[test]
+		fn test_hard_hearted_handout() {
+			assert_eq!(position(2, 1, &[0, 1, 2, 3]).moves(), vec![5]);
+		}
+
+		#[test]
+		fn test_one_opening_per_component() {
+			assert_eq!(position(2, 2, &[0, 1, 4, 5, 6, 8, 9, 11]).moves(), vec![2]);
+		}
+	}
+
+	mod evaluate {
+		use super::*;
+
+		#[test]
+		fn test_long_chain_rule() {
+			assert_eq!(position(1, 1, &[]).evaluate(), -PARITY_WEIGHT);
+			assert_eq!(position(1, 1, &[0]).evaluate(), PARITY_WEIGHT);
+		}
+
+		#[test]
+		fn test_open_last_box() {
+			assert_eq!(position(1, 1, &[0, 1]).evaluate(), -BOX_WEIGHT);
+		}
+
+		#[test]
+		fn test_open_loop() {
+			assert_eq!(position(2, 2, &[0, 1, 4, 5, 6, 8, 9, 11]).evaluate(), -4 * BOX_WEIGHT);
+		}
+
+		#[test]
+		fn test_keep_control() {
+			// Declining the pair wins the closed chain of four boxes:
+			assert_eq!(position(3, 2, &PAIR_AND_CHAIN).evaluate(), 2 * BOX_WEIGHT);
+		}
+	}
+
+	mod opening_value {
+		use super::*;
+
+		macro_rules! gen_test {
+			($($name:ident: [$chains:expr, $loops:expr, $outcome:expr],)*) => ($(
+				#[test]
+				fn $name() {
+					assert_eq!(opening_value(&$chains, &$loops), $outcome);
+				}
+			)*);
+		}
+
+		gen_test! {
+			test_nothing_left: [[], [], 0],
+			test_single_chain: [[5], [], -5],
+			// The opponent declines 2 boxes of the first two chains, and takes
+			// the last one:
+			test_keeps_control: [[3, 3, 3], [], -1],
+			// Giving the single box away first leaves the opponent to open the
+			// long chain:
+			test_short_chain_flips_control: [[1, 4], [], 3],
+			// Opening the loop first costs 8 boxes to decline:
+			test_loop_first: [[3], [4], -1],
+		}
+	}
+
+	mod get_best_move {
+		use super::*;
+
+		macro_rules! gen_test {
+			($($name:ident: [$width:expr, $height:expr, $edges:expr, $outcome:expr],)*) => ($(
+				#[test]
+				fn $name() {
+					let mut board = DotsAndBoxes::new(position($width, $height, &$edges));
+					assert_eq!(board.get_best_move(6, board.position.player), $outcome);
+				}
+			)*);
+		}
+
+		gen_test! {
+			test_capture: [2, 1, [0, 2, 4], 5],
+			test_double_deal: [3, 2, PAIR_AND_CHAIN, 16],
+			test_finished: [1, 1, [0, 1, 2, 3], U_INVALID_INDEX],
+		}
+
+		#[test]
+		fn test_other_player() {
+			let mut board = DotsAndBoxes::new(position(2, 1, &[0, 2, 4]));
+
+			assert_eq!(board.get_best_move(6, Player::Machine), 5);
+			assert_eq!(board.position.player, Player::Human);
+		}
+
+		#[test]
+		fn test_stats() {
+			let mut board = DotsAndBoxes::new(position(3, 3, &[]));
+			board.get_best_move(4, board.position.player);
+
+			assert!(board.stats.nodes > 0);
+			assert!(board.stats.cutoffs > 0);
+			assert_eq!(board.stats.max_depth, 4);
+		}
+
+		#[test]
+		fn test_monte_carlo() {
+			let mut board = DotsAndBoxes::new(position(3, 3, &[]));
+			board
+				.js_set_engine(
+					Engine::MonteCarlo,
+					Some(MonteCarloOptions { iterations: Some(500), seed: Some(7), ..Default::default() }),
+				)
+				.unwrap();
+
+			assert!(position(3, 3, &[]).available(board.get_best_move(6, board.position.player)));
+			assert!(board.stats.leaves > 0);
+		}
+	}
+}
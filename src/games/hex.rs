@@ -0,0 +1,594 @@
+use std::time::Instant;
+
+use napi::{bindgen_prelude::Uint8Array, Error, Result};
+
+use crate::{
+	isize_to_usize, napi_assert,
+	search::{
+		game::{Game, Outcome},
+		mcts::{MonteCarlo, MonteCarloOptions},
+		options::{BestMoveArgument, BestMoveOptions},
+		random::Random,
+		stats::{SearchStats, Stats},
+	},
+	Player, U_INVALID_INDEX,
+};
+
+/// The range of board sizes, so every cell fits in a move.
+const MIN_SIZE: u32 = 2;
+const MAX_SIZE: u32 = 13;
+
+/// The neighbours of a cell as row and column offsets, in order around it, so
+/// consecutive ones are neighbours too.
+const DIRECTIONS: [(isize, isize); 6] = [(0, 1), (-1, 1), (-1, 0), (0, -1), (1, -1), (1, 0)];
+
+/// The options of a [`Hex`] board.
+#[napi(object)]
+#[derive(Debug, Default)]
+pub struct HexOptions {
+	/// The amount of rows and columns, between 2 and 13, defaults to 11.
+	pub size: Option<u32>,
+	/// Whether the second player may take over the first stone instead of
+	/// placing one, defaults to `true`.
+	pub swap: Option<bool>,
+	/// The player to move, defaults to the human.
+	pub turn: Option<Player>,
+}
+
+/// A board alongside the player to move, as searched by the engine.
+///
+/// The stones are grouped with a union-find, where four extra nodes stand for
+/// the top, bottom, left, and right sides of the board.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Position {
+	size: usize,
+	cells: Vec<Player>,
+	parents: Vec<u8>,
+	player: Player,
+	stones: usize,
+	/// Whether the next move may swap the first stone.
+	swappable: bool,
+	/// The last cell played, so the playouts can answer it.
+	last: Option<usize>,
+	winner: Option<Player>,
+}
+
+impl Position {
+	fn new(size: usize, player: Player, swap: bool) -> Self {
+		let cells = size * size;
+		Self {
+			size,
+			cells: vec![Player::Unset; cells],
+			parents: (0..cells as u8 + 4).collect(),
+			player,
+			stones: 0,
+			swappable: swap,
+			last: None,
+			winner: None,
+		}
+	}
+
+	/// Returns a copy of this position with `player` to move.
+	fn with_player(&self, player: Player) -> Self {
+		Self { player, ..self.clone() }
+	}
+
+	/// Returns the nodes of the two sides `player` connects: the top and the
+	/// bottom for the human, the left and the right for the machine.
+	fn sides(&self, player: Player) -> (usize, usize) {
+		let first = self.cells.len();
+		match player {
+			Player::Human => (first, first + 1),
+			_ => (first + 2, first + 3),
+		}
+	}
+
+	fn neighbour(&self, cell: usize, (dr, dc): (isize, isize)) -> Option<usize> {
+		let row = (cell / self.size).checked_add_signed(dr).filter(|&row| row < self.size)?;
+		let column = (cell % self.size).checked_add_signed(dc).filter(|&column| column < self.size)?;
+		Some(row * self.size + column)
+	}
+
+	/// Returns the first stone while the player to move may swap it, which
+	/// must belong to the opponent.
+	fn swap_cell(&self) -> Option<usize> {
+		if self.swappable && self.stones == 1 {
+			self.cells
+				.iter()
+				.position(|&cell| cell != Player::Unset)
+				.filter(|&cell| self.cells[cell] == self.player.opponent())
+		} else {
+			None
+		}
+	}
+
+	fn available(&self, cell: usize) -> bool {
+		self.winner.is_none() && (self.cells[cell] == Player::Unset || self.swap_cell() == Some(cell))
+	}
+
+	fn find(&mut self, mut node: usize) -> usize {
+		while self.parents[node] as usize != node {
+			// Path halving, every visited node skips its parent:
+			self.parents[node] = self.parents[self.parents[node] as usize];
+			node = self.parents[node] as usize;
+		}
+
+		node
+	}
+
+	fn union(&mut self, a: usize, b: usize) {
+		let (a, b) = (self.find(a), self.find(b));
+		self.parents[a] = b as u8;
+	}
+
+	/// Places a stone of `player`, joining it with its neighbours of the same
+	/// player and the sides of the board it touches.
+	fn place(&mut self, cell: usize, player: Player) {
+		self.cells[cell] = player;
+
+		let (row, column) = (cell / self.size, cell % self.size);
+		let (first, second) = self.sides(player);
+		let (near, far) = match player {
+			Player::Human => (row == 0, row == self.size - 1),
+			_ => (column == 0, column == self.size - 1),
+		};
+		if near {
+			self.union(cell, first);
+		}
+		if far {
+			self.union(cell, second);
+		}
+
+		for direction in DIRECTIONS {
+			if let Some(next) = self.neighbour(cell, direction) {
+				if self.cells[next] == player {
+					self.union(cell, next);
+				}
+			}
+		}
+
+		if self.find(first) == self.find(second) {
+			self.winner = Some(player);
+		}
+	}
+
+	/// Returns the cell saving a bridge of the player to move that the last
+	/// move intruded: two stones sharing two empty neighbours, one of which
+	/// was just taken.
+	fn bridge_reply(&self) -> Option<usize> {
+		let last = self.last?;
+		(0..DIRECTIONS.len()).find_map(|i| {
+			let stone = |offset: usize| self.neighbour(last, DIRECTIONS[(i + offset) % DIRECTIONS.len()]);
+			let (a, reply, b) = (stone(0)?, stone(1)?, stone(2)?);
+			(self.cells[a] == self.player && self.cells[b] == self.player && self.cells[reply] == Player::Unset)
+				.then_some(reply)
+		})
+	}
+}
+
+impl Game for Position {
+	type Move = u8;
+
+	fn player(&self) -> Player {
+		self.player
+	}
+
+	/// Returns the empty cells, and the first stone while it can be swapped.
+	fn moves(&self) -> Vec<u8> {
+		if self.winner.is_some() {
+			return Vec::new();
+		}
+
+		let empty = (0..self.cells.len()).filter(|&cell| self.cells[cell] == Player::Unset);
+		self.swap_cell().into_iter().chain(empty).map(|cell| cell as u8).collect()
+	}
+
+	/// Places a stone, or swaps the first one: it is replaced by a stone of the
+	/// player to move mirrored across the long diagonal, so it serves the same
+	/// purpose for its new owner.
+	fn play(&mut self, cell: u8) {
+		let cell = cell as usize;
+		if self.cells[cell] == Player::Unset {
+			self.place(cell, self.player);
+			self.stones += 1;
+			self.swappable &= self.stones == 1;
+			self.last = Some(cell);
+		} else {
+			debug_assert_eq!(self.cells[cell], self.player.opponent());
+
+			let mirrored = cell % self.size * self.size + cell / self.size;
+			self.cells[cell] = Player::Unset;
+			self.parents = (0..self.parents.len() as u8).collect();
+			self.place(mirrored, self.player);
+			self.swappable = false;
+			self.last = Some(mirrored);
+		}
+
+		self.player = self.player.opponent();
+	}
+
+	/// Returns the winner, a full board always having one.
+	fn outcome(&self) -> Option<Outcome> {
+		self.winner.map(Outcome::Win)
+	}
+
+	/// Saves an intruded bridge, falling back to a random move.
+	fn heuristic_move(&mut self, _moves: &[u8], _random: &mut Random) -> Option<u8> {
+		self.bridge_reply().map(|cell| cell as u8)
+	}
+}
+
+/// Hex, where players take turns placing stones on a rhombus of hexagons. The
+/// human connects the top and bottom sides, the machine the left and right
+/// ones, and as they cannot both do so, the game never ends in a draw.
+///
+/// The engine is a Monte Carlo Tree Search, as connections are hard to
+/// evaluate without playing them out.
+#[napi]
+pub struct Hex {
+	position: Position,
+	mcts: Box<MonteCarlo<Position>>,
+	/// The counters of the last search.
+	stats: Stats,
+}
+
+impl Hex {
+	pub fn new(position: Position) -> Self {
+		Self {
+			position,
+			mcts: Box::new(MonteCarlo::new(MonteCarloOptions::default()).unwrap()),
+			stats: Stats::default(),
+		}
+	}
+
+	/// Returns the best cell for `player`, handing it the turn if the other
+	/// player is to move.
+	pub fn get_best_move(&mut self, player: Player) -> usize {
+		let start = Instant::now();
+
+		let handed;
+		let position = if player == self.position.player {
+			&self.position
+		} else {
+			handed = self.position.with_player(player);
+			&handed
+		};
+
+		let cell = self.mcts.search(position);
+		self.stats = self.mcts.stats();
+
+		self.stats.elapsed = start.elapsed();
+		cell.map_or(U_INVALID_INDEX, usize::from)
+	}
+}
+
+#[napi]
+impl Hex {
+	/// Creates a board from its cells, row by row, which defaults to an empty
+	/// board. With a single stone, the player to move may swap it.
+	#[napi(constructor)]
+	pub fn js_new(values: Option<Uint8Array>, options: Option<HexOptions>) -> Result<Self> {
+		let options = options.unwrap_or_default();
+		let size = options.size.unwrap_or(11);
+		if !(MIN_SIZE..=MAX_SIZE).contains(&size) {
+			return Err(Error::from_reason(format!("size must be between {MIN_SIZE} and {MAX_SIZE}")));
+		}
+
+		let turn = options.turn.unwrap_or(Player::Human);
+		napi_assert!(turn != Player::Unset);
+
+		let size = size as usize;
+		let mut position = Position::new(size, turn, options.swap.unwrap_or(true));
+		if let Some(v) = values {
+			let input = v.to_vec();
+			if input.len() != size * size {
+				return Err(Error::from_reason(format!("data must have exactly {} numbers", size * size)));
+			}
+
+			for (cell, &value) in input.iter().enumerate() {
+				let player = Player::try_from(value).map_err(Error::from_reason)?;
+				if player != Player::Unset {
+					position.place(cell, player);
+					position.stones += 1;
+				}
+			}
+
+			position.swappable &= position.stones <= 1;
+		}
+
+		Ok(Hex::new(position))
+	}
+
+	#[napi(getter = board)]
+	pub fn js_get_board(&self) -> Uint8Array {
+		Uint8Array::new(self.position.cells.iter().map(|&cell| cell.into()).collect())
+	}
+
+	/// The amount of rows and columns.
+	#[napi(getter = size)]
+	pub fn js_get_size(&self) -> u32 {
+		self.position.size as u32
+	}
+
+	#[napi(getter = turn)]
+	pub fn js_get_turn(&self) -> Player {
+		self.position.player
+	}
+
+	/// The first stone while the player to move may swap it, otherwise
+	/// `INVALID_INDEX`.
+	#[napi(getter = swapCell)]
+	pub fn js_get_swap_cell(&self) -> i32 {
+		self.position.swap_cell().unwrap_or(U_INVALID_INDEX) as i32
+	}
+
+	/// Whether the player to move can play on `cell`, which is either empty or
+	/// the first stone to swap.
+	#[napi(js_name = "available")]
+	pub fn js_available(&self, cell: i32) -> Result<bool> {
+		Ok(self.position.available(isize_to_usize!(cell, self.position.cells.len())?))
+	}
+
+	#[napi(getter = finished)]
+	pub fn js_finished(&self) -> bool {
+		self.position.winner.is_some()
+	}
+
+	/// The player who connected its sides, `Player.Unset` while the game is
+	/// ongoing.
+	#[napi(getter = winner)]
+	pub fn js_get_winner(&self) -> Player {
+		self.position.winner.unwrap_or(Player::Unset)
+	}
+
+	/// Places a stone of the player to move on `cell`, or swaps the first stone
+	/// if `cell` is `swapCell`, returning whether it won the game.
+	#[napi(js_name = "add")]
+	pub fn js_add(&mut self, cell: i32) -> Result<bool> {
+		let c = isize_to_usize!(cell, self.position.cells.len())?;
+		napi_assert!(self.position.available(c));
+
+		let player = self.position.player;
+		self.position.play(c as u8);
+		self.mcts.advance(c as u8);
+
+		Ok(self.position.winner == Some(player))
+	}
+
+	/// Replaces the options of the Monte Carlo Tree Search, which uses the
+	/// default ones until then.
+	#[napi(js_name = "setSearchOptions")]
+	pub fn js_set_search_options(&mut self, options: MonteCarloOptions) -> Result<()> {
+		*self.mcts = MonteCarlo::new(options).map_err(Error::from_reason)?;
+		Ok(())
+	}
+
+	/// The statistics of the last call to `getBestMove`, every counter is zero
+	/// before the first search.
+	#[napi(getter = lastSearchStats)]
+	pub fn js_get_last_search_stats(&self) -> SearchStats {
+		self.stats.into()
+	}
+
+	/// Returns the best cell for the player to move, or for the player given
+	/// in the options, which may be `swapCell`, or `INVALID_INDEX` if the game
+	/// is finished. The depth is ignored, as the search is bounded by the
+	/// options given to `setSearchOptions`.
+	#[napi(js_name = "getBestMove")]
+	pub fn js_get_best_move(&mut self, options: Option<BestMoveArgument>) -> Result<i32> {
+		let (_, player) = BestMoveOptions::resolve_or(options, 0, self.position.player)?;
+		Ok(self.get_best_move(player) as i32)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn position(size: usize, player: Player, stones: &[(usize, Player)]) -> Position {
+		let mut position = Position::new(size, player, true);
+		for &(cell, stone) in stones {
+			position.place(cell, stone);
+			position.stones += 1;
+		}
+
+		position.swappable &= position.stones <= 1;
+		position
+	}
+
+	mod place {
+		use super::*;
+
+		macro_rules! gen_test {
+			($($name:ident: [$size:expr, $stones:expr, $outcome:expr],)*) => ($(
+				#[test]
+				fn $name() {
+					let position = position($size, Player::Human, &$stones);
+					assert_eq!(position.winner, $outcome);
+				}
+			)*);
+		}
+
+		gen_test! {
+			test_column: [3, [(1, Player::Human), (4, Player::Human), (7, Player::Human)], Some(Player::Human)],
+			test_row: [3, [(3, Player::Machine), (4, Player::Machine), (5, Player::Machine)], Some(Player::Machine)],
+			// The cells 2, 4, and 6 are neighbours along the short diagonal:
+			test_diagonal: [3, [(2, Player::Human), (4, Player::Human), (6, Player::Human)], Some(Player::Human)],
+			test_wrong_sides: [3, [(3, Player::Human), (4, Player::Human), (5, Player::Human)], None],
+			// The cells 0, 4, and 8 are not neighbours along the long diagonal:
+			test_long_diagonal: [3, [(0, Player::Human), (4, Player::Human), (8, Player::Human)], None],
+			test_blocked: [
+				3,
+				[(1, Player::Human), (4, Player::Human), (7, Player::Machine), (6, Player::Machine)],
+				None
+			],
+			test_zigzag: [
+				4,
+				[(2, Player::Human), (9, Player::Human), (12, Player::Human), (5, Player::Human)],
+				Some(Player::Human)
+			],
+		}
+	}
+
+	mod play {
+		use super::*;
+
+		#[test]
+		fn test_turns() {
+			let mut position = position(3, Player::Human, &[]);
+			position.play(4);
+
+			assert_eq!(position.cells[4], Player::Human);
+			assert_eq!(position.player, Player::Machine);
+			assert_eq!(position.swap_cell(), Some(4));
+		}
+
+		#[test]
+		fn test_swap() {
+			let mut position = position(3, Player::Human, &[]);
+			position.play(1);
+			position.play(1);
+
+			assert_eq!(position.cells[1], Player::Unset);
+			assert_eq!(position.cells[3], Player::Machine);
+			assert_eq!(position.player, Player::Human);
+			assert_eq!(position.swap_cell(), None);
+			assert!(!position.moves().contains(&3));
+		}
+
+		#[test]
+		fn test_swapped_stone_connects() {
+			let mut position = position(2, Player::Human, &[]);
+			position.play(1);
+			position.play(1);
+			position.play(0);
+			position.play(3);
+
+			assert_eq!(position.winner, Some(Player::Machine));
+		}
+
+		#[test]
+		fn test_no_swap_after_second_stone() {
+			let mut position = position(3, Player::Human, &[]);
+			position.play(4);
+			position.play(0);
+
+			assert_eq!(position.swap_cell(), None);
+			assert_eq!(position.moves().len(), 7);
+		}
+
+		#[test]
+		fn test_no_swap_own_stone() {
+			let position = position(3, Player::Human, &[(4, Player::Human)]);
+
+			assert_eq!(position.swap_cell(), None);
+			assert!(!position.available(4));
+		}
+
+		#[test]
+		fn test_no_swap_rule() {
+			let mut position = Position::new(3, Player::Human, false);
+			position.play(4);
+
+			assert_eq!(position.swap_cell(), None);
+			assert!(!position.available(4));
+		}
+	}
+
+	mod moves {
+		use super::*;
+
+		#[test]
+		fn test_swap_first() {
+			let position = position(2, Player::Machine, &[(2, Player::Human)]);
+			assert_eq!(position.moves(), vec![2, 0, 1, 3]);
+		}
+
+		#[test]
+		fn test_finished() {
+			let position = position(2, Player::Machine, &[(0, Player::Human), (2, Player::Human)]);
+			assert_eq!(position.moves(), vec![]);
+		}
+	}
+
+	mod bridge_reply {
+		use super::*;
+
+		#[test]
+		fn test_save_bridge() {
+			// The stones 5 and 10 share the neighbours 6 and 9 on a 4×4 board:
+			let mut position = position(4, Player::Machine, &[(5, Player::Machine), (10, Player::Machine)]);
+			position.place(6, Player::Human);
+			position.last = Some(6);
+
+			assert_eq!(position.bridge_reply(), Some(9));
+		}
+
+		#[test]
+		fn test_no_bridge() {
+			let mut position = position(4, Player::Machine, &[(5, Player::Machine), (11, Player::Machine)]);
+			position.place(6, Player::Human);
+			position.last = Some(6);
+
+			assert_eq!(position.bridge_reply(), None);
+		}
+	}
+
+	mod get_best_move {
+		use super::*;
+
+		fn hex(position: Position) -> Hex {
+			let mut board = Hex::new(position);
+			board
+				.js_set_search_options(MonteCarloOptions {
+					iterations: Some(2000),
+					seed: Some(7),
+					..Default::default()
+				})
+				.unwrap();
+			board
+		}
+
+		#[test]
+		fn test_win() {
+			let stones = [(0, Player::Machine), (1, Player::Machine), (4, Player::Human), (7, Player::Human)];
+			let mut board = hex(position(3, Player::Machine, &stones));
+
+			assert_eq!(board.get_best_move(Player::Machine), 2);
+		}
+
+		#[test]
+		fn test_block() {
+			let stones = [(1, Player::Human), (4, Player::Human), (6, Player::Machine)];
+			let mut board = hex(position(3, Player::Machine, &stones));
+
+			assert_eq!(board.get_best_move(Player::Machine), 7);
+		}
+
+		#[test]
+		fn test_other_player() {
+			let stones = [(0, Player::Machine), (1, Player::Machine), (4, Player::Human), (7, Player::Human)];
+			let mut board = hex(position(3, Player::Human, &stones));
+
+			assert_eq!(board.get_best_move(Player::Machine), 2);
+			assert_eq!(board.position.player, Player::Human);
+		}
+
+		#[test]
+		fn test_finished() {
+			let stones = [(1, Player::Human), (4, Player::Human), (7, Player::Human)];
+			let mut board = hex(position(3, Player::Machine, &stones));
+
+			assert_eq!(board.get_best_move(Player::Machine), U_INVALID_INDEX);
+		}
+
+		#[test]
+		fn test_stats() {
+			let mut board = hex(position(5, Player::Human, &[]));
+			board.get_best_move(Player::Human);
+
+			assert!(board.stats.nodes > 0);
+			assert_eq!(board.stats.leaves, 2000);
+		}
+	}
+}
@@ -0,0 +1,505 @@
+use std::time::Instant;
+
+use napi::{bindgen_prelude::Uint8Array, Error, Result};
+
+use crate::{
+	isize_to_usize, napi_assert,
+	search::{
+		alpha_beta::{AlphaBeta, Evaluate},
+		game::{Game, Outcome},
+		mcts::{MonteCarlo, MonteCarloOptions},
+		options::{BestMoveArgument, BestMoveOptions},
+		random::Random,
+		stats::{SearchStats, Stats},
+	},
+	Engine, Player, U_INVALID_INDEX,
+};
+
+/// The amount of pits per player.
+pub const PITS: usize = 6;
+/// The amount of pits and stores: the human's pits and store come first,
+/// followed by the machine's, in sowing order.
+pub const CELLS: usize = 2 * (PITS + 1);
+
+const HUMAN_STORE: usize = PITS;
+const MACHINE_STORE: usize = CELLS - 1;
+
+/// The range of seeds per pit a game can start with.
+const MIN_SEEDS: u32 = 1;
+const MAX_SEEDS: u32 = 12;
+
+/// The evaluation weight of every seed in the store of the player to move over
+/// the opponent's, and of every seed left in its pits, which it may still
+/// collect.
+const STORE_WEIGHT: i32 = 4;
+const PIT_WEIGHT: i32 = 1;
+
+/// The options of a [`Mancala`] board.
+#[napi(object)]
+#[derive(Debug, Default)]
+pub struct MancalaOptions {
+	/// The amount of seeds in every pit, between 1 and 12, defaults to 4, when
+	/// no board is given.
+	pub seeds: Option<u32>,
+	/// The player to move, defaults to the human.
+	pub turn: Option<Player>,
+}
+
+/// A board alongside the player to move, as searched by both engines.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Position {
+	cells: [u8; CELLS],
+	player: Player,
+	outcome: Option<Outcome>,
+}
+
+impl Position {
+	fn new(cells: [u8; CELLS], player: Player) -> Self {
+		let mut position = Self { cells, player, outcome: None };
+		position.finish_if_empty();
+		position
+	}
+
+	/// Returns a copy of this position with `player` to move.
+	fn with_player(&self, player: Player) -> Self {
+		Self { player, ..self.clone() }
+	}
+
+	/// Returns the indexes of the pits of `player`.
+	fn pits(player: Player) -> std::ops::Range<usize> {
+		match player {
+			Player::Human => 0..PITS,
+			_ => HUMAN_STORE + 1..MACHINE_STORE,
+		}
+	}
+
+	fn store(player: Player) -> usize {
+		match player {
+			Player::Human => HUMAN_STORE,
+			_ => MACHINE_STORE,
+		}
+	}
+
+	/// Returns the seeds left in the pits of `player`.
+	fn seeds(&self, player: Player) -> u8 {
+		self.cells[Self::pits(player)].iter().sum()
+	}
+
+	fn available(&self, pit: usize) -> bool {
+		self.outcome.is_none() && Self::pits(self.player).contains(&pit) && self.cells[pit] != 0
+	}
+
+	/// Ends the game once a player has no seeds left, each player collecting
+	/// the seeds left in its pits.
+	fn finish_if_empty(&mut self) {
+		if self.seeds(Player::Human) != 0 && self.seeds(Player::Machine) != 0 {
+			return;
+		}
+
+		for player in [Player::Human, Player::Machine] {
+			let seeds = self.seeds(player);
+			self.cells[Self::pits(player)].fill(0);
+			self.cells[Self::store(player)] += seeds;
+		}
+
+		let (human, machine) = (self.cells[HUMAN_STORE], self.cells[MACHINE_STORE]);
+		self.outcome = Some(match human.cmp(&machine) {
+			std::cmp::Ordering::Greater => Outcome::Win(Player::Human),
+			std::cmp::Ordering::Less => Outcome::Win(Player::Machine),
+			std::cmp::Ordering::Equal => Outcome::Draw,
+		});
+	}
+}
+
+impl Game for Position {
+	type Move = u8;
+
+	fn player(&self) -> Player {
+		self.player
+	}
+
+	fn moves(&self) -> Vec<u8> {
+		if self.outcome.is_some() {
+			return Vec::new();
+		}
+
+		Self::pits(self.player).filter(|&pit| self.cells[pit] != 0).map(|pit| pit as u8).collect()
+	}
+
+	/// Sows the seeds of a pit one by one in the following pits and the own
+	/// store, skipping the opponent's store. The player moves again if the
+	/// last seed lands in its store, and captures it alongside the seeds of the
+	/// opposite pit if it lands in an empty own pit across a non-empty one.
+	fn play(&mut self, pit: u8) {
+		let mut cell = pit as usize;
+		let seeds = std::mem::take(&mut self.cells[cell]);
+		let skipped = Self::store(self.player.opponent());
+		for _ in 0..seeds {
+			cell = (cell + 1) % CELLS;
+			if cell == skipped {
+				cell = (cell + 1) % CELLS;
+			}
+
+			self.cells[cell] += 1;
+		}
+
+		let store = Self::store(self.player);
+		if Self::pits(self.player).contains(&cell) && self.cells[cell] == 1 {
+			let opposite = MACHINE_STORE - 1 - cell;
+			if self.cells[opposite] != 0 {
+				self.cells[store] += 1 + std::mem::take(&mut self.cells[opposite]);
+				self.cells[cell] = 0;
+			}
+		}
+
+		if cell != store {
+			self.player = self.player.opponent();
+		}
+
+		self.finish_if_empty();
+	}
+
+	fn outcome(&self) -> Option<Outcome> {
+		self.outcome
+	}
+
+	/// Takes an extra turn if there is one, otherwise the largest capture.
+	fn heuristic_move(&mut self, moves: &[u8], _random: &mut Random) -> Option<u8> {
+		let store = Self::store(self.player);
+		let seeds = self.cells[store];
+		let results = moves.iter().map(|&pit| {
+			let mut next = self.clone();
+			next.play(pit);
+			(pit, next.player == self.player, next.cells[store] - seeds)
+		});
+
+		results.max_by_key(|&(_, extra, gain)| (extra, gain)).filter(|&(_, extra, gain)| extra || gain > 1).map(|r| r.0)
+	}
+}
+
+impl Evaluate for Position {
+	fn evaluate(&self) -> i32 {
+		let opponent = self.player.opponent();
+		let stores = self.cells[Self::store(self.player)] as i32 - self.cells[Self::store(opponent)] as i32;
+		let pits = self.seeds(self.player) as i32 - self.seeds(opponent) as i32;
+		STORE_WEIGHT * stores + PIT_WEIGHT * pits
+	}
+}
+
+/// Kalah, where each player has 6 pits and a store, and collects the most
+/// seeds in its store. Landing the last sown seed in the own store grants
+/// another turn, so a player may move several times in a row.
+#[napi]
+pub struct Mancala {
+	position: Position,
+	mcts: Option<Box<MonteCarlo<Position>>>,
+	/// The counters of the last search.
+	stats: Stats,
+}
+
+impl Mancala {
+	pub fn new(position: Position) -> Self {
+		Self { position, mcts: None, stats: Stats::default() }
+	}
+
+	/// Returns the best pit for `player` using the selected engine, the depth
+	/// is only used by the alpha-beta engine. The search hands `player` the
+	/// turn if it is not theirs.
+	pub fn get_best_move(&mut self, maximum_depth: u8, player: Player) -> usize {
+		let start = Instant::now();
+
+		let handed;
+		let position = if player == self.position.player {
+			&self.position
+		} else {
+			handed = self.position.with_player(player);
+			&handed
+		};
+
+		let pit = if let Some(mut mcts) = self.mcts.take() {
+			let pit = mcts.search(position);
+			self.stats = mcts.stats();
+			self.mcts = Some(mcts);
+			pit
+		} else {
+			let mut search = AlphaBeta::default();
+			let pit = search.search(position, maximum_depth);
+			self.stats = search.stats();
+			pit
+		};
+
+		self.stats.elapsed = start.elapsed();
+		pit.map_or(U_INVALID_INDEX, usize::from)
+	}
+}
+
+#[napi]
+impl Mancala {
+	/// Creates a board from its 14 pits and stores, holding up to 255 seeds
+	/// in total, which defaults to every pit holding `options.seeds` seeds.
+	/// The seeds cannot be given alongside the board.
+	#[napi(constructor)]
+	pub fn js_new(values: Option<Uint8Array>, options: Option<MancalaOptions>) -> Result<Self> {
+		let options = options.unwrap_or_default();
+		let turn = options.turn.unwrap_or(Player::Human);
+		napi_assert!(turn != Player::Unset);
+
+		let mut cells = [0; CELLS];
+		if let Some(v) = values {
+			napi_assert!(options.seeds.is_none());
+
+			let input = v.to_vec();
+			if input.len() != CELLS {
+				return Err(Error::from_reason("data must have exactly 14 numbers"));
+			}
+
+			// Seeds are counted in a byte, which every sum of them must fit in:
+			napi_assert!(input.iter().map(|&seeds| u32::from(seeds)).sum::<u32>() <= u8::MAX.into());
+			cells.copy_from_slice(&input);
+		} else {
+			let seeds = options.seeds.unwrap_or(4);
+			if !(MIN_SEEDS..=MAX_SEEDS).contains(&seeds) {
+				return Err(Error::from_reason(format!("seeds must be between {MIN_SEEDS} and {MAX_SEEDS}")));
+			}
+
+			for player in [Player::Human, Player::Machine] {
+				cells[Position::pits(player)].fill(seeds as u8);
+			}
+		}
+
+		Ok(Mancala::new(Position::new(cells, turn)))
+	}
+
+	/// The seeds of the human's pits and store, followed by the machine's.
+	#[napi(getter = board)]
+	pub fn js_get_board(&self) -> Uint8Array {
+		Uint8Array::new(self.position.cells.to_vec())
+	}
+
+	/// The player to move, which moves again after sowing its last seed in
+	/// its store.
+	#[napi(getter = turn)]
+	pub fn js_get_turn(&self) -> Player {
+		self.position.player
+	}
+
+	/// Whether the player to move can sow the seeds of `pit`.
+	#[napi(js_name = "available")]
+	pub fn js_available(&self, pit: i32) -> Result<bool> {
+		Ok(self.position.available(isize_to_usize!(pit, CELLS)?))
+	}
+
+	#[napi(getter = finished)]
+	pub fn js_finished(&self) -> bool {
+		self.position.outcome.is_some()
+	}
+
+	/// The player who won, `Player.Unset` while the game is ongoing or if it
+	/// was drawn.
+	#[napi(getter = winner)]
+	pub fn js_get_winner(&self) -> Player {
+		match self.position.outcome {
+			Some(Outcome::Win(player)) => player,
+			_ => Player::Unset,
+		}
+	}
+
+	/// Sows the seeds of `pit` for the player to move, returning whether it won
+	/// the game.
+	#[napi(js_name = "add")]
+	pub fn js_add(&mut self, pit: i32) -> Result<bool> {
+		let p = isize_to_usize!(pit, CELLS)?;
+		napi_assert!(self.position.available(p));
+
+		let player = self.position.player;
+		self.position.play(p as u8);
+		if let Some(mcts) = self.mcts.as_mut() {
+			mcts.advance(p as u8);
+		}
+
+		Ok(self.position.outcome == Some(Outcome::Win(player)))
+	}
+
+	#[napi(getter = engine)]
+	pub fn js_get_engine(&self) -> Engine {
+		if self.mcts.is_some() {
+			Engine::MonteCarlo
+		} else {
+			Engine::AlphaBeta
+		}
+	}
+
+	#[napi(js_name = "setEngine")]
+	pub fn js_set_engine(&mut self, engine: Engine, options: Option<MonteCarloOptions>) -> Result<()> {
+		self.mcts = match engine {
+			Engine::AlphaBeta => None,
+			Engine::MonteCarlo => {
+				Some(Box::new(MonteCarlo::new(options.unwrap_or_default()).map_err(Error::from_reason)?))
+			}
+		};
+
+		Ok(())
+	}
+
+	/// The statistics of the last call to `getBestMove`, every counter is zero
+	/// before the first search.
+	#[napi(getter = lastSearchStats)]
+	pub fn js_get_last_search_stats(&self) -> SearchStats {
+		self.stats.into()
+	}
+
+	/// Returns the best pit for the player to move, or for the player given in
+	/// the options, or `INVALID_INDEX` if the game is finished. Every sowing
+	/// counts as a ply, extra turns included.
+	#[napi(js_name = "getBestMove")]
+	pub fn js_get_best_move(&mut self, options: Option<BestMoveArgument>) -> Result<i32> {
+		let (depth, player) = BestMoveOptions::resolve_or(options, 10, self.position.player)?;
+		Ok(self.get_best_move(depth, player) as i32)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn start(seeds: u8) -> Position {
+		let mut cells = [seeds; CELLS];
+		(cells[HUMAN_STORE], cells[MACHINE_STORE]) = (0, 0);
+		Position::new(cells, Player::Human)
+	}
+
+	mod play {
+		use super::*;
+
+		macro_rules! gen_test {
+			($($name:ident: [$cells:expr, $player:expr, $pit:expr, $outcome:expr, $next:expr],)*) => ($(
+				#[test]
+				fn $name() {
+					let mut position = Position::new($cells, $player);
+					position.play($pit);
+
+					assert_eq!(position.cells, $outcome);
+					assert_eq!(position.player, $next);
+				}
+			)*);
+		}
+
+		gen_test! {
+			test_sow: [
+				[4, 4, 4, 4, 4, 4, 0, 4, 4, 4, 4, 4, 4, 0], Player::Human, 0,
+				[0, 5, 5, 5, 5, 4, 0, 4, 4, 4, 4, 4, 4, 0], Player::Machine
+			],
+			test_extra_turn: [
+				[4, 4, 4, 4, 4, 4, 0, 4, 4, 4, 4, 4, 4, 0], Player::Human, 2,
+				[4, 4, 0, 5, 5, 5, 1, 4, 4, 4, 4, 4, 4, 0], Player::Human
+			],
+			test_machine_extra_turn: [
+				[4, 4, 4, 4, 4, 4, 0, 4, 4, 4, 4, 4, 4, 0], Player::Machine, 9,
+				[4, 4, 4, 4, 4, 4, 0, 4, 4, 0, 5, 5, 5, 1], Player::Machine
+			],
+			test_skip_opponent_store: [
+				[0, 0, 1, 0, 0, 10, 0, 1, 1, 1, 1, 1, 1, 0], Player::Human, 5,
+				[1, 1, 2, 0, 0, 0, 1, 2, 2, 2, 2, 2, 2, 0], Player::Machine
+			],
+			// The last seed lands in the empty pit 4, across the 3 seeds of 8:
+			test_capture: [
+				[1, 0, 0, 1, 0, 1, 0, 1, 3, 1, 1, 1, 1, 0], Player::Human, 3,
+				[1, 0, 0, 0, 0, 1, 4, 1, 0, 1, 1, 1, 1, 0], Player::Machine
+			],
+			test_no_capture_of_empty_pit: [
+				[1, 0, 0, 1, 0, 1, 0, 1, 0, 1, 1, 1, 1, 0], Player::Human, 3,
+				[1, 0, 0, 0, 1, 1, 0, 1, 0, 1, 1, 1, 1, 0], Player::Machine
+			],
+			// The machine ran out of seeds, so the human collects its own:
+			test_sweep: [
+				[0, 0, 3, 0, 0, 0, 20, 0, 0, 0, 0, 0, 1, 24], Player::Machine, 12,
+				[0, 0, 0, 0, 0, 0, 23, 0, 0, 0, 0, 0, 0, 25], Player::Machine
+			],
+		}
+
+		#[test]
+		fn test_outcome() {
+			let mut position = Position::new([0, 0, 3, 0, 0, 0, 20, 0, 0, 0, 0, 0, 1, 24], Player::Machine);
+			assert_eq!(position.outcome, None);
+
+			position.play(12);
+			assert_eq!(position.outcome, Some(Outcome::Win(Player::Machine)));
+			assert_eq!(position.moves(), vec![]);
+		}
+	}
+
+	mod moves {
+		use super::*;
+
+		#[test]
+		fn test_start() {
+			assert_eq!(start(4).moves(), vec![0, 1, 2, 3, 4, 5]);
+			assert_eq!(Position { player: Player::Machine, ..start(4) }.moves(), vec![7, 8, 9, 10, 11, 12]);
+		}
+
+		#[test]
+		fn test_skips_empty_pits() {
+			let position = Position::new([0, 2, 0, 0, 1, 0, 0, 1, 0, 0, 0, 0, 0, 0], Player::Human);
+
+			assert_eq!(position.moves(), vec![1, 4]);
+			assert!(!position.available(0));
+			assert!(!position.available(7));
+		}
+	}
+
+	mod get_best_move {
+		use super::*;
+
+		macro_rules! gen_test {
+			($($name:ident: [$cells:expr, $player:expr, $outcome:expr],)*) => ($(
+				#[test]
+				fn $name() {
+					let mut board = Mancala::new(Position::new($cells, $player));
+					assert_eq!(board.get_best_move(6, board.position.player), $outcome);
+				}
+			)*);
+		}
+
+		gen_test! {
+			// Both pits end in the store, but sowing pit 2 first would leave 2
+			// seeds in pit 5, which then overshoots it:
+			test_chain_extra_turns: [[0, 0, 4, 0, 0, 1, 10, 2, 2, 2, 2, 2, 2, 10], Player::Human, 5],
+			// Capturing the 8 seeds across pit 8 beats sowing into the store:
+			test_capture: [[1, 0, 0, 0, 8, 0, 10, 1, 0, 0, 0, 0, 2, 10], Player::Machine, 7],
+			test_finished: [[0, 0, 0, 0, 0, 0, 30, 0, 0, 0, 0, 0, 0, 18], Player::Human, U_INVALID_INDEX],
+		}
+
+		#[test]
+		fn test_other_player() {
+			let cells = [1, 0, 0, 0, 8, 0, 10, 1, 0, 0, 0, 0, 2, 10];
+			let mut board = Mancala::new(Position::new(cells, Player::Human));
+
+			assert_eq!(board.get_best_move(6, Player::Machine), 7);
+			assert_eq!(board.position.player, Player::Human);
+		}
+
+		#[test]
+		fn test_stats() {
+			let mut board = Mancala::new(start(4));
+			board.get_best_move(6, board.position.player);
+
+			assert!(board.stats.nodes > 0);
+			assert!(board.stats.cutoffs > 0);
+			assert_eq!(board.stats.max_depth, 6);
+		}
+
+		#[test]
+		fn test_monte_carlo() {
+			let mut board = Mancala::new(start(4));
+			board
+				.js_set_engine(
+					Engine::MonteCarlo,
+					Some(MonteCarloOptions { iterations: Some(500), seed: Some(7), ..Default::default() }),
+				)
+				.unwrap();
+
+			assert!(start(4).available(board.get_best_move(6, board.position.player)));
+			assert!(board.stats.leaves > 0);
+		}
+	}
+}
@@ -0,0 +1,710 @@
+use std::{cmp, time::Instant};
+
+use napi::{bindgen_prelude::Uint8Array, Error, Result};
+
+use crate::{
+	isize_to_usize, napi_assert,
+	search::{
+		options::{BestMoveArgument, BestMoveOptions},
+		stats::{SearchStats, Stats},
+	},
+	Player, U_INVALID_INDEX,
+};
+
+/// The smallest supported width, height and line length.
+const MIN_SIZE: usize = 3;
+/// The largest supported width and height, the size of a Go board.
+const MAX_SIZE: usize = 19;
+/// The longest supported line, as the evaluation weights grow exponentially
+/// with it.
+const MAX_K: usize = 8;
+
+/// The score of a lost game from the perspective of the loser, adjusted by the
+/// remaining depth so the search prefers the fastest wins and the slowest
+/// losses.
+const OUTCOME_LOSS: i64 = -1_000_000_000_000_000;
+const OUTCOME_DRAW: i64 = 0;
+
+/// The horizontal and vertical movement of each direction a line can take.
+const DIRECTIONS: [(isize, isize); 4] = [(1, 0), (0, 1), (1, 1), (-1, 1)];
+
+/// How far from the placed pieces the candidate moves are looked for, as the
+/// moves far from any piece take no part in the fight.
+const NEIGHBOURHOOD: isize = 2;
+
+/// The most moves searched per node when no move is forced, keeping the
+/// branching factor of large boards low.
+const BEAM_WIDTH: usize = 12;
+
+/// The score of a window holding `n` pieces of a single player and no piece of
+/// the other one, by `n`.
+const WINDOW_WEIGHTS: [i64; MAX_K + 1] = [0, 1, 10, 100, 1_000, 10_000, 100_000, 1_000_000, 10_000_000];
+
+/// Which lines of `k` pieces win the game.
+#[napi]
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "tune", derive(Clone, Copy))]
+pub enum LineRule {
+	/// Lines of `k` or more pieces win.
+	Freestyle,
+	/// Only lines of exactly `k` pieces win, longer lines (overlines) do not,
+	/// as in standard Gomoku.
+	Exact,
+}
+
+/// The options of an [`MnkGame`] board, which defaults to freestyle Gomoku on
+/// a 15×15 board.
+#[napi(object)]
+#[derive(Debug, Default)]
+pub struct MnkOptions {
+	/// The amount of columns (m), between 3 and 19, defaults to 15.
+	pub width: Option<u32>,
+	/// The amount of rows (n), between 3 and 19, defaults to 15.
+	pub height: Option<u32>,
+	/// The amount of pieces in a row needed to win, between 3 and 8 and no
+	/// longer than the board, defaults to 5.
+	pub k: Option<u32>,
+	/// Which lines win the game, defaults to `LineRule.Freestyle`.
+	pub rule: Option<LineRule>,
+}
+
+/// An m,n,k-game, where two players take turns placing pieces in an m×n board
+/// and the first one with `k` pieces in a row wins. Tic-tac-toe is the 3,3,3
+/// game, and Gomoku the 15,15,5 one.
+#[napi]
+pub struct MnkGame {
+	width: usize,
+	height: usize,
+	k: usize,
+	rule: LineRule,
+	cells: Vec<Player>,
+	empty: usize,
+	/// The player who completed a line, `Player::Unset` while nobody did.
+	winner: Player,
+	/// The first cell of every window of `k` cells, alongside the offset
+	/// between its cells.
+	windows: Vec<(usize, usize)>,
+	/// The counters of the last search.
+	stats: Stats,
+}
+
+impl MnkGame {
+	/// Creates a board of the given size, which must be valid, from its cells.
+	pub fn new(width: usize, height: usize, k: usize, rule: LineRule, cells: Vec<Player>) -> Self {
+		debug_assert!(Self::validate(width, height, k).is_ok());
+		debug_assert_eq!(cells.len(), width * height);
+
+		let empty = cells.iter().filter(|&&c| c == Player::Unset).count();
+
+		let mut windows = Vec::new();
+		for cell in 0..width * height {
+			let (x, y) = ((cell % width) as isize, (cell / width) as isize);
+			for (dx, dy) in DIRECTIONS {
+				let (x, y) = (x + dx * (k as isize - 1), y + dy * (k as isize - 1));
+				if (0..width as isize).contains(&x) && (0..height as isize).contains(&y) {
+					windows.push((cell, (dy * width as isize + dx) as usize));
+				}
+			}
+		}
+
+		let mut board =
+			Self { width, height, k, rule, cells, empty, windows, winner: Player::Unset, stats: Stats::default() };
+		if let Some(cell) = (0..board.cells.len()).find(|&c| !board.available(c) && board.status(c)) {
+			board.winner = board.cells[cell];
+		}
+
+		board
+	}
+
+	/// Checks that boards of this size are supported.
+	fn validate(width: usize, height: usize, k: usize) -> std::result::Result<(), String> {
+		if !(MIN_SIZE..=MAX_SIZE).contains(&width) {
+			return Err(format!("width must be between {MIN_SIZE} and {MAX_SIZE}"));
+		}
+
+		if !(MIN_SIZE..=MAX_SIZE).contains(&height) {
+			return Err(format!("height must be between {MIN_SIZE} and {MAX_SIZE}"));
+		}
+
+		let max_k = MAX_K.min(width.max(height));
+		if !(MIN_SIZE..=max_k).contains(&k) {
+			return Err(format!("k must be between {MIN_SIZE} and {max_k}"));
+		}
+
+		Ok(())
+	}
+
+	fn available(&self, cell: usize) -> bool {
+		debug_assert!(cell < self.cells.len());
+
+		self.cells[cell] == Player::Unset
+	}
+
+	fn add(&mut self, cell: usize, player: Player) {
+		debug_assert!(self.available(cell));
+
+		self.cells[cell] = player;
+		self.empty -= 1;
+	}
+
+	fn remove(&mut self, cell: usize) {
+		debug_assert!(!self.available(cell));
+
+		self.cells[cell] = Player::Unset;
+		self.empty += 1;
+	}
+
+	/// Returns the cell `distance` steps away from `cell` in a direction, if it
+	/// is inside the board.
+	fn step(&self, cell: usize, (dx, dy): (isize, isize), distance: isize) -> Option<usize> {
+		let x = (cell % self.width) as isize + dx * distance;
+		let y = (cell / self.width) as isize + dy * distance;
+
+		((0..self.width as isize).contains(&x) && (0..self.height as isize).contains(&y))
+			.then(|| y as usize * self.width + x as usize)
+	}
+
+	/// Counts the pieces of the player in `cell` that follow it in a direction.
+	fn count_direction(&self, cell: usize, direction: (isize, isize)) -> usize {
+		let player = self.cells[cell];
+		(1..)
+			.take_while(|&distance| self.step(cell, direction, distance).is_some_and(|c| self.cells[c] == player))
+			.count()
+	}
+
+	/// Checks whether the piece at `cell` is part of a winning line.
+	fn status(&self, cell: usize) -> bool {
+		debug_assert!(!self.available(cell));
+
+		DIRECTIONS.into_iter().any(|(dx, dy)| {
+			let length = 1 + self.count_direction(cell, (dx, dy)) + self.count_direction(cell, (-dx, -dy));
+			match self.rule {
+				LineRule::Freestyle => length >= self.k,
+				LineRule::Exact => length == self.k,
+			}
+		})
+	}
+
+	/// Checks whether placing a piece of `player` in `cell` wins the game,
+	/// leaving the board unchanged.
+	fn is_winning_move(&mut self, cell: usize, player: Player) -> bool {
+		self.add(cell, player);
+		let won = self.status(cell);
+		self.remove(cell);
+		won
+	}
+
+	/// Scores the window starting at `start`, positive for the machine.
+	fn evaluate_window(&self, start: usize, step: usize) -> i64 {
+		let (mut human, mut machine) = (0, 0);
+		for i in 0..self.k {
+			match self.cells[start + step * i] {
+				Player::Human => human += 1,
+				Player::Machine => machine += 1,
+				Player::Unset => {}
+			}
+		}
+
+		match (human, machine) {
+			(0, n) => WINDOW_WEIGHTS[n],
+			(n, 0) => -WINDOW_WEIGHTS[n],
+			_ => 0,
+		}
+	}
+
+	/// Scores the board from the perspective of the machine, adding up the
+	/// windows that can still become a line for a single player.
+	fn evaluate(&self) -> i64 {
+		self.windows.iter().map(|&(start, step)| self.evaluate_window(start, step)).sum()
+	}
+
+	/// Scores how much placing a piece in `cell` helps either player, by the
+	/// pieces of every window through it that it extends or blocks.
+	fn threat(&self, cell: usize) -> i64 {
+		let mut score = 0;
+		for direction in DIRECTIONS {
+			// Every window of `k` cells in this direction that contains `cell`:
+			for offset in 0..self.k as isize {
+				let Some(start) = self.step(cell, direction, -offset) else {
+					break;
+				};
+				if self.step(start, direction, self.k as isize - 1).is_none() {
+					continue;
+				}
+
+				let (mut human, mut machine) = (0, 0);
+				for i in 0..self.k as isize {
+					match self.step(start, direction, i).map(|c| self.cells[c]) {
+						Some(Player::Human) => human += 1,
+						Some(Player::Machine) => machine += 1,
+						_ => {}
+					}
+				}
+
+				score += match (human, machine) {
+					(0, n) | (n, 0) => WINDOW_WEIGHTS[n + 1],
+					_ => 0,
+				};
+			}
+		}
+
+		score
+	}
+
+	/// Checks whether there is a piece within [`NEIGHBOURHOOD`] of `cell`.
+	fn is_near_pieces(&self, cell: usize) -> bool {
+		(-NEIGHBOURHOOD..=NEIGHBOURHOOD).any(|dy| {
+			(-NEIGHBOURHOOD..=NEIGHBOURHOOD)
+				.any(|dx| self.step(cell, (dx, dy), 1).is_some_and(|c| self.cells[c] != Player::Unset))
+		})
+	}
+
+	/// Generates the moves worth searching for `player`:
+	///
+	/// - A winning move, if there is one.
+	/// - Otherwise, the moves blocking the opponent's immediate wins, if any.
+	/// - Otherwise, the empty cells near the placed pieces, ordered by how much
+	///   they extend or block lines, keeping the [`BEAM_WIDTH`] best ones.
+	fn candidates(&mut self, player: Player) -> Vec<usize> {
+		let near: Vec<usize> = (0..self.cells.len()).filter(|&c| self.available(c) && self.is_near_pieces(c)).collect();
+		if near.is_empty() {
+			// The board is empty, take the center:
+			return vec![(self.height / 2) * self.width + self.width / 2];
+		}
+
+		if let Some(&cell) = near.iter().find(|&&c| self.is_winning_move(c, player)) {
+			return vec![cell];
+		}
+
+		let blocks: Vec<usize> = near.iter().copied().filter(|&c| self.is_winning_move(c, player.opponent())).collect();
+		if !blocks.is_empty() {
+			return blocks;
+		}
+
+		let mut scored: Vec<(i64, usize)> = near.into_iter().map(|c| (self.threat(c), c)).collect();
+		scored.sort_by_key(|&(score, cell)| (cmp::Reverse(score), cell));
+		scored.truncate(BEAM_WIDTH);
+		scored.into_iter().map(|(_, cell)| cell).collect()
+	}
+
+	/// Scores the position from the perspective of `player`, who is to move
+	/// after the opponent played `last_cell`.
+	fn negamax(&mut self, player: Player, last_cell: usize, remaining: u8, alpha: i64, beta: i64) -> i64 {
+		self.stats.visit(remaining);
+
+		if self.status(last_cell) {
+			self.stats.leaves += 1;
+			return OUTCOME_LOSS - remaining as i64;
+		}
+
+		if self.empty == 0 {
+			self.stats.leaves += 1;
+			return OUTCOME_DRAW;
+		}
+
+		if remaining == 0 {
+			self.stats.leaves += 1;
+			return match player {
+				Player::Machine => self.evaluate(),
+				_ => -self.evaluate(),
+			};
+		}
+
+		let mut max_v = i64::MIN;
+		let mut alpha = alpha;
+		for cell in self.candidates(player) {
+			self.add(cell, player);
+			let m = -self.negamax(player.opponent(), cell, remaining - 1, -beta, -alpha);
+			self.remove(cell);
+
+			if m > max_v {
+				max_v = m;
+
+				alpha = cmp::max(alpha, max_v);
+				if alpha >= beta {
+					self.stats.cutoffs += 1;
+					break;
+				}
+			}
+		}
+
+		max_v
+	}
+
+	fn max_top(&mut self, player: Player, remaining: u8) -> usize {
+		if remaining == 0 {
+			return U_INVALID_INDEX;
+		}
+
+		// The root is not visited, its children are the first ply:
+		self.stats = Stats::new(remaining - 1);
+
+		let mut max_v = i64::MIN;
+		let mut best = U_INVALID_INDEX;
+		let mut alpha = -i64::MAX;
+		for cell in self.candidates(player) {
+			self.add(cell, player);
+			let points = -self.negamax(player.opponent(), cell, remaining - 1, -i64::MAX, -alpha);
+			self.remove(cell);
+
+			if points > max_v {
+				max_v = points;
+				best = cell;
+				alpha = max_v;
+			}
+		}
+
+		best
+	}
+
+	/// Returns the best move for `player`, searching `maximum_depth` plies
+	/// deep, or [`U_INVALID_INDEX`] if the game is finished.
+	pub fn get_best_move(&mut self, maximum_depth: u8, player: Player) -> usize {
+		if self.winner != Player::Unset {
+			return U_INVALID_INDEX;
+		}
+
+		let start = Instant::now();
+
+		let remaining = cmp::min(self.empty, maximum_depth as usize) as u8;
+		let cell = self.max_top(player, remaining);
+
+		self.stats.elapsed = start.elapsed();
+		cell
+	}
+}
+
+#[napi]
+impl MnkGame {
+	#[napi(constructor)]
+	pub fn js_new(values: Option<Uint8Array>, options: Option<MnkOptions>) -> Result<Self> {
+		let options = options.unwrap_or_default();
+		let width = options.width.map_or(15, |v| v as usize);
+		let height = options.height.map_or(15, |v| v as usize);
+		let k = options.k.map_or(5, |v| v as usize);
+		Self::validate(width, height, k).map_err(Error::from_reason)?;
+
+		let mut cells = vec![Player::Unset; width * height];
+		if let Some(v) = values {
+			let input = v.to_vec();
+			if input.len() != cells.len() {
+				return Err(Error::from_reason(format!("data must have exactly {} numbers", cells.len())));
+			}
+
+			for (cell, value) in cells.iter_mut().zip(input) {
+				*cell = Player::try_from(value).map_err(Error::from_reason)?;
+			}
+		}
+
+		Ok(MnkGame::new(width, height, k, options.rule.unwrap_or(LineRule::Freestyle), cells))
+	}
+
+	#[napi(getter = board)]
+	pub fn js_get_board(&self) -> Uint8Array {
+		Uint8Array::new(self.cells.iter().map(|&v| v as u8).collect())
+	}
+
+	#[napi(getter = width)]
+	pub fn js_get_width(&self) -> u32 {
+		self.width as u32
+	}
+
+	#[napi(getter = height)]
+	pub fn js_get_height(&self) -> u32 {
+		self.height as u32
+	}
+
+	/// The amount of pieces in a row needed to win.
+	#[napi(getter = k)]
+	pub fn js_get_k(&self) -> u32 {
+		self.k as u32
+	}
+
+	#[napi(getter = rule)]
+	pub fn js_get_rule(&self) -> LineRule {
+		match self.rule {
+			LineRule::Freestyle => LineRule::Freestyle,
+			LineRule::Exact => LineRule::Exact,
+		}
+	}
+
+	#[napi(js_name = "available")]
+	pub fn js_available(&self, cell: i32) -> Result<bool> {
+		Ok(self.available(isize_to_usize!(cell, self.cells.len())?))
+	}
+
+	/// Whether a player completed a line, or the board is full.
+	#[napi(getter = finished)]
+	pub fn js_finished(&self) -> bool {
+		self.winner != Player::Unset || self.empty == 0
+	}
+
+	/// The player who completed a line, `Player.Unset` while nobody did.
+	#[napi(getter = winner)]
+	pub fn js_get_winner(&self) -> Player {
+		self.winner
+	}
+
+	/// Places a piece of `player` on `cell`, returning whether it completed a
+	/// line. Fails once the game is won.
+	#[napi(js_name = "add")]
+	pub fn js_add(&mut self, cell: i32, player: Player) -> Result<bool> {
+		let c = isize_to_usize!(cell, self.cells.len())?;
+		napi_assert!(self.winner == Player::Unset);
+		napi_assert!(self.available(c));
+
+		self.add(c, player);
+		let won = self.status(c);
+		if won {
+			self.winner = player;
+		}
+
+		Ok(won)
+	}
+
+	/// The statistics of the last call to `getBestMove`, every counter is zero
+	/// before the first search.
+	#[napi(getter = lastSearchStats)]
+	pub fn js_get_last_search_stats(&self) -> SearchStats {
+		self.stats.into()
+	}
+
+	/// Returns the best cell for the machine, or for the player given in the
+	/// options, or `INVALID_INDEX` if the game is finished.
+	#[napi(js_name = "getBestMove")]
+	pub fn js_get_best_move(&mut self, options: Option<BestMoveArgument>) -> Result<i32> {
+		let (depth, player) = BestMoveOptions::resolve(options, 4)?;
+		Ok(self.get_best_move(depth, player) as i32)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Creates a board with the pieces of each player in the given cells.
+	fn board(width: usize, height: usize, k: usize, rule: LineRule, human: &[usize], machine: &[usize]) -> MnkGame {
+		let mut cells = vec![Player::Unset; width * height];
+		for &cell in human {
+			cells[cell] = Player::Human;
+		}
+		for &cell in machine {
+			cells[cell] = Player::Machine;
+		}
+
+		MnkGame::new(width, height, k, rule, cells)
+	}
+
+	fn gomoku(human: &[usize], machine: &[usize]) -> MnkGame {
+		board(15, 15, 5, LineRule::Freestyle, human, machine)
+	}
+
+	mod new {
+		use super::*;
+
+		macro_rules! gen_test {
+			($($name:ident: [$width:expr, $height:expr, $k:expr, $outcome:expr],)*) => ($(
+				#[test]
+				fn $name() {
+					let board = board($width, $height, $k, LineRule::Freestyle, &[], &[]);
+
+					assert_eq!(board.empty, $width * $height);
+					assert_eq!(board.windows.len(), $outcome);
+				}
+			)*);
+		}
+
+		gen_test! {
+			test_tic_tac_toe: [3, 3, 3, 8],
+			test_4x4x4: [4, 4, 4, 10],
+			test_5x5x4: [5, 5, 4, 28],
+			test_gomoku: [15, 15, 5, 572],
+		}
+
+		macro_rules! gen_validate_test {
+			($($name:ident: [$width:expr, $height:expr, $k:expr, $outcome:expr],)*) => ($(
+				#[test]
+				fn $name() {
+					assert_eq!(MnkGame::validate($width, $height, $k), $outcome);
+				}
+			)*);
+		}
+
+		gen_validate_test! {
+			test_validate_gomoku: [15, 15, 5, Ok(())],
+			test_validate_too_wide: [20, 15, 5, Err("width must be between 3 and 19".to_string())],
+			test_validate_too_short: [15, 2, 5, Err("height must be between 3 and 19".to_string())],
+			test_validate_k_too_long: [4, 4, 5, Err("k must be between 3 and 4".to_string())],
+		}
+
+		macro_rules! gen_winner_test {
+			($($name:ident: [$rule:expr, $human:expr, $outcome:expr],)*) => ($(
+				#[test]
+				fn $name() {
+					assert_eq!(board(15, 15, 5, $rule, &$human, &[]).winner, $outcome);
+				}
+			)*);
+		}
+
+		gen_winner_test! {
+			test_winner_none: [LineRule::Freestyle, [0, 1, 2, 3], Player::Unset],
+			test_winner_line: [LineRule::Freestyle, [0, 16, 32, 48, 64], Player::Human],
+			test_winner_exact_overline: [LineRule::Exact, [0, 1, 2, 3, 4, 5], Player::Unset],
+		}
+	}
+
+	mod add {
+		use super::*;
+
+		#[test]
+		fn test_winning_move() {
+			let mut board = gomoku(&[30, 31, 32, 33], &[0, 1, 2]);
+
+			assert!(!board.js_add(3, Player::Machine).unwrap());
+			assert_eq!(board.js_get_winner(), Player::Unset);
+			assert!(board.js_add(34, Player::Human).unwrap());
+			assert_eq!(board.js_get_winner(), Player::Human);
+			assert!(board.js_finished());
+		}
+	}
+
+	mod status {
+		use super::*;
+
+		macro_rules! gen_test {
+			($($name:ident: [$rule:expr, $human:expr, $last:expr, $outcome:expr],)*) => ($(
+				#[test]
+				fn $name() {
+					assert_eq!(board(15, 15, 5, $rule, &$human, &[]).status($last), $outcome);
+				}
+			)*);
+		}
+
+		gen_test! {
+			test_four: [LineRule::Freestyle, [0, 1, 2, 3], 3, false],
+			test_horizontal: [LineRule::Freestyle, [0, 1, 2, 3, 4], 2, true],
+			test_vertical: [LineRule::Freestyle, [7, 22, 37, 52, 67], 67, true],
+			test_diagonal: [LineRule::Freestyle, [0, 16, 32, 48, 64], 32, true],
+			test_anti_diagonal: [LineRule::Freestyle, [14, 28, 42, 56, 70], 14, true],
+			// The end of a row does not continue in the next one:
+			test_no_wrap_around: [LineRule::Freestyle, [12, 13, 14, 15, 16], 14, false],
+			test_freestyle_overline: [LineRule::Freestyle, [0, 1, 2, 3, 4, 5], 5, true],
+			test_exact_five: [LineRule::Exact, [0, 1, 2, 3, 4], 4, true],
+			test_exact_overline: [LineRule::Exact, [0, 1, 2, 3, 4, 5], 5, false],
+		}
+	}
+
+	mod evaluate {
+		use super::*;
+
+		#[test]
+		fn test_empty() {
+			assert_eq!(gomoku(&[], &[]).evaluate(), 0);
+		}
+
+		#[test]
+		fn test_symmetric() {
+			assert_eq!(gomoku(&[112], &[]).evaluate(), -gomoku(&[], &[112]).evaluate());
+		}
+
+		#[test]
+		fn test_blocked_windows() {
+			// A piece in the center is part of 20 windows, 4 of which are blocked:
+			assert_eq!(gomoku(&[112], &[]).evaluate(), -20);
+			assert_eq!(gomoku(&[112], &[113]).evaluate(), -20 + 4 + 20 - 4);
+		}
+	}
+
+	mod candidates {
+		use super::*;
+
+		#[test]
+		fn test_empty_board() {
+			assert_eq!(gomoku(&[], &[]).candidates(Player::Machine), vec![112]);
+		}
+
+		#[test]
+		fn test_win() {
+			let mut board = gomoku(&[30, 31, 32, 33], &[0, 1, 2, 3]);
+			assert_eq!(board.candidates(Player::Machine), vec![4]);
+		}
+
+		#[test]
+		fn test_blocks() {
+			// An open four has to be blocked at either end:
+			let mut board = gomoku(&[31, 32, 33, 34], &[0, 2, 4]);
+			assert_eq!(board.candidates(Player::Machine), vec![30, 35]);
+		}
+
+		#[test]
+		fn test_beam_width() {
+			let mut board = gomoku(&[112], &[]);
+			let candidates = board.candidates(Player::Machine);
+
+			assert_eq!(candidates.len(), BEAM_WIDTH);
+			assert!(candidates.iter().all(|&c| board.is_near_pieces(c)));
+		}
+	}
+
+	mod get_best_move {
+		use super::*;
+
+		macro_rules! gen_test {
+			($($name:ident: [$board:expr, $outcome:expr],)*) => ($(
+				#[test]
+				fn $name() {
+					let mut board = $board;
+					assert_eq!(board.get_best_move(4, Player::Machine), $outcome);
+				}
+			)*);
+		}
+
+		gen_test! {
+			// H H H v
+			// M M _ _
+			// _ _ _ _
+			// _ _ _ _
+			test_4x4x4_block: [board(4, 4, 4, LineRule::Freestyle, &[0, 1, 2], &[4, 5]), 3],
+			// _ _ _ _ _
+			// M M M v _
+			// H H _ _ _
+			// H _ _ _ _
+			// _ _ _ _ _
+			test_5x5x4_win: [board(5, 5, 4, LineRule::Freestyle, &[10, 11, 15], &[5, 6, 7]), 8],
+			test_gomoku_win: [gomoku(&[30, 31, 32, 33], &[0, 1, 2, 3]), 4],
+			test_gomoku_block_four: [gomoku(&[30, 31, 32, 33, 100], &[0, 2, 4, 110]), 34],
+			// An open three must be blocked before it becomes an open four:
+			test_gomoku_block_open_three: [gomoku(&[110, 111, 112], &[80, 96]), 113],
+			test_freestyle_overline: [
+				board(15, 15, 5, LineRule::Freestyle, &[150, 151, 152, 153], &[0, 1, 2, 3, 5]),
+				4
+			],
+			// Six in a row does not win in exact Gomoku, so the machine blocks:
+			test_exact_skips_overline: [
+				board(15, 15, 5, LineRule::Exact, &[150, 151, 152, 153], &[0, 1, 2, 3, 5]),
+				154
+			],
+			test_full_board: [board(3, 3, 3, LineRule::Freestyle, &[0, 2, 3, 7, 8], &[1, 4, 5, 6]), U_INVALID_INDEX],
+			test_won: [gomoku(&[30, 31, 32, 33, 34], &[0, 1, 2, 3]), U_INVALID_INDEX],
+		}
+
+		#[test]
+		fn test_human() {
+			let mut board = gomoku(&[30, 31, 32, 33], &[0, 1, 2, 3]);
+			assert_eq!(board.get_best_move(4, Player::Human), 34);
+
+			let mut board = gomoku(&[100], &[0, 1, 2, 3]);
+			assert_eq!(board.get_best_move(4, Player::Human), 4);
+		}
+
+		#[test]
+		fn test_stats() {
+			let mut board = gomoku(&[112], &[]);
+			board.get_best_move(3, Player::Machine);
+
+			assert!(board.stats.nodes > 0);
+			assert!(board.stats.cutoffs > 0);
+			assert_eq!(board.stats.max_depth, 3);
+		}
+	}
+}
@@ -0,0 +1,882 @@
+use std::time::Instant;
+
+use napi::{
+	bindgen_prelude::{Either, Uint8Array},
+	Error, Result,
+};
+
+use crate::{
+	napi_assert,
+	search::{
+		alpha_beta::{AlphaBeta, Evaluate},
+		game::{Game, Outcome},
+		mcts::{MonteCarlo, MonteCarloOptions},
+		options::{BestMoveArgument, BestMoveOptions},
+		random::Random,
+		stats::{SearchStats, Stats},
+		tt::zobrist_keys,
+	},
+	Engine, Player,
+};
+
+/// The amount of points, numbered row by row:
+///
+/// ```text
+/// 0-----------1-----------2
+/// |           |           |
+/// |   3-------4-------5   |
+/// |   |       |       |   |
+/// |   |   6---7---8   |   |
+/// |   |   |       |   |   |
+/// 9---10--11      12--13--14
+/// |   |   |       |   |   |
+/// |   |   15--16--17  |   |
+/// |   |       |       |   |
+/// |   18------19------20  |
+/// |           |           |
+/// 21----------22----------23
+/// ```
+pub const POINTS: usize = 24;
+const ALL_POINTS: u32 = (1 << POINTS) - 1;
+
+/// The amount of pieces each player places.
+const PIECES: u8 = 9;
+
+/// The amount of pieces left for a player to fly to any empty point.
+const FLYING_PIECES: u32 = 3;
+
+/// The amount of plies without mills after which the game is drawn, 50 moves
+/// per player.
+const INACTIVITY_PLIES: u8 = 100;
+
+/// How many times a position has to occur for the game to be drawn.
+const REPETITIONS: usize = 3;
+
+/// The evaluation weights, per piece, mill, and piece without an empty
+/// neighbour of the player to move over the opponent.
+const PIECE_WEIGHT: i32 = 100;
+const MILL_WEIGHT: i32 = 30;
+const BLOCKED_WEIGHT: i32 = 10;
+
+/// The Zobrist keys of every point for each player, followed by the key of the
+/// positions where [`Player::Human`] is to move.
+const ZOBRIST_KEYS: [u64; POINTS * 2 + 1] = zobrist_keys(0x3A11_5E7D);
+
+/// The lines of three points, the rows first and the columns second.
+const MILLS: [[usize; 3]; 16] = [
+	[0, 1, 2],
+	[3, 4, 5],
+	[6, 7, 8],
+	[9, 10, 11],
+	[12, 13, 14],
+	[15, 16, 17],
+	[18, 19, 20],
+	[21, 22, 23],
+	[0, 9, 21],
+	[3, 10, 18],
+	[6, 11, 15],
+	[1, 4, 7],
+	[16, 19, 22],
+	[8, 12, 17],
+	[5, 13, 20],
+	[2, 14, 23],
+];
+
+/// The points of the two mills every point is part of.
+const POINT_MILLS: [[u32; 2]; POINTS] = point_mills();
+
+/// The points linked to every point, the consecutive points of its mills.
+const ADJACENT: [u32; POINTS] = adjacent();
+
+/// A value of [`Move`] for the point a placed piece comes from, or for the
+/// piece it does not remove.
+const NONE: u8 = u8::MAX;
+
+const fn mask(mill: [usize; 3]) -> u32 {
+	1 << mill[0] | 1 << mill[1] | 1 << mill[2]
+}
+
+const fn point_mills() -> [[u32; 2]; POINTS] {
+	let mut table = [[0; 2]; POINTS];
+	let mut counts = [0; POINTS];
+	let mut m = 0;
+	while m < MILLS.len() {
+		let mut i = 0;
+		while i < 3 {
+			let point = MILLS[m][i];
+			table[point][counts[point]] = mask(MILLS[m]);
+			counts[point] += 1;
+			i += 1;
+		}
+		m += 1;
+	}
+
+	table
+}
+
+const fn adjacent() -> [u32; POINTS] {
+	let mut table = [0; POINTS];
+	let mut m = 0;
+	while m < MILLS.len() {
+		let [a, b, c] = MILLS[m];
+		table[a] |= 1 << b;
+		table[b] |= 1 << a | 1 << c;
+		table[c] |= 1 << b;
+		m += 1;
+	}
+
+	table
+}
+
+/// Iterates over the points set in `bits`.
+fn points(mut bits: u32) -> impl Iterator<Item = usize> {
+	std::iter::from_fn(move || {
+		(bits != 0).then(|| {
+			let point = bits.trailing_zeros() as usize;
+			bits &= bits - 1;
+			point
+		})
+	})
+}
+
+/// Whether the piece on `point` is part of a mill of `pieces`.
+fn in_mill(pieces: u32, point: usize) -> bool {
+	let [first, second] = POINT_MILLS[point];
+	pieces & first == first || pieces & second == second
+}
+
+/// How a player moves its pieces.
+#[napi]
+#[repr(u8)]
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "tune", derive(Clone, Copy))]
+pub enum Phase {
+	/// The player places the pieces in its hand on empty points.
+	Placing,
+	/// The player moves a piece to an adjacent empty point.
+	Moving,
+	/// The player has three pieces left, which move to any empty point.
+	Flying,
+}
+
+/// A move as exposed to JavaScript: the point a piece leaves, unless it is
+/// placed, the point it lands on, and the opponent's piece to remove if it
+/// closes a mill.
+#[napi(object)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MorrisMove {
+	/// The point the piece leaves, unset while placing pieces.
+	pub from: Option<u32>,
+	pub to: u32,
+	/// The point of the opponent's piece to remove, which must be set exactly
+	/// when the move closes a mill while the opponent has a piece on the
+	/// board.
+	pub remove: Option<u32>,
+}
+
+/// A placement or a move of a piece, with the opponent's piece it removes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Move {
+	/// The point the piece leaves, [`NONE`] for a placement.
+	from: u8,
+	to: u8,
+	/// The point of the removed piece, [`NONE`] if the move closes no mill.
+	remove: u8,
+}
+
+impl From<Move> for MorrisMove {
+	fn from(mv: Move) -> Self {
+		Self {
+			from: (mv.from != NONE).then(|| mv.from.into()),
+			to: mv.to.into(),
+			remove: (mv.remove != NONE).then(|| mv.remove.into()),
+		}
+	}
+}
+
+/// Reads the pieces left to place of the human and the machine, either the
+/// same amount for both or a `[human, machine]` pair, `default` unless given.
+fn hands(hand: Option<Either<u32, Vec<u32>>>, default: u8) -> std::result::Result<[u8; 2], String> {
+	let hands: [u32; 2] = match hand {
+		None => return Ok([default; 2]),
+		Some(Either::A(hand)) => [hand; 2],
+		Some(Either::B(hands)) => hands.try_into().map_err(|_| "hand must have exactly 2 numbers")?,
+	};
+
+	if hands.iter().any(|&hand| hand > PIECES.into()) {
+		return Err(format!("hand must be at most {PIECES}"));
+	}
+
+	Ok(hands.map(|hand| hand as u8))
+}
+
+/// A board alongside the pieces left to place, the player to move and the
+/// history needed by the draw rules, as searched by both engines.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Position {
+	human: u32,
+	machine: u32,
+	/// The pieces the human and the machine have yet to place.
+	hands: [u8; 2],
+	player: Player,
+	/// The plies since the last placement or mill.
+	quiet: u8,
+	/// The Zobrist keys of the positions since the last placement or mill,
+	/// including the current one, as earlier ones cannot occur again.
+	history: Vec<u64>,
+	outcome: Option<Outcome>,
+}
+
+impl Position {
+	fn new(human: u32, machine: u32, hands: [u8; 2], player: Player) -> Self {
+		let mut position = Self { human, machine, hands, player, quiet: 0, history: Vec::new(), outcome: None };
+		position.history.push(position.hash());
+		position.outcome = position.compute_outcome();
+		position
+	}
+
+	/// Returns a copy of this position with `player` to move, whose draw rules
+	/// count it as the latest position.
+	fn with_player(&self, player: Player) -> Self {
+		let mut position = Self { player, ..self.clone() };
+		position.history.pop();
+		position.history.push(position.hash());
+		position.outcome = position.compute_outcome();
+		position
+	}
+
+	/// Returns the pieces of `player` and of its opponent.
+	fn sides(&self, player: Player) -> (u32, u32) {
+		match player {
+			Player::Human => (self.human, self.machine),
+			Player::Machine => (self.machine, self.human),
+			Player::Unset => unreachable!(),
+		}
+	}
+
+	fn hand(&self, player: Player) -> u8 {
+		match player {
+			Player::Human => self.hands[0],
+			_ => self.hands[1],
+		}
+	}
+
+	fn phase(&self, player: Player) -> Phase {
+		let (own, _) = self.sides(player);
+		if self.hand(player) > 0 {
+			Phase::Placing
+		} else if own.count_ones() <= FLYING_PIECES {
+			Phase::Flying
+		} else {
+			Phase::Moving
+		}
+	}
+
+	fn empty(&self) -> u32 {
+		!(self.human | self.machine) & ALL_POINTS
+	}
+
+	fn point(&self, point: usize) -> Player {
+		if self.human & 1 << point != 0 {
+			Player::Human
+		} else if self.machine & 1 << point != 0 {
+			Player::Machine
+		} else {
+			Player::Unset
+		}
+	}
+
+	fn hash(&self) -> u64 {
+		let pieces = points(self.human | self.machine)
+			.fold(0, |hash, point| hash ^ ZOBRIST_KEYS[point * 2 + u8::from(self.point(point)) as usize - 1]);
+
+		match self.player {
+			Player::Human => pieces ^ ZOBRIST_KEYS[POINTS * 2],
+			_ => pieces,
+		}
+	}
+
+	/// Returns the pieces that can be removed from `pieces`: the ones outside
+	/// of mills, or any of them if they are all in mills.
+	fn removable(pieces: u32) -> u32 {
+		let free = points(pieces).filter(|&point| !in_mill(pieces, point)).fold(0, |bits, point| bits | 1 << point);
+		if free == 0 {
+			pieces
+		} else {
+			free
+		}
+	}
+
+	/// Returns the pieces of `player` without an empty neighbour.
+	fn blocked(&self, player: Player) -> u32 {
+		let (own, _) = self.sides(player);
+		let empty = self.empty();
+		points(own).filter(|&point| ADJACENT[point] & empty == 0).fold(0, |bits, point| bits | 1 << point)
+	}
+
+	/// Returns the legal moves of the player to move, ignoring the outcome. A
+	/// move closing a mill is listed once per piece it can remove, or without
+	/// removing any if the opponent has no piece on the board.
+	fn legal_moves(&self) -> Vec<Move> {
+		let (own, opponent) = self.sides(self.player);
+		let empty = self.empty();
+
+		let steps: Vec<(u8, usize)> = match self.phase(self.player) {
+			Phase::Placing => points(empty).map(|to| (NONE, to)).collect(),
+			Phase::Moving => {
+				points(own).flat_map(|from| points(ADJACENT[from] & empty).map(move |to| (from as u8, to))).collect()
+			}
+			Phase::Flying => points(own).flat_map(|from| points(empty).map(move |to| (from as u8, to))).collect(),
+		};
+
+		let mut moves = Vec::with_capacity(steps.len());
+		for (from, to) in steps {
+			let left = if from == NONE { 0 } else { 1 << from };
+			if in_mill(own & !left | 1 << to, to) && opponent != 0 {
+				for remove in points(Self::removable(opponent)) {
+					moves.push(Move { from, to: to as u8, remove: remove as u8 });
+				}
+			} else {
+				moves.push(Move { from, to: to as u8, remove: NONE });
+			}
+		}
+
+		moves
+	}
+
+	fn compute_outcome(&self) -> Option<Outcome> {
+		// A player reduced to two pieces, or unable to move, loses:
+		let (own, _) = self.sides(self.player);
+		let pieces = own.count_ones() + u32::from(self.hand(self.player));
+		let stuck = match self.phase(self.player) {
+			Phase::Moving => self.blocked(self.player) == own,
+			_ => self.empty() == 0,
+		};
+		if pieces < FLYING_PIECES || stuck {
+			return Some(Outcome::Win(self.player.opponent()));
+		}
+
+		let hash = self.history.last().copied().unwrap_or_default();
+		if self.quiet >= INACTIVITY_PLIES || self.history.iter().filter(|&&h| h == hash).count() >= REPETITIONS {
+			return Some(Outcome::Draw);
+		}
+
+		None
+	}
+
+	/// Scores the pieces of `player`, its mills, and its blocked pieces.
+	fn score(&self, player: Player) -> i32 {
+		let (own, _) = self.sides(player);
+		let pieces = own.count_ones() as i32 + self.hand(player) as i32;
+		let mills = MILLS.iter().filter(|&&mill| own & mask(mill) == mask(mill)).count() as i32;
+		let blocked = match self.phase(player) {
+			Phase::Moving => self.blocked(player).count_ones() as i32,
+			_ => 0,
+		};
+
+		PIECE_WEIGHT * pieces + MILL_WEIGHT * mills - BLOCKED_WEIGHT * blocked
+	}
+}
+
+impl Game for Position {
+	type Move = Move;
+
+	fn player(&self) -> Player {
+		self.player
+	}
+
+	fn moves(&self) -> Vec<Move> {
+		if self.outcome.is_some() {
+			return Vec::new();
+		}
+
+		self.legal_moves()
+	}
+
+	fn play(&mut self, mv: Move) {
+		let (mut own, mut opponent) = self.sides(self.player);
+		own |= 1 << mv.to;
+		if mv.from == NONE {
+			match self.player {
+				Player::Human => self.hands[0] -= 1,
+				_ => self.hands[1] -= 1,
+			}
+		} else {
+			own &= !(1 << mv.from);
+		}
+
+		if mv.remove != NONE {
+			opponent &= !(1 << mv.remove);
+		}
+
+		match self.player {
+			Player::Human => (self.human, self.machine) = (own, opponent),
+			_ => (self.machine, self.human) = (own, opponent),
+		}
+
+		self.player = self.player.opponent();
+
+		// Placing and removing pieces cannot be undone, so no earlier position
+		// can occur again:
+		if mv.from == NONE || mv.remove != NONE {
+			self.quiet = 0;
+			self.history.clear();
+		} else {
+			self.quiet += 1;
+		}
+
+		self.history.push(self.hash());
+		self.outcome = self.compute_outcome();
+	}
+
+	fn outcome(&self) -> Option<Outcome> {
+		self.outcome
+	}
+
+	/// Closes a mill if possible.
+	fn heuristic_move(&mut self, moves: &[Move], _random: &mut Random) -> Option<Move> {
+		moves.iter().copied().find(|mv| mv.remove != NONE)
+	}
+}
+
+impl Evaluate for Position {
+	fn evaluate(&self) -> i32 {
+		self.score(self.player) - self.score(self.player.opponent())
+	}
+}
+
+/// Nine men's morris: players place their 9 pieces in turns, then move them
+/// to adjacent points, and fly them to any empty point once they have 3 left.
+/// Forming a line of three pieces, a mill, removes a piece of the opponent,
+/// which loses once reduced to 2 pieces or unable to move.
+///
+/// The game is drawn after 50 moves per player without mills, or when a
+/// position occurs for the third time.
+#[napi]
+pub struct NineMensMorris {
+	position: Position,
+	mcts: Option<Box<MonteCarlo<Position>>>,
+	/// The counters of the last search.
+	stats: Stats,
+}
+
+impl NineMensMorris {
+	pub fn new(position: Position) -> Self {
+		Self { position, mcts: None, stats: Stats::default() }
+	}
+
+	/// Returns the best move for `player` using the selected engine, the depth
+	/// is only used by the alpha-beta engine. The search hands `player` the
+	/// turn if it is not theirs.
+	pub fn get_best_move(&mut self, maximum_depth: u8, player: Player) -> Option<Move> {
+		let start = Instant::now();
+
+		let handed;
+		let position = if player == self.position.player {
+			&self.position
+		} else {
+			handed = self.position.with_player(player);
+			&handed
+		};
+
+		let mv = if let Some(mut mcts) = self.mcts.take() {
+			let mv = mcts.search(position);
+			self.stats = mcts.stats();
+			self.mcts = Some(mcts);
+			mv
+		} else {
+			let mut search = AlphaBeta::default();
+			let mv = search.search(position, maximum_depth);
+			self.stats = search.stats();
+			mv
+		};
+
+		self.stats.elapsed = start.elapsed();
+		mv
+	}
+}
+
+#[napi]
+impl NineMensMorris {
+	/// Creates a board from its 24 points, which defaults to an empty board.
+	/// Each player has `hand` pieces left to place, or the human and the
+	/// machine have the ones of a `[human, machine]` pair, defaulting to 9 on
+	/// an empty board and 0 otherwise, and `turn` moves first, defaulting to
+	/// the human.
+	#[napi(constructor)]
+	pub fn js_new(
+		values: Option<Uint8Array>,
+		hand: Option<Either<u32, Vec<u32>>>,
+		turn: Option<Player>,
+	) -> Result<Self> {
+		let turn = turn.unwrap_or(Player::Human);
+		napi_assert!(turn != Player::Unset);
+
+		let default = if values.is_some() { 0 } else { PIECES };
+		let hands = hands(hand, default).map_err(Error::from_reason)?;
+
+		let (mut human, mut machine) = (0, 0);
+		if let Some(values) = values {
+			let input = values.to_vec();
+			if input.len() != POINTS {
+				return Err(Error::from_reason("data must have exactly 24 numbers"));
+			}
+
+			for (point, value) in input.into_iter().enumerate() {
+				match Player::try_from(value).map_err(Error::from_reason)? {
+					Player::Human => human |= 1 << point,
+					Player::Machine => machine |= 1 << point,
+					Player::Unset => {}
+				}
+			}
+		}
+
+		Ok(NineMensMorris::new(Position::new(human, machine, hands, turn)))
+	}
+
+	#[napi(getter = board)]
+	pub fn js_get_board(&self) -> Uint8Array {
+		Uint8Array::new((0..POINTS).map(|point| self.position.point(point).into()).collect())
+	}
+
+	/// The player to move.
+	#[napi(getter = turn)]
+	pub fn js_get_turn(&self) -> Player {
+		self.position.player
+	}
+
+	/// The amount of pieces `player` has yet to place.
+	#[napi(js_name = "inHand")]
+	pub fn js_in_hand(&self, player: Player) -> Result<u32> {
+		napi_assert!(player != Player::Unset);
+		Ok(self.position.hand(player).into())
+	}
+
+	/// How the player to move moves its pieces.
+	#[napi(getter = phase)]
+	pub fn js_get_phase(&self) -> Phase {
+		self.position.phase(self.position.player)
+	}
+
+	/// The legal moves of the player to move.
+	#[napi(getter = moves)]
+	pub fn js_get_moves(&self) -> Vec<MorrisMove> {
+		self.position.moves().into_iter().map(MorrisMove::from).collect()
+	}
+
+	#[napi(getter = finished)]
+	pub fn js_finished(&self) -> bool {
+		self.position.outcome.is_some()
+	}
+
+	/// The player who won, `Player.Unset` while the game is ongoing or if it
+	/// was drawn.
+	#[napi(getter = winner)]
+	pub fn js_get_winner(&self) -> Player {
+		match self.position.outcome {
+			Some(Outcome::Win(player)) => player,
+			_ => Player::Unset,
+		}
+	}
+
+	/// Plays a move of the player to move, returning whether it won the game.
+	#[napi(js_name = "add")]
+	pub fn js_add(&mut self, mv: MorrisMove) -> Result<bool> {
+		let Some(mv) = self.position.moves().into_iter().find(|&legal| MorrisMove::from(legal) == mv) else {
+			return Err(Error::from_reason("The move is not legal"));
+		};
+
+		let player = self.position.player;
+		self.position.play(mv);
+		if let Some(mcts) = self.mcts.as_mut() {
+			mcts.advance(mv);
+		}
+
+		Ok(self.position.outcome == Some(Outcome::Win(player)))
+	}
+
+	#[napi(getter = engine)]
+	pub fn js_get_engine(&self) -> Engine {
+		if self.mcts.is_some() {
+			Engine::MonteCarlo
+		} else {
+			Engine::AlphaBeta
+		}
+	}
+
+	#[napi(js_name = "setEngine")]
+	pub fn js_set_engine(&mut self, engine: Engine, options: Option<MonteCarloOptions>) -> Result<()> {
+		self.mcts = match engine {
+			Engine::AlphaBeta => None,
+			Engine::MonteCarlo => {
+				Some(Box::new(MonteCarlo::new(options.unwrap_or_default()).map_err(Error::from_reason)?))
+			}
+		};
+
+		Ok(())
+	}
+
+	/// The statistics of the last call to `getBestMove`, every counter is zero
+	/// before the first search.
+	#[napi(getter = lastSearchStats)]
+	pub fn js_get_last_search_stats(&self) -> SearchStats {
+		self.stats.into()
+	}
+
+	/// Returns the best move for the player to move, or for the player given
+	/// in the options, or `null` if the game is finished.
+	#[napi(js_name = "getBestMove")]
+	pub fn js_get_best_move(&mut self, options: Option<BestMoveArgument>) -> Result<Option<MorrisMove>> {
+		let (depth, player) = BestMoveOptions::resolve_or(options, 6, self.position.player)?;
+		Ok(self.get_best_move(depth, player).map(MorrisMove::from))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn bits(points: &[usize]) -> u32 {
+		points.iter().fold(0, |bits, &point| bits | 1 << point)
+	}
+
+	/// Creates a position from the points of each player, with `hand` pieces
+	/// left to place for both.
+	fn position(human: &[usize], machine: &[usize], hand: u8, player: Player) -> Position {
+		Position::new(bits(human), bits(machine), [hand; 2], player)
+	}
+
+	/// Returns the moves as `(from, to, remove)`.
+	fn moves(position: &Position) -> Vec<(u8, u8, u8)> {
+		let mut moves: Vec<_> = position.moves().iter().map(|mv| (mv.from, mv.to, mv.remove)).collect();
+		moves.sort();
+		moves
+	}
+
+	#[test]
+	fn test_tables() {
+		assert_eq!(ADJACENT[0], bits(&[1, 9]));
+		assert_eq!(ADJACENT[4], bits(&[1, 3, 5, 7]));
+		assert_eq!(ADJACENT[11], bits(&[6, 10, 15]));
+		assert_eq!(POINT_MILLS[19], [bits(&[18, 19, 20]), bits(&[16, 19, 22])]);
+	}
+
+	mod hands {
+		use super::*;
+
+		#[test]
+		fn test_hands() {
+			assert_eq!(hands(None, PIECES), Ok([PIECES; 2]));
+			assert_eq!(hands(Some(Either::A(4)), PIECES), Ok([4, 4]));
+			assert_eq!(hands(Some(Either::B(vec![3, 5])), 0), Ok([3, 5]));
+		}
+
+		#[test]
+		fn test_invalid_hands() {
+			assert_eq!(hands(Some(Either::A(10)), 0), Err("hand must be at most 9".into()));
+			assert_eq!(hands(Some(Either::B(vec![3, 10])), 0), Err("hand must be at most 9".into()));
+			assert_eq!(hands(Some(Either::B(vec![3])), 0), Err("hand must have exactly 2 numbers".into()));
+		}
+	}
+
+	mod moves {
+		use super::*;
+
+		#[test]
+		fn test_start() {
+			let position = position(&[], &[], PIECES, Player::Human);
+
+			assert_eq!(position.phase(Player::Human), Phase::Placing);
+			assert_eq!(position.moves().len(), POINTS);
+		}
+
+		#[test]
+		fn test_mill_removes() {
+			let position = position(&[0, 1], &[9, 10, 11, 20], 5, Player::Human);
+
+			// The pieces on 9, 10, and 11 form a mill, so only 20 can be removed:
+			assert!(moves(&position).contains(&(NONE, 2, 20)));
+			assert!(!moves(&position).contains(&(NONE, 2, NONE)));
+			assert_eq!(moves(&position).iter().filter(|mv| mv.1 == 2).count(), 1);
+		}
+
+		#[test]
+		fn test_mill_without_pieces_to_remove() {
+			let position = position(&[0, 1], &[], 5, Player::Human);
+			assert!(moves(&position).contains(&(NONE, 2, NONE)));
+		}
+
+		#[test]
+		fn test_remove_from_mills() {
+			let position = position(&[0, 1], &[9, 10, 11], 5, Player::Human);
+			assert_eq!(moves(&position).iter().filter(|mv| mv.1 == 2).count(), 3);
+		}
+
+		#[test]
+		fn test_moving() {
+			let position = position(&[0, 4, 13, 22], &[1, 3, 9, 23], 0, Player::Human);
+
+			assert_eq!(position.phase(Player::Human), Phase::Moving);
+			assert_eq!(
+				moves(&position),
+				vec![
+					(4, 5, NONE),
+					(4, 7, NONE),
+					(13, 5, NONE),
+					(13, 12, NONE),
+					(13, 14, NONE),
+					(13, 20, NONE),
+					(22, 19, NONE),
+					(22, 21, NONE)
+				]
+			);
+		}
+
+		#[test]
+		fn test_flying() {
+			let position = position(&[0, 4, 13], &[1, 3, 9, 23], 0, Player::Human);
+
+			assert_eq!(position.phase(Player::Human), Phase::Flying);
+			assert_eq!(position.moves().len(), 3 * 17);
+		}
+	}
+
+	mod play {
+		use super::*;
+
+		#[test]
+		fn test_place() {
+			let mut position = position(&[], &[], PIECES, Player::Human);
+			position.play(Move { from: NONE, to: 4, remove: NONE });
+
+			assert_eq!(position.human, bits(&[4]));
+			assert_eq!(position.hands, [8, 9]);
+			assert_eq!(position.player, Player::Machine);
+		}
+
+		#[test]
+		fn test_remove() {
+			let mut position = position(&[0, 1, 6, 14], &[9, 10, 20, 22], 0, Player::Human);
+			position.play(Move { from: 14, to: 2, remove: 20 });
+
+			assert_eq!(position.human, bits(&[0, 1, 2, 6]));
+			assert_eq!(position.machine, bits(&[9, 10, 22]));
+			assert_eq!(position.outcome, None);
+		}
+
+		#[test]
+		fn test_two_pieces_lose() {
+			let mut position = position(&[0, 1, 14], &[9, 10, 20], 0, Player::Human);
+			position.play(Move { from: 14, to: 2, remove: 20 });
+
+			assert_eq!(position.outcome, Some(Outcome::Win(Player::Human)));
+			assert_eq!(position.moves(), vec![]);
+		}
+
+		#[test]
+		fn test_blocked_loses() {
+			// The machine's pieces on 0, 1, 2, and 9 are surrounded once 21 is taken:
+			let mut position = position(&[3, 4, 5, 10, 14, 22, 23], &[0, 1, 2, 9], 0, Player::Human);
+			position.play(Move { from: 22, to: 21, remove: NONE });
+
+			assert_eq!(position.outcome, Some(Outcome::Win(Player::Human)));
+		}
+
+		#[test]
+		fn test_repetition() {
+			let mut position = position(&[0, 4, 13, 22], &[2, 3, 9, 20], 0, Player::Human);
+			for _ in 0..2 {
+				position.play(Move { from: 13, to: 12, remove: NONE });
+				position.play(Move { from: 20, to: 19, remove: NONE });
+				position.play(Move { from: 12, to: 13, remove: NONE });
+				assert_eq!(position.outcome, None);
+				position.play(Move { from: 19, to: 20, remove: NONE });
+			}
+
+			assert_eq!(position.outcome, Some(Outcome::Draw));
+		}
+
+		#[test]
+		fn test_inactivity() {
+			let mut position = position(&[0, 4, 13, 22], &[2, 3, 9, 20], 0, Player::Human);
+			position.quiet = INACTIVITY_PLIES - 1;
+			position.play(Move { from: 13, to: 12, remove: NONE });
+
+			assert_eq!(position.outcome, Some(Outcome::Draw));
+		}
+	}
+
+	mod evaluate {
+		use super::*;
+
+		#[test]
+		fn test_start() {
+			assert_eq!(position(&[], &[], PIECES, Player::Human).evaluate(), 0);
+		}
+
+		#[test]
+		fn test_material_and_mills() {
+			let position = position(&[0, 1, 2, 13], &[10, 11, 20], 0, Player::Machine);
+			assert_eq!(position.evaluate(), -PIECE_WEIGHT - MILL_WEIGHT);
+		}
+
+		#[test]
+		fn test_blocked() {
+			let position = position(&[0, 3, 5, 13, 19], &[1, 9, 10, 14, 23], 0, Player::Human);
+			assert_eq!(position.evaluate(), -BLOCKED_WEIGHT);
+		}
+	}
+
+	mod get_best_move {
+		use super::*;
+
+		macro_rules! gen_test {
+			($($name:ident: [$position:expr, $outcome:expr],)*) => ($(
+				#[test]
+				fn $name() {
+					let mut board = NineMensMorris::new($position);
+					assert_eq!(board.get_best_move(4, board.position.player).map(|mv| (mv.from, mv.to)), $outcome);
+				}
+			)*);
+		}
+
+		gen_test! {
+			test_close_mill: [position(&[9, 10], &[0, 1], 6, Player::Machine), Some((NONE, 2))],
+			test_block_mill: [position(&[0, 1, 13], &[4, 20], 6, Player::Machine), Some((NONE, 2))],
+			test_finished: [position(&[0, 1], &[9, 10, 11], 0, Player::Human), None],
+		}
+
+		#[test]
+		fn test_other_player() {
+			let mut board = NineMensMorris::new(position(&[9, 10], &[0, 1], 6, Player::Human));
+
+			assert_eq!(board.get_best_move(4, Player::Machine).map(|mv| (mv.from, mv.to)), Some((NONE, 2)));
+			assert_eq!(board.position.player, Player::Human);
+		}
+
+		#[test]
+		fn test_stats() {
+			let mut board = NineMensMorris::new(position(&[], &[], PIECES, Player::Human));
+			board.get_best_move(4, board.position.player);
+
+			assert!(board.stats.nodes > 0);
+			assert!(board.stats.cutoffs > 0);
+			assert_eq!(board.stats.max_depth, 4);
+		}
+
+		#[test]
+		fn test_monte_carlo() {
+			let mut board = NineMensMorris::new(position(&[9, 10], &[0, 1], 6, Player::Machine));
+			board
+				.js_set_engine(
+					Engine::MonteCarlo,
+					Some(MonteCarloOptions { iterations: Some(500), seed: Some(7), ..Default::default() }),
+				)
+				.unwrap();
+
+			assert_eq!(board.js_get_engine(), Engine::MonteCarlo);
+			assert_eq!(board.get_best_move(4, board.position.player).map(|mv| mv.to), Some(2));
+			assert!(board.stats.leaves > 0);
+		}
+	}
+}
@@ -0,0 +1,525 @@
+use std::{
+	cmp,
+	collections::HashMap,
+	sync::{LazyLock, Mutex, PoisonError},
+	time::Instant,
+};
+
+use napi::{bindgen_prelude::Uint8Array, Error, Result};
+
+use super::tic_tac_toe::{BOARD_CELLS, CELL_ORDER, LINES};
+use crate::{
+	isize_to_usize, napi_assert,
+	search::{
+		options::{BestMoveArgument, BestMoveOptions},
+		stats::{SearchStats, Stats},
+	},
+	Player, U_INVALID_INDEX,
+};
+
+/// The largest amount of boards of a game. Solving 4 empty boards visits
+/// about 200,000 positions, and 5 of them take ten times as many.
+pub const MAX_BOARDS: usize = 4;
+
+/// The rotations and reflections of a board, as the cell every cell takes its
+/// mark from.
+const SYMMETRIES: [[usize; BOARD_CELLS]; 8] = [
+	[0, 1, 2, 3, 4, 5, 6, 7, 8],
+	[6, 3, 0, 7, 4, 1, 8, 5, 2],
+	[8, 7, 6, 5, 4, 3, 2, 1, 0],
+	[2, 5, 8, 1, 4, 7, 0, 3, 6],
+	[2, 1, 0, 5, 4, 3, 8, 7, 6],
+	[6, 7, 8, 3, 4, 5, 0, 1, 2],
+	[0, 3, 6, 1, 4, 7, 2, 5, 8],
+	[8, 5, 2, 7, 4, 1, 6, 3, 0],
+];
+
+/// Whether every board, as a mask of its marked cells, holds a line.
+const DEAD: [bool; 1 << BOARD_CELLS] = dead_boards();
+/// The smallest mask of the symmetries of every board, so equivalent boards
+/// share it.
+const CANONICAL: [u16; 1 << BOARD_CELLS] = canonical_boards();
+
+const fn dead_boards() -> [bool; 1 << BOARD_CELLS] {
+	let mut dead = [false; 1 << BOARD_CELLS];
+	let mut board = 0;
+	while board < dead.len() {
+		let mut line = 0;
+		while line < LINES.len() {
+			let [a, b, c] = LINES[line];
+			let mask = 1 << a | 1 << b | 1 << c;
+			dead[board] |= board & mask == mask;
+			line += 1;
+		}
+		board += 1;
+	}
+
+	dead
+}
+
+const fn canonical_boards() -> [u16; 1 << BOARD_CELLS] {
+	let mut canonical = [0; 1 << BOARD_CELLS];
+	let mut board = 0;
+	while board < canonical.len() {
+		let mut smallest = u16::MAX;
+		let mut symmetry = 0;
+		while symmetry < SYMMETRIES.len() {
+			let mut mapped = 0;
+			let mut cell = 0;
+			while cell < BOARD_CELLS {
+				mapped |= ((board >> SYMMETRIES[symmetry][cell]) & 1) << cell;
+				cell += 1;
+			}
+
+			if (mapped as u16) < smallest {
+				smallest = mapped as u16;
+			}
+			symmetry += 1;
+		}
+
+		canonical[board] = smallest;
+		board += 1;
+	}
+
+	canonical
+}
+
+/// The options of a [`Notakto`] game.
+#[napi(object)]
+#[derive(Debug, Default)]
+pub struct NotaktoOptions {
+	/// The amount of boards, between 1 and 4, defaults to 1.
+	pub boards: Option<u32>,
+	/// The player to move, defaults to the human.
+	pub turn: Option<Player>,
+}
+
+/// Notakto, where both players mark cells of one or more tic-tac-toe boards
+/// with the same mark. A board with a line is dead and takes no more marks,
+/// and the player who kills the last board loses.
+///
+/// Cells are numbered by board, so cell `9 * b + c` is the cell `c` of the
+/// board `b`, numbered like the cells of `TicTacToe`.
+#[napi]
+pub struct Notakto {
+	/// The marked cells of every board, as a mask.
+	boards: Vec<u16>,
+	player: Player,
+	/// The counters of the last search.
+	stats: Stats,
+}
+
+/// Whether the player to move wins, per position made of the canonical masks
+/// of its live boards. A position does not depend on the game it comes from,
+/// so every game shares the positions solved by the previous searches.
+static SOLUTIONS: LazyLock<Mutex<HashMap<u64, bool>>> = LazyLock::new(Default::default);
+
+/// The key of a position made of the canonical masks of its live boards in
+/// ascending order, which leaves room for every mask to be zero.
+fn key(boards: &[u16]) -> u64 {
+	boards.iter().fold(0, |key, &board| key << 10 | (board as u64 + 1))
+}
+
+/// Replaces the board `index` of `boards` with `board`, returning the
+/// canonical masks of the live boards in ascending order.
+fn canonical(boards: &[u16], index: usize, board: u16) -> ([u16; MAX_BOARDS], usize) {
+	let mut live = [0; MAX_BOARDS];
+	let mut count = 0;
+	for (i, &b) in boards.iter().enumerate() {
+		let b = if i == index { board } else { b };
+		if !DEAD[b as usize] {
+			live[count] = CANONICAL[b as usize];
+			count += 1;
+		}
+	}
+
+	live[..count].sort_unstable();
+	(live, count)
+}
+
+impl Notakto {
+	pub fn new(boards: Vec<u16>, player: Player) -> Self {
+		debug_assert!((1..=MAX_BOARDS).contains(&boards.len()));
+
+		Self { boards, player, stats: Stats::default() }
+	}
+
+	fn available(&self, cell: usize) -> bool {
+		debug_assert!(cell < self.boards.len() * BOARD_CELLS);
+
+		let board = self.boards[cell / BOARD_CELLS];
+		!DEAD[board as usize] && board & 1 << (cell % BOARD_CELLS) == 0
+	}
+
+	fn finished(&self) -> bool {
+		self.boards.iter().all(|&board| DEAD[board as usize])
+	}
+
+	/// Marks `cell` for the player to move, returning whether it killed its
+	/// board.
+	fn play(&mut self, cell: usize) -> bool {
+		debug_assert!(self.available(cell));
+
+		let board = &mut self.boards[cell / BOARD_CELLS];
+		*board |= 1 << (cell % BOARD_CELLS);
+		self.player = self.player.opponent();
+		DEAD[*board as usize]
+	}
+
+	/// Whether the player to move wins the position made of the canonical
+	/// masks of the live boards in ascending order, `ply` moves after the root.
+	/// A position without live boards is won, as the opponent killed the last
+	/// one.
+	fn wins(&mut self, solutions: &mut HashMap<u64, bool>, live: &[u16], ply: u8) -> bool {
+		self.stats.nodes += 1;
+		self.stats.max_depth = cmp::max(self.stats.max_depth, ply);
+
+		if live.is_empty() {
+			self.stats.leaves += 1;
+			return true;
+		}
+
+		let key = key(live);
+		if let Some(&wins) = solutions.get(&key) {
+			self.stats.tt_hits += 1;
+			return wins;
+		}
+
+		let mut wins = false;
+		'search: for (index, &board) in live.iter().enumerate() {
+			// Equal boards have the same moves:
+			if index > 0 && live[index - 1] == board {
+				continue;
+			}
+
+			for cell in 0..BOARD_CELLS {
+				if board & 1 << cell != 0 {
+					continue;
+				}
+
+				let (child, count) = canonical(live, index, board | 1 << cell);
+				if !self.wins(solutions, &child[..count], ply + 1) {
+					self.stats.cutoffs += 1;
+					wins = true;
+					break 'search;
+				}
+			}
+		}
+
+		solutions.insert(key, wins);
+		wins
+	}
+
+	/// Returns a winning move for the player to move if there is one, otherwise
+	/// the first one that does not kill a board, to make the opponent work for
+	/// the win, or the first one if they all do.
+	pub fn get_best_move(&mut self) -> usize {
+		let start = Instant::now();
+		self.stats = Stats::default();
+		let mut solutions = SOLUTIONS.lock().unwrap_or_else(PoisonError::into_inner);
+
+		let mut fallback = None;
+		let mut best = None;
+		'search: for b in 0..self.boards.len() {
+			for cell in CELL_ORDER {
+				let index = b * BOARD_CELLS + cell;
+				if !self.available(index) {
+					continue;
+				}
+
+				let board = self.boards[b] | 1 << cell;
+				let (child, count) = canonical(&self.boards, b, board);
+				if !self.wins(&mut solutions, &child[..count], 1) {
+					self.stats.cutoffs += 1;
+					best = Some(index);
+					break 'search;
+				}
+
+				if fallback.is_none_or(|(kills, _)| kills && !DEAD[board as usize]) {
+					fallback = Some((DEAD[board as usize], index));
+				}
+			}
+		}
+
+		self.stats.elapsed = start.elapsed();
+		best.or(fallback.map(|(_, index)| index)).unwrap_or(U_INVALID_INDEX)
+	}
+}
+
+#[napi]
+impl Notakto {
+	/// Creates a game from the cells of its boards, where any non-zero value is
+	/// a mark, which defaults to empty boards.
+	#[napi(constructor)]
+	pub fn js_new(values: Option<Uint8Array>, options: Option<NotaktoOptions>) -> Result<Self> {
+		let options = options.unwrap_or_default();
+		let turn = options.turn.unwrap_or(Player::Human);
+		napi_assert!(turn != Player::Unset);
+
+		let count = options.boards.unwrap_or(1) as usize;
+		if !(1..=MAX_BOARDS).contains(&count) {
+			return Err(Error::from_reason(format!("boards must be between 1 and {MAX_BOARDS}")));
+		}
+
+		let mut boards = vec![0; count];
+		if let Some(v) = values {
+			let input = v.to_vec();
+			if input.len() != count * BOARD_CELLS {
+				return Err(Error::from_reason(format!("data must have exactly {} numbers", count * BOARD_CELLS)));
+			}
+
+			for (cell, &value) in input.iter().enumerate() {
+				if value != 0 {
+					boards[cell / BOARD_CELLS] |= 1 << (cell % BOARD_CELLS);
+				}
+			}
+		}
+
+		Ok(Notakto::new(boards, turn))
+	}
+
+	/// The cells of every board, 1 for the marked ones.
+	#[napi(getter = board)]
+	pub fn js_get_board(&self) -> Uint8Array {
+		let cells = (0..self.boards.len() * BOARD_CELLS).map(|cell| {
+			let board = self.boards[cell / BOARD_CELLS];
+			(board >> (cell % BOARD_CELLS) & 1) as u8
+		});
+
+		Uint8Array::new(cells.collect())
+	}
+
+	/// The amount of boards.
+	#[napi(getter = boards)]
+	pub fn js_get_boards(&self) -> u32 {
+		self.boards.len() as u32
+	}
+
+	/// Whether `board` has no line yet, and so takes marks.
+	#[napi(js_name = "alive")]
+	pub fn js_alive(&self, board: i32) -> Result<bool> {
+		Ok(!DEAD[self.boards[isize_to_usize!(board, self.boards.len())?] as usize])
+	}
+
+	#[napi(getter = turn)]
+	pub fn js_get_turn(&self) -> Player {
+		self.player
+	}
+
+	#[napi(js_name = "available")]
+	pub fn js_available(&self, cell: i32) -> Result<bool> {
+		Ok(self.available(isize_to_usize!(cell, self.boards.len() * BOARD_CELLS)?))
+	}
+
+	#[napi(getter = finished)]
+	pub fn js_finished(&self) -> bool {
+		self.finished()
+	}
+
+	/// The player who won, as the opponent killed the last board,
+	/// `Player.Unset` while the game is ongoing.
+	#[napi(getter = winner)]
+	pub fn js_get_winner(&self) -> Player {
+		if self.finished() {
+			self.player
+		} else {
+			Player::Unset
+		}
+	}
+
+	/// Marks `cell` for the player to move, returning whether it completed a
+	/// line, which loses the game if it killed the last board.
+	#[napi(js_name = "add")]
+	pub fn js_add(&mut self, cell: i32) -> Result<bool> {
+		let c = isize_to_usize!(cell, self.boards.len() * BOARD_CELLS)?;
+		napi_assert!(self.available(c));
+
+		Ok(self.play(c))
+	}
+
+	/// The statistics of the last call to `getBestMove`, every counter is zero
+	/// before the first search. Positions solved by previous searches, of any
+	/// game, count as transposition table hits.
+	#[napi(getter = lastSearchStats)]
+	pub fn js_get_last_search_stats(&self) -> SearchStats {
+		self.stats.into()
+	}
+
+	/// Returns the best cell for the player to move, or for the player given
+	/// in the options, or `INVALID_INDEX` if the game is finished. The game is
+	/// solved, so a winning move is found whenever there is one. When every
+	/// move loses, the first one that does not kill a board is returned, to
+	/// make the opponent work for the win, or the first one if they all do.
+	///
+	/// Both players make the same marks, so the best cell is the same for
+	/// either of them, and the depth is ignored as the search always plays to
+	/// the end of the game.
+	///
+	/// The first search of 4 empty boards blocks for a fraction of a second
+	/// in release builds. The solved positions are shared by every game, so
+	/// later searches mostly find them in the table.
+	#[napi(js_name = "getBestMove")]
+	pub fn js_get_best_move(&mut self, options: Option<BestMoveArgument>) -> Result<i32> {
+		BestMoveOptions::resolve_or(options, 0, self.player)?;
+		Ok(self.get_best_move() as i32)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use napi::bindgen_prelude::Either;
+
+	use super::*;
+	use crate::search::random::Random;
+
+	/// Creates a game with the marked cells of every board.
+	fn game(boards: &[&[usize]], player: Player) -> Notakto {
+		let boards = boards.iter().map(|cells| cells.iter().fold(0, |board, &cell| board | 1 << cell)).collect();
+		Notakto::new(boards, player)
+	}
+
+	mod tables {
+		use std::collections::HashSet;
+
+		use super::*;
+
+		#[test]
+		fn test_dead() {
+			assert_eq!(DEAD.iter().filter(|&&dead| !dead).count(), 230);
+			assert!(DEAD[0o124] && DEAD[0o777]);
+			assert!(!DEAD[0] && !DEAD[0o033]);
+		}
+
+		#[test]
+		fn test_canonical() {
+			let live: HashSet<u16> = (0..1 << BOARD_CELLS).filter(|&b| !DEAD[b]).map(|b| CANONICAL[b]).collect();
+			assert_eq!(live.len(), 46);
+
+			// Every corner, and every edge, is the same:
+			for corner in [0, 2, 6, 8] {
+				assert_eq!(CANONICAL[1 << corner], 1);
+			}
+			for edge in [1, 3, 5, 7] {
+				assert_eq!(CANONICAL[1 << edge], 2);
+			}
+			assert_eq!(CANONICAL[1 << 4], 1 << 4);
+		}
+	}
+
+	mod wins {
+		use super::*;
+
+		macro_rules! gen_test {
+			($($name:ident: [$boards:expr, $outcome:expr],)*) => ($(
+				#[test]
+				fn $name() {
+					let mut notakto = game(&$boards, Player::Human);
+					let boards = notakto.boards.clone();
+					let (live, count) = canonical(&boards, usize::MAX, 0);
+
+					assert_eq!(notakto.wins(&mut HashMap::new(), &live[..count], 0), $outcome);
+				}
+			)*);
+		}
+
+		gen_test! {
+			test_empty: [[&[]], true],
+			// The center wins a single board:
+			test_center: [[&[4]], false],
+			// X X v
+			// X X v
+			// v v v
+			test_every_cell_kills: [[&[0, 1, 3, 4]], false],
+			test_two_dead_ends: [[&[0, 1, 3, 4], &[0, 1, 3, 4]], true],
+			test_dead_board: [[&[0, 1, 2], &[4]], false],
+			// The second player wins on two boards, but the first one on three:
+			test_two_empty: [[&[], &[]], false],
+			test_three_empty: [[&[], &[], &[]], true],
+		}
+	}
+
+	mod get_best_move {
+		use super::*;
+
+		#[test]
+		fn test_single_board() {
+			let mut notakto = game(&[&[]], Player::Machine);
+
+			assert_eq!(notakto.get_best_move(), 4);
+			assert!(notakto.stats.nodes > 0);
+			assert!(notakto.stats.leaves <= notakto.stats.nodes);
+			assert!(notakto.stats.max_depth > 0);
+			assert!(notakto.stats.cutoffs > 0);
+		}
+
+		#[test]
+		fn test_shared_solutions() {
+			game(&[&[], &[4]], Player::Machine).get_best_move();
+
+			let mut notakto = game(&[&[], &[4]], Player::Machine);
+			notakto.get_best_move();
+			assert!(notakto.stats.tt_hits > 0);
+			assert_eq!(notakto.stats.leaves, 0);
+		}
+
+		#[test]
+		fn test_skips_dead_boards() {
+			let mut notakto = game(&[&[0, 1, 2], &[]], Player::Machine);
+
+			assert_eq!(notakto.get_best_move(), BOARD_CELLS + 4);
+		}
+
+		#[test]
+		fn test_losing_avoids_killing() {
+			// The center loses a single board, but no corner kills it yet:
+			assert_eq!(game(&[&[4]], Player::Machine).get_best_move(), 0);
+
+			// X X v
+			// X X _
+			// _ _ _
+			assert_eq!(game(&[&[0, 1, 3, 4]], Player::Machine).get_best_move(), 2);
+		}
+
+		#[test]
+		fn test_other_player() {
+			let mut notakto = game(&[&[]], Player::Machine);
+			let options = BestMoveOptions { depth: None, player: Some(Player::Human) };
+
+			assert_eq!(notakto.js_get_best_move(Some(Either::B(options))).unwrap(), 4);
+			assert_eq!(notakto.player, Player::Machine);
+		}
+
+		#[test]
+		fn test_finished() {
+			let mut notakto = game(&[&[0, 1, 2]], Player::Machine);
+
+			assert!(notakto.finished());
+			assert_eq!(notakto.get_best_move(), U_INVALID_INDEX);
+		}
+
+		#[test]
+		fn test_wins_against_random() {
+			// The machine takes the winning side, which moves second on 2 boards:
+			for (boards, first) in
+				[(1, Player::Machine), (2, Player::Human), (3, Player::Machine), (4, Player::Machine)]
+			{
+				for seed in 0..5 {
+					let mut notakto = Notakto::new(vec![0; boards], first);
+					let mut random = Random::new(seed);
+					while !notakto.finished() {
+						let cell = if notakto.player == Player::Machine {
+							notakto.get_best_move()
+						} else {
+							let cells: Vec<usize> =
+								(0..boards * BOARD_CELLS).filter(|&c| notakto.available(c)).collect();
+							random.pick(&cells)
+						};
+						notakto.play(cell);
+					}
+
+					assert_eq!(notakto.js_get_winner(), Player::Machine, "{boards} boards, seed {seed}");
+				}
+			}
+		}
+	}
+}
@@ -0,0 +1,657 @@
+use std::{cmp, time::Instant};
+
+use napi::{bindgen_prelude::Uint8Array, Error, Result};
+
+use crate::{
+	isize_to_usize, napi_assert,
+	search::{
+		options::{BestMoveArgument, BestMoveOptions},
+		stats::{SearchStats, Stats},
+	},
+	Player, U_INVALID_INDEX,
+};
+
+pub const BOARD_WIDTH: usize = 8;
+pub const BOARD_CELLS: usize = BOARD_WIDTH * BOARD_WIDTH;
+
+/// The score of a won game from the perspective of the winner, adjusted by the
+/// disc difference so the search prefers the largest wins and the smallest
+/// losses.
+const OUTCOME_WIN: i32 = 1_000_000;
+const OUTCOME_DRAW: i32 = 0;
+
+/// The amount of empty cells from which the search plays the game to its end,
+/// scoring the final disc count instead of the evaluation.
+const ENDGAME_EMPTIES: u32 = 14;
+
+/// The evaluation weights of every corner, legal move and stable disc the
+/// machine has over the human.
+const CORNER_WEIGHT: i32 = 30;
+const MOBILITY_WEIGHT: i32 = 8;
+const STABILITY_WEIGHT: i32 = 12;
+
+const CORNERS: u64 = 0x8100_0000_0000_0081;
+/// Every cell but the ones in the first column.
+const NOT_FIRST_COLUMN: u64 = 0xFEFE_FEFE_FEFE_FEFE;
+/// Every cell but the ones in the last column.
+const NOT_LAST_COLUMN: u64 = 0x7F7F_7F7F_7F7F_7F7F;
+
+/// The offset between two neighbouring cells in each of the 8 directions, a
+/// cell `y * 8 + x` being stored in the bit of the same index.
+const DIRECTIONS: [i8; 8] = [1, -1, 8, -8, 9, -9, 7, -7];
+
+/// Moves every cell of `bits` one step in a direction, dropping the ones that
+/// leave the board.
+fn shift(bits: u64, direction: i8) -> u64 {
+	match direction {
+		1 => (bits << 1) & NOT_FIRST_COLUMN,
+		-1 => (bits >> 1) & NOT_LAST_COLUMN,
+		8 => bits << 8,
+		-8 => bits >> 8,
+		9 => (bits << 9) & NOT_FIRST_COLUMN,
+		-9 => (bits >> 9) & NOT_LAST_COLUMN,
+		7 => (bits << 7) & NOT_LAST_COLUMN,
+		-7 => (bits >> 7) & NOT_FIRST_COLUMN,
+		_ => unreachable!(),
+	}
+}
+
+/// Returns the legal moves of the player owning `own` against `opponent`: the
+/// empty cells that close a line of opponent discs ended by an own disc.
+fn moves(own: u64, opponent: u64) -> u64 {
+	let empty = !(own | opponent);
+
+	let mut moves = 0;
+	for direction in DIRECTIONS {
+		// A line holds at most 6 discs between both ends:
+		let mut line = shift(own, direction) & opponent;
+		for _ in 0..5 {
+			line |= shift(line, direction) & opponent;
+		}
+
+		moves |= shift(line, direction) & empty;
+	}
+
+	moves
+}
+
+/// Returns the opponent discs flipped by placing a disc in `cell`.
+fn flips(own: u64, opponent: u64, cell: usize) -> u64 {
+	let mut flipped = 0;
+	for direction in DIRECTIONS {
+		let mut line = 0;
+		let mut next = shift(1 << cell, direction);
+		while next & opponent != 0 {
+			line |= next;
+			next = shift(next, direction);
+		}
+
+		if next & own != 0 {
+			flipped |= line;
+		}
+	}
+
+	flipped
+}
+
+/// Returns the discs of `own` that can never be flipped: the ones that, along
+/// each of the 4 lines through them, touch the edge of the board or another
+/// stable disc. It misses some stable discs, such as the ones in full lines,
+/// but never reports an unstable one.
+fn stable(own: u64) -> u64 {
+	let mut stable = 0;
+	loop {
+		let mut next = own;
+		for direction in [1, 8, 9, 7] {
+			// The cells whose neighbour is outside of the board or stable, in
+			// either side of the line:
+			let edges = !shift(u64::MAX, -direction) | !shift(u64::MAX, direction);
+			next &= edges | shift(stable, direction) | shift(stable, -direction);
+		}
+
+		if next == stable {
+			return stable;
+		}
+
+		stable = next;
+	}
+}
+
+#[napi]
+pub struct Othello {
+	human: u64,
+	machine: u64,
+	/// The counters of the last search.
+	stats: Stats,
+}
+
+impl Othello {
+	/// Creates a board from its cells.
+	pub fn new(cells: &[Player; BOARD_CELLS]) -> Self {
+		let bits = |player: Player| (0..BOARD_CELLS).filter(|&c| cells[c] == player).fold(0, |bits, c| bits | 1 << c);
+		Self { human: bits(Player::Human), machine: bits(Player::Machine), stats: Stats::default() }
+	}
+
+	/// Returns the discs of `player` and of its opponent.
+	fn sides(&self, player: Player) -> (u64, u64) {
+		match player {
+			Player::Human => (self.human, self.machine),
+			Player::Machine => (self.machine, self.human),
+			Player::Unset => unreachable!(),
+		}
+	}
+
+	fn moves(&self, player: Player) -> u64 {
+		let (own, opponent) = self.sides(player);
+		moves(own, opponent)
+	}
+
+	fn available(&self, cell: usize, player: Player) -> bool {
+		debug_assert!(cell < BOARD_CELLS);
+
+		self.moves(player) & 1 << cell != 0
+	}
+
+	/// Places a disc of `player` in `cell`, flipping the discs it closes.
+	fn add(&mut self, cell: usize, player: Player) {
+		debug_assert!(self.available(cell, player));
+
+		let (own, opponent) = self.sides(player);
+		let flipped = flips(own, opponent, cell);
+		let (own, opponent) = (own | flipped | 1 << cell, opponent & !flipped);
+		match player {
+			Player::Human => (self.human, self.machine) = (own, opponent),
+			_ => (self.machine, self.human) = (own, opponent),
+		}
+	}
+
+	fn finished(&self) -> bool {
+		self.moves(Player::Human) == 0 && self.moves(Player::Machine) == 0
+	}
+
+	fn count(&self, player: Player) -> u32 {
+		self.sides(player).0.count_ones()
+	}
+
+	fn empty(&self) -> u32 {
+		(!(self.human | self.machine)).count_ones()
+	}
+
+	/// Scores a finished game from the perspective of `player`, by the
+	/// difference of discs.
+	fn outcome(&self, player: Player) -> i32 {
+		let difference = self.count(player) as i32 - self.count(player.opponent()) as i32;
+		match difference.cmp(&0) {
+			cmp::Ordering::Greater => OUTCOME_WIN + difference,
+			cmp::Ordering::Less => -OUTCOME_WIN + difference,
+			cmp::Ordering::Equal => OUTCOME_DRAW,
+		}
+	}
+
+	/// Scores the board from the perspective of the machine, by the corners
+	/// taken, the legal moves and the stable discs of each player.
+	fn evaluate(&self) -> i32 {
+		let difference = |machine: u64, human: u64| machine.count_ones() as i32 - human.count_ones() as i32;
+
+		CORNER_WEIGHT * difference(self.machine & CORNERS, self.human & CORNERS)
+			+ MOBILITY_WEIGHT * difference(self.moves(Player::Machine), self.moves(Player::Human))
+			+ STABILITY_WEIGHT * difference(stable(self.machine), stable(self.human))
+	}
+
+	/// Orders the moves of `player`, trying the corners first and then the
+	/// moves leaving the opponent the fewest replies, which also finds the
+	/// fastest endgame wins early.
+	fn order_moves(&self, player: Player, legal: u64) -> ([u8; BOARD_CELLS], usize) {
+		let (own, opponent) = self.sides(player);
+
+		let mut keyed = [(0u8, 0u8); BOARD_CELLS];
+		let mut count = 0;
+		let mut remaining = legal;
+		while remaining != 0 {
+			let cell = remaining.trailing_zeros() as usize;
+			remaining &= remaining - 1;
+
+			let key = if CORNERS & 1 << cell != 0 {
+				0
+			} else {
+				let flipped = flips(own, opponent, cell);
+				1 + moves(opponent & !flipped, own | flipped | 1 << cell).count_ones() as u8
+			};
+
+			keyed[count] = (key, cell as u8);
+			count += 1;
+		}
+
+		keyed[..count].sort_by_key(|&(key, _)| key);
+
+		let mut cells = [0; BOARD_CELLS];
+		for (cell, &(_, c)) in cells.iter_mut().zip(&keyed[..count]) {
+			*cell = c;
+		}
+
+		(cells, count)
+	}
+
+	/// Scores the position from the perspective of `player`, who is to move,
+	/// `passed` tells whether the opponent had no move to play, which ends the
+	/// game if `player` has none either.
+	fn negamax(&mut self, player: Player, remaining: u8, alpha: i32, beta: i32, passed: bool) -> i32 {
+		self.stats.visit(remaining);
+
+		let moves = self.moves(player);
+		if moves == 0 {
+			if passed {
+				self.stats.leaves += 1;
+				return self.outcome(player);
+			}
+
+			return -self.negamax(player.opponent(), remaining, -beta, -alpha, true);
+		}
+
+		if remaining == 0 {
+			self.stats.leaves += 1;
+			return match player {
+				Player::Machine => self.evaluate(),
+				_ => -self.evaluate(),
+			};
+		}
+
+		let (human, machine) = (self.human, self.machine);
+		let mut max_v = i32::MIN;
+		let mut local_alpha = alpha;
+		let (cells, count) = self.order_moves(player, moves);
+		for &cell in &cells[..count] {
+			self.add(cell as usize, player);
+			let m = -self.negamax(player.opponent(), remaining - 1, -beta, -local_alpha, false);
+			(self.human, self.machine) = (human, machine);
+
+			if m > max_v {
+				max_v = m;
+
+				local_alpha = cmp::max(local_alpha, max_v);
+				if local_alpha >= beta {
+					self.stats.cutoffs += 1;
+					break;
+				}
+			}
+		}
+
+		max_v
+	}
+
+	fn max_top(&mut self, player: Player, remaining: u8) -> usize {
+		let moves = self.moves(player);
+		if moves == 0 || remaining == 0 {
+			return U_INVALID_INDEX;
+		}
+
+		// The root is not visited, its children are the first ply:
+		self.stats = Stats::new(remaining - 1);
+
+		let (human, machine) = (self.human, self.machine);
+		let mut max_v = i32::MIN;
+		let mut best = U_INVALID_INDEX;
+		let mut alpha = -i32::MAX;
+		let (cells, count) = self.order_moves(player, moves);
+		for &cell in &cells[..count] {
+			self.add(cell as usize, player);
+			let points = -self.negamax(player.opponent(), remaining - 1, -i32::MAX, -alpha, false);
+			(self.human, self.machine) = (human, machine);
+
+			if points > max_v {
+				max_v = points;
+				best = cell as usize;
+				alpha = max_v;
+			}
+		}
+
+		best
+	}
+
+	/// Returns the best move for `player`, or [`U_INVALID_INDEX`] if it has to
+	/// pass. With [`ENDGAME_EMPTIES`] or fewer empty cells, the game is solved
+	/// regardless of the depth, as every move fills one of them.
+	pub fn get_best_move(&mut self, maximum_depth: u8, player: Player) -> usize {
+		let start = Instant::now();
+		self.stats = Stats::default();
+
+		let empty = self.empty();
+		let remaining = if empty <= ENDGAME_EMPTIES { empty as u8 } else { maximum_depth };
+		let cell = self.max_top(player, remaining);
+
+		self.stats.elapsed = start.elapsed();
+		cell
+	}
+}
+
+#[napi]
+impl Othello {
+	/// Creates a board from its 64 cells, row by row, which defaults to the
+	/// starting position with the human playing first.
+	#[napi(constructor)]
+	pub fn js_new(values: Option<Uint8Array>) -> Result<Self> {
+		let mut cells = [Player::Unset; BOARD_CELLS];
+		if let Some(v) = values {
+			let input = v.to_vec();
+			if input.len() != BOARD_CELLS {
+				return Err(Error::from_reason("data must have exactly 64 numbers"));
+			}
+
+			for (cell, value) in cells.iter_mut().zip(input) {
+				*cell = Player::try_from(value).map_err(Error::from_reason)?;
+			}
+		} else {
+			(cells[27], cells[36]) = (Player::Machine, Player::Machine);
+			(cells[28], cells[35]) = (Player::Human, Player::Human);
+		}
+
+		Ok(Othello::new(&cells))
+	}
+
+	#[napi(getter = board)]
+	pub fn js_get_board(&self) -> Uint8Array {
+		Uint8Array::new(
+			(0..BOARD_CELLS)
+				.map(|c| match (self.human >> c & 1, self.machine >> c & 1) {
+					(1, _) => Player::Human as u8,
+					(_, 1) => Player::Machine as u8,
+					_ => Player::Unset as u8,
+				})
+				.collect(),
+		)
+	}
+
+	#[napi(js_name = "available")]
+	pub fn js_available(&self, cell: i32, player: Player) -> Result<bool> {
+		napi_assert!(player != Player::Unset);
+		Ok(self.available(isize_to_usize!(cell, BOARD_CELLS)?, player))
+	}
+
+	/// The cells where `player` can place a disc, a player without any has to
+	/// pass.
+	#[napi(js_name = "moves")]
+	pub fn js_moves(&self, player: Player) -> Result<Vec<u32>> {
+		napi_assert!(player != Player::Unset);
+		Ok((0..BOARD_CELLS as u32).filter(|&c| self.moves(player) & 1 << c != 0).collect())
+	}
+
+	/// Whether neither player can place a disc.
+	#[napi(getter = finished)]
+	pub fn js_finished(&self) -> bool {
+		self.finished()
+	}
+
+	/// The amount of discs of `player`, which decides the winner once the game
+	/// is finished.
+	#[napi(js_name = "count")]
+	pub fn js_count(&self, player: Player) -> Result<u32> {
+		napi_assert!(player != Player::Unset);
+		Ok(self.count(player))
+	}
+
+	/// Places a disc of `player`, returning whether it finished the game with
+	/// `player` having more discs.
+	#[napi(js_name = "add")]
+	pub fn js_add(&mut self, cell: i32, player: Player) -> Result<bool> {
+		let c = isize_to_usize!(cell, BOARD_CELLS)?;
+		napi_assert!(player != Player::Unset);
+		napi_assert!(self.available(c, player));
+
+		self.add(c, player);
+		Ok(self.finished() && self.count(player) > self.count(player.opponent()))
+	}
+
+	/// The statistics of the last call to `getBestMove`, every counter is zero
+	/// before the first search.
+	#[napi(getter = lastSearchStats)]
+	pub fn js_get_last_search_stats(&self) -> SearchStats {
+		self.stats.into()
+	}
+
+	/// Returns the best cell for the machine, or for the player given in the
+	/// options, or `INVALID_INDEX` if it has to pass. The last 14 empty cells
+	/// are always searched to the end of the game.
+	#[napi(js_name = "getBestMove")]
+	pub fn js_get_best_move(&mut self, options: Option<BestMoveArgument>) -> Result<i32> {
+		let (depth, player) = BestMoveOptions::resolve(options, 6)?;
+		Ok(self.get_best_move(depth, player) as i32)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Creates a board from a drawing of its rows, where `x` is a human disc,
+	/// `o` a machine disc, and anything else an empty cell.
+	fn board(rows: [&str; BOARD_WIDTH]) -> Othello {
+		let mut cells = [Player::Unset; BOARD_CELLS];
+		for (y, row) in rows.iter().enumerate() {
+			for (x, c) in row.chars().enumerate() {
+				cells[y * BOARD_WIDTH + x] = match c {
+					'x' => Player::Human,
+					'o' => Player::Machine,
+					_ => Player::Unset,
+				};
+			}
+		}
+
+		Othello::new(&cells)
+	}
+
+	fn start() -> Othello {
+		board(["........", "........", "........", "...ox...", "...xo...", "........", "........", "........"])
+	}
+
+	fn cells(bits: u64) -> Vec<usize> {
+		(0..BOARD_CELLS).filter(|&c| bits & 1 << c != 0).collect()
+	}
+
+	mod moves {
+		use super::*;
+
+		#[test]
+		fn test_start() {
+			let board = start();
+
+			assert_eq!(cells(board.moves(Player::Human)), vec![19, 26, 37, 44]);
+			assert_eq!(cells(board.moves(Player::Machine)), vec![20, 29, 34, 43]);
+		}
+
+		#[test]
+		fn test_no_wrap_around() {
+			// The discs at the end of a row do not close the start of the next:
+			let board =
+				board(["......xo", "o.......", "........", "........", "........", "........", "........", "........"]);
+
+			assert_eq!(board.moves(Player::Human), 0);
+		}
+
+		#[test]
+		fn test_pass() {
+			// The human can only play below the machine's discs:
+			let board =
+				board(["xxxxxxxx", "oooooooo", "........", "........", "........", "........", "........", "........"]);
+
+			assert_eq!(board.moves(Player::Machine), 0);
+			assert_eq!(cells(board.moves(Player::Human)), (16..24).collect::<Vec<_>>());
+			assert!(!board.finished());
+		}
+	}
+
+	mod flips {
+		use super::*;
+
+		#[test]
+		fn test_start() {
+			let board = start();
+
+			assert_eq!(cells(flips(board.human, board.machine, 19)), vec![27]);
+		}
+
+		#[test]
+		fn test_several_directions() {
+			// The disc at d4 closes a row, a column and a diagonal:
+			let mut board =
+				board(["x..x....", ".o.o....", "..oo....", "xoo.ooox", "..o.....", ".o......", "x.......", "........"]);
+			board.add(27, Player::Human);
+
+			assert_eq!(cells(board.human), vec![0, 3, 9, 11, 18, 19, 24, 25, 26, 27, 28, 29, 30, 31, 34, 41, 48]);
+			assert_eq!(board.machine, 0);
+		}
+
+		#[test]
+		fn test_open_line() {
+			// A line that does not end in an own disc is not flipped:
+			let board =
+				board([".oo.....", "........", "........", "........", "........", "........", "........", "........"]);
+
+			assert_eq!(flips(board.human, board.machine, 3), 0);
+		}
+	}
+
+	mod stable {
+		use super::*;
+
+		#[test]
+		fn test_corner() {
+			let board =
+				board(["oo......", "o.......", "........", "........", "........", "........", "........", "........"]);
+
+			assert_eq!(cells(stable(board.machine)), vec![0, 1, 8]);
+		}
+
+		#[test]
+		fn test_unanchored_edge() {
+			let board =
+				board([".oo.....", "........", "........", "........", "........", "........", "........", "........"]);
+
+			assert_eq!(stable(board.machine), 0);
+		}
+
+		#[test]
+		fn test_filled_corner_area() {
+			let board =
+				board(["ooo.....", "oo......", "o.......", "........", "........", "........", "........", "........"]);
+
+			assert_eq!(cells(stable(board.machine)), vec![0, 1, 2, 8, 9, 16]);
+		}
+	}
+
+	mod evaluate {
+		use super::*;
+
+		#[test]
+		fn test_start() {
+			assert_eq!(start().evaluate(), 0);
+		}
+
+		#[test]
+		fn test_corner() {
+			let board =
+				board(["o.......", ".x......", "..x.....", "...xo...", "...ox...", "........", "........", "........"]);
+
+			assert!(board.evaluate() > 0);
+		}
+	}
+
+	mod get_best_move {
+		use super::*;
+
+		/// Scores the position with a full minimax to the end of the game.
+		fn reference(board: &mut Othello, player: Player, passed: bool) -> i32 {
+			let moves = board.moves(player);
+			if moves == 0 {
+				return if passed { board.outcome(Player::Machine) } else { reference(board, player.opponent(), true) };
+			}
+
+			let (human, machine) = (board.human, board.machine);
+			let scores = cells(moves).into_iter().map(|cell| {
+				board.add(cell, player);
+				let score = reference(board, player.opponent(), false);
+				(board.human, board.machine) = (human, machine);
+				score
+			});
+
+			match player {
+				Player::Machine => scores.max().unwrap(),
+				_ => scores.min().unwrap(),
+			}
+		}
+
+		/// Plays the first legal move of each player from the start until
+		/// `empty` cells are left, machine to move.
+		fn endgame(empty: u32) -> Othello {
+			let mut board = start();
+			let mut player = Player::Human;
+			while (board.empty() > empty || player != Player::Machine) && !board.finished() {
+				let moves = board.moves(player);
+				if moves != 0 {
+					board.add(cells(moves)[(board.empty() % 3) as usize % cells(moves).len()], player);
+				}
+				player = player.opponent();
+			}
+
+			board
+		}
+
+		#[test]
+		fn test_take_corner() {
+			let mut board =
+				board(["........", ".x......", "..x.....", "...xo...", "...oo...", "........", "........", "........"]);
+
+			assert_eq!(board.get_best_move(4, Player::Machine), 0);
+		}
+
+		#[test]
+		fn test_human_take_corner() {
+			let mut board =
+				board(["........", ".o......", "..o.....", "...ox...", "...xx...", "........", "........", "........"]);
+
+			assert_eq!(board.get_best_move(4, Player::Human), 0);
+		}
+
+		#[test]
+		fn test_pass() {
+			let mut board =
+				board(["xxxxxxxx", "xxxxxxxx", "xxxxxxxx", "xxxxxxxx", "xxxxxxxx", "xxxxxxxx", "xxxxxxxx", "xxxxxx.o"]);
+
+			assert_eq!(board.get_best_move(4, Player::Machine), U_INVALID_INDEX);
+		}
+
+		#[test]
+		fn test_endgame_is_exact() {
+			for empty in [6, 8, 9] {
+				let mut board = endgame(empty);
+				let expected = reference(&mut board, Player::Machine, false);
+
+				let cell = board.get_best_move(1, Player::Machine);
+				board.add(cell, Player::Machine);
+				assert_eq!(reference(&mut board, Player::Human, false), expected, "{empty} empty cells");
+			}
+		}
+
+		#[test]
+		fn test_human_endgame_is_exact() {
+			for empty in [7, 9] {
+				let mut board = endgame(empty);
+				board.add(cells(board.moves(Player::Machine))[0], Player::Machine);
+				let expected = reference(&mut board, Player::Human, false);
+
+				let cell = board.get_best_move(1, Player::Human);
+				board.add(cell, Player::Human);
+				assert_eq!(reference(&mut board, Player::Machine, false), expected, "{empty} empty cells");
+			}
+		}
+
+		#[test]
+		fn test_stats() {
+			let mut board = start();
+			board.get_best_move(4, Player::Machine);
+
+			assert!(board.stats.nodes > 0);
+			assert!(board.stats.cutoffs > 0);
+			assert_eq!(board.stats.max_depth, 4);
+		}
+	}
+}
@@ -0,0 +1,742 @@
+use std::{cmp, collections::HashMap, time::Instant};
+
+use napi::{bindgen_prelude::Uint8Array, Error, Result};
+
+use crate::{
+	isize_to_usize, napi_assert,
+	search::{
+		options::{BestMoveArgument, BestMoveOptions},
+		stats::{SearchStats, Stats},
+		tt::{zobrist_keys, Bound, TranspositionTable},
+	},
+	Player, U_INVALID_INDEX,
+};
+
+/// The amount of cells in every row, column and pillar of the cube.
+pub const BOARD_SIZE: usize = 4;
+pub const BOARD_CELLS: usize = BOARD_SIZE * BOARD_SIZE * BOARD_SIZE;
+
+/// The amount of winning lines: 48 rows, columns and pillars, 24 diagonals
+/// within a plane, and 4 diagonals across the cube.
+const LINE_COUNT: usize = 76;
+
+/// The winning lines, as masks of their cells.
+const LINES: [u64; LINE_COUNT] = generate_lines();
+/// The lines through every cell, as masks of their indices in [`LINES`].
+const CELL_LINES: [u128; BOARD_CELLS] = generate_cell_lines();
+
+/// The score of a won game from the perspective of the winner, adjusted by the
+/// remaining depth so the search prefers the fastest wins and the slowest
+/// losses.
+const OUTCOME_WIN: i32 = 1_000_000;
+const OUTCOME_DRAW: i32 = 0;
+
+/// The score of a line holding `n` pieces of a single player and no piece of
+/// the other one, by `n`.
+const LINE_WEIGHTS: [i32; BOARD_SIZE + 1] = [0, 1, 10, 100, 1_000];
+
+/// The most moves of the attacker the threat search plays, at the root and at
+/// the leaves of the alpha-beta search.
+const ROOT_THREAT_DEPTH: u8 = 12;
+const LEAF_THREAT_DEPTH: u8 = 4;
+
+/// The size of the transposition table, as a power of two.
+const TRANSPOSITION_TABLE_BITS: u8 = 18;
+
+/// The Zobrist keys of every cell for each player, followed by the key of the
+/// positions where [`Player::Human`] is to move.
+const ZOBRIST_KEYS: [u64; BOARD_CELLS * 2 + 1] = zobrist_keys(0x0C0B_E5E5);
+const ZOBRIST_SIDE_KEY: u64 = ZOBRIST_KEYS[BOARD_CELLS * 2];
+
+fn zobrist(cell: usize, player: Player) -> u64 {
+	ZOBRIST_KEYS[cell * 2 + side(player)]
+}
+
+/// Returns the index of a player in the tables kept per player.
+fn side(player: Player) -> usize {
+	debug_assert_ne!(player, Player::Unset);
+
+	player as usize - 1
+}
+
+/// Walks every direction once, keeping those whose first non-zero step is
+/// positive, and every start cell whose line fits in the cube.
+const fn generate_lines() -> [u64; LINE_COUNT] {
+	let mut lines = [0; LINE_COUNT];
+	let mut count = 0;
+
+	let mut direction = 0;
+	while direction < 27 {
+		let (dx, dy, dz) = (direction % 3 - 1, direction / 3 % 3 - 1, direction / 9 - 1);
+		let forward = dz > 0 || (dz == 0 && (dy > 0 || (dy == 0 && dx > 0)));
+
+		let mut start = 0;
+		while forward && start < BOARD_CELLS as isize {
+			let size = BOARD_SIZE as isize;
+			let (x, y, z) = (start % size, start / size % size, start / (size * size));
+			let (ex, ey, ez) = (x + dx * (size - 1), y + dy * (size - 1), z + dz * (size - 1));
+			if ex >= 0 && ex < size && ey >= 0 && ey < size && ez >= 0 && ez < size {
+				let mut line = 0;
+				let mut i = 0;
+				while i < size {
+					line |= 1 << ((z + dz * i) * size * size + (y + dy * i) * size + x + dx * i);
+					i += 1;
+				}
+
+				lines[count] = line;
+				count += 1;
+			}
+			start += 1;
+		}
+		direction += 1;
+	}
+
+	assert!(count == LINE_COUNT);
+	lines
+}
+
+const fn generate_cell_lines() -> [u128; BOARD_CELLS] {
+	let mut cell_lines = [0; BOARD_CELLS];
+	let mut line = 0;
+	while line < LINE_COUNT {
+		let mut cell = 0;
+		while cell < BOARD_CELLS {
+			if LINES[line] & (1 << cell) != 0 {
+				cell_lines[cell] |= 1 << line;
+			}
+			cell += 1;
+		}
+		line += 1;
+	}
+
+	cell_lines
+}
+
+/// Iterates over the indices of the set bits of a mask, lowest first.
+fn bits(mut mask: u128) -> impl Iterator<Item = usize> {
+	std::iter::from_fn(move || {
+		(mask != 0).then(|| {
+			let bit = mask.trailing_zeros() as usize;
+			mask &= mask - 1;
+			bit
+		})
+	})
+}
+
+/// Qubic, tic-tac-toe in a 4×4×4 cube, where the first player with 4 pieces in
+/// any of its 76 lines wins.
+///
+/// The cell `16 * layer + 4 * row + column` is the cell at that column of that
+/// row of that layer.
+#[napi]
+pub struct Qubic {
+	/// The cells of every player, as a mask, by [`side`].
+	pieces: [u64; 2],
+	hash: u64,
+	tt: TranspositionTable<u8>,
+	/// The positions where the threat search found no win for the player to
+	/// move, by the depth it searched, cleared before every search.
+	no_threat_wins: HashMap<u64, u8>,
+	/// The counters of the last search.
+	stats: Stats,
+}
+
+impl Qubic {
+	pub fn new(cells: &[Player]) -> Self {
+		debug_assert_eq!(cells.len(), BOARD_CELLS);
+
+		let mut qubic = Self {
+			pieces: [0; 2],
+			hash: 0,
+			tt: TranspositionTable::default(),
+			no_threat_wins: HashMap::new(),
+			stats: Stats::default(),
+		};
+		for (cell, &player) in cells.iter().enumerate() {
+			if player != Player::Unset {
+				qubic.add(cell, player);
+			}
+		}
+
+		qubic
+	}
+
+	fn empty_cells(&self) -> u64 {
+		!(self.pieces[0] | self.pieces[1])
+	}
+
+	fn empty(&self) -> usize {
+		self.empty_cells().count_ones() as usize
+	}
+
+	fn cell(&self, cell: usize) -> Player {
+		if self.pieces[side(Player::Human)] & (1 << cell) != 0 {
+			Player::Human
+		} else if self.pieces[side(Player::Machine)] & (1 << cell) != 0 {
+			Player::Machine
+		} else {
+			Player::Unset
+		}
+	}
+
+	fn available(&self, cell: usize) -> bool {
+		debug_assert!(cell < BOARD_CELLS);
+
+		self.empty_cells() & (1 << cell) != 0
+	}
+
+	fn add(&mut self, cell: usize, player: Player) {
+		debug_assert!(self.available(cell));
+
+		self.pieces[side(player)] |= 1 << cell;
+		self.hash ^= zobrist(cell, player);
+	}
+
+	fn remove(&mut self, cell: usize, player: Player) {
+		debug_assert_eq!(self.cell(cell), player);
+
+		self.pieces[side(player)] &= !(1 << cell);
+		self.hash ^= zobrist(cell, player);
+	}
+
+	/// Checks whether the piece at `cell` is part of a winning line.
+	fn status(&self, cell: usize) -> bool {
+		let player = self.cell(cell);
+		debug_assert_ne!(player, Player::Unset);
+
+		let pieces = self.pieces[side(player)];
+		bits(CELL_LINES[cell]).any(|line| pieces & LINES[line] == LINES[line])
+	}
+
+	/// Returns the player with a complete line, if any.
+	fn winner(&self) -> Player {
+		for player in [Player::Human, Player::Machine] {
+			let pieces = self.pieces[side(player)];
+			if LINES.iter().any(|line| line & !pieces == 0) {
+				return player;
+			}
+		}
+
+		Player::Unset
+	}
+
+	/// Returns the empty cells of the lines holding `count` pieces of `player`
+	/// and none of the opponent, as a mask. With a `count` of 3, these are the
+	/// cells where `player` wins.
+	fn open_cells(&self, player: Player, count: u32) -> u64 {
+		let (mine, theirs) = (self.pieces[side(player)], self.pieces[side(player.opponent())]);
+		LINES
+			.iter()
+			.filter(|&&line| line & theirs == 0 && (line & mine).count_ones() == count)
+			.fold(0, |mask, &line| mask | (line & !mine))
+	}
+
+	/// Scores the board from the perspective of the machine, adding up the
+	/// lines that can still be completed by a single player.
+	fn evaluate(&self) -> i32 {
+		let (human, machine) = (self.pieces[side(Player::Human)], self.pieces[side(Player::Machine)]);
+		LINES
+			.iter()
+			.map(|&line| match ((line & human).count_ones(), (line & machine).count_ones()) {
+				(0, n) => LINE_WEIGHTS[n as usize],
+				(n, 0) => -LINE_WEIGHTS[n as usize],
+				_ => 0,
+			})
+			.sum()
+	}
+
+	/// Scores how much placing a piece in `cell` helps either player, by the
+	/// pieces of every line through it that it extends or blocks.
+	fn threat(&self, cell: usize) -> i32 {
+		let (human, machine) = (self.pieces[side(Player::Human)], self.pieces[side(Player::Machine)]);
+		bits(CELL_LINES[cell])
+			.map(|line| match ((LINES[line] & human).count_ones(), (LINES[line] & machine).count_ones()) {
+				(0, n) | (n, 0) => LINE_WEIGHTS[n as usize + 1],
+				_ => 0,
+			})
+			.sum()
+	}
+
+	/// Looks for a win of `player` made only of threats, where every move but
+	/// the last one makes a line of 3 the opponent has to block, and the last
+	/// one makes two of them at once. Returns the first move of the sequence.
+	///
+	/// The search gives up when the opponent has a line of 3 to block, or makes
+	/// one while blocking, so every win it finds is sound.
+	fn forced_win(&mut self, player: Player, depth: u8) -> Option<usize> {
+		let empty = self.empty_cells();
+		if let Some(cell) = bits((self.open_cells(player, 3) & empty) as u128).next() {
+			return Some(cell);
+		}
+
+		if depth == 0 || self.open_cells(player.opponent(), 3) & empty != 0 {
+			return None;
+		}
+
+		// The threats can be played in many orders, which reach the same positions:
+		let key = match player {
+			Player::Human => self.hash ^ ZOBRIST_SIDE_KEY,
+			_ => self.hash,
+		};
+		if self.no_threat_wins.get(&key).is_some_and(|&searched| searched >= depth) {
+			return None;
+		}
+
+		for cell in bits((self.open_cells(player, 2) & empty) as u128) {
+			self.add(cell, player);
+			let threats = self.open_cells(player, 3) & self.empty_cells();
+			let wins = match threats.count_ones() {
+				0 => false,
+				1 => {
+					let block = threats.trailing_zeros() as usize;
+					self.add(block, player.opponent());
+					let wins = self.forced_win(player, depth - 1).is_some();
+					self.remove(block, player.opponent());
+					wins
+				}
+				_ => true,
+			};
+			self.remove(cell, player);
+
+			if wins {
+				return Some(cell);
+			}
+		}
+
+		self.no_threat_wins.insert(key, depth);
+		None
+	}
+
+	/// Orders the empty cells for `player`, who has no line of 3 to complete.
+	/// If the opponent has one, only the cells blocking it are returned.
+	/// Otherwise, the best move stored in the transposition table comes first,
+	/// then the rest by how much they extend or block lines.
+	fn order_moves(&self, player: Player, tt_move: Option<u8>) -> ([u8; BOARD_CELLS], usize) {
+		let blocks = self.open_cells(player.opponent(), 3) & self.empty_cells();
+		let candidates = if blocks != 0 { blocks } else { self.empty_cells() };
+
+		let mut moves = [0; BOARD_CELLS];
+		let mut keys = [(0, 0); BOARD_CELLS];
+		let mut count = 0;
+		for cell in bits(candidates as u128) {
+			let class = u8::from(tt_move == Some(cell as u8));
+			moves[count] = cell as u8;
+			keys[count] = (class, self.threat(cell));
+			count += 1;
+		}
+
+		// Insertion sort, which is stable so ties keep the order of the cells:
+		for i in 1..count {
+			let mut j = i;
+			while j > 0 && keys[j - 1] < keys[j] {
+				keys.swap(j - 1, j);
+				moves.swap(j - 1, j);
+				j -= 1;
+			}
+		}
+
+		(moves, count)
+	}
+
+	/// Scores the position from the perspective of `player`, who is to move,
+	/// returning [`None`] if it has to be searched.
+	///
+	/// A player with a line of 3 to complete wins on its move, one facing two
+	/// lines of 3 of the opponent loses, and a player out of depth is scored by
+	/// the threat search and the evaluation.
+	fn settle(&mut self, player: Player, remaining: u8) -> Option<i32> {
+		let win = OUTCOME_WIN + remaining as i32;
+
+		let empty = self.empty_cells();
+		if self.open_cells(player, 3) & empty != 0 {
+			return Some(win);
+		}
+
+		if (self.open_cells(player.opponent(), 3) & empty).count_ones() > 1 {
+			return Some(-win);
+		}
+
+		if remaining == 0 {
+			if self.forced_win(player, LEAF_THREAT_DEPTH).is_some() {
+				return Some(win);
+			}
+
+			return Some(match player {
+				Player::Machine => self.evaluate(),
+				_ => -self.evaluate(),
+			});
+		}
+
+		None
+	}
+
+	/// Scores the position from the perspective of `player`, who is to move
+	/// after the opponent played `last_cell`.
+	fn negamax(&mut self, player: Player, last_cell: usize, remaining: u8, alpha: i32, beta: i32) -> i32 {
+		self.stats.visit(remaining);
+
+		if self.status(last_cell) {
+			self.stats.leaves += 1;
+			return -(OUTCOME_WIN + remaining as i32);
+		}
+
+		if self.empty() == 0 {
+			self.stats.leaves += 1;
+			return OUTCOME_DRAW;
+		}
+
+		if let Some(value) = self.settle(player, remaining) {
+			self.stats.leaves += 1;
+			return value;
+		}
+
+		let key = match player {
+			Player::Human => self.hash ^ ZOBRIST_SIDE_KEY,
+			_ => self.hash,
+		};
+		let tt_move = match self.tt.probe(key) {
+			Some(entry) => {
+				self.stats.tt_hits += 1;
+				match entry.cutoff(remaining, alpha, beta) {
+					Some(value) => return value,
+					None => entry.mv,
+				}
+			}
+			None => None,
+		};
+
+		let mut max_v = i32::MIN;
+		let mut local_alpha = alpha;
+		let mut best = None;
+
+		let (moves, count) = self.order_moves(player, tt_move);
+		for &cell in &moves[..count] {
+			self.add(cell as usize, player);
+			let m = -self.negamax(player.opponent(), cell as usize, remaining - 1, -beta, -local_alpha);
+			self.remove(cell as usize, player);
+
+			if m > max_v {
+				max_v = m;
+				best = Some(cell);
+
+				local_alpha = cmp::max(local_alpha, max_v);
+				if local_alpha >= beta {
+					self.stats.cutoffs += 1;
+					break;
+				}
+			}
+		}
+
+		self.tt.store(key, remaining, max_v, Bound::from_window(max_v, alpha, beta), best);
+		max_v
+	}
+
+	fn max_top(&mut self, player: Player, remaining: u8) -> usize {
+		if remaining == 0 {
+			return U_INVALID_INDEX;
+		}
+
+		if self.tt.is_empty() {
+			self.tt = TranspositionTable::new(TRANSPOSITION_TABLE_BITS);
+		}
+
+		// The root is not visited, its children are the first ply:
+		self.stats = Stats::new(remaining - 1);
+		self.no_threat_wins.clear();
+
+		// A win made of threats needs no search, and is often too deep for it:
+		if let Some(cell) = self.forced_win(player, ROOT_THREAT_DEPTH) {
+			return cell;
+		}
+
+		let mut max_v = i32::MIN;
+		let mut best = U_INVALID_INDEX;
+		let mut alpha = -i32::MAX;
+		let (moves, count) = self.order_moves(player, None);
+		for &cell in &moves[..count] {
+			self.add(cell as usize, player);
+			let points = -self.negamax(player.opponent(), cell as usize, remaining - 1, -i32::MAX, -alpha);
+			self.remove(cell as usize, player);
+
+			if points > max_v {
+				max_v = points;
+				best = cell as usize;
+				alpha = max_v;
+			}
+		}
+
+		best
+	}
+
+	/// Returns the best move for `player`, searching `maximum_depth` plies
+	/// deep, or [`U_INVALID_INDEX`] if the game is finished.
+	pub fn get_best_move(&mut self, maximum_depth: u8, player: Player) -> usize {
+		let start = Instant::now();
+
+		let cell = if self.winner() == Player::Unset {
+			self.max_top(player, cmp::min(self.empty(), maximum_depth as usize) as u8)
+		} else {
+			self.stats = Stats::default();
+			U_INVALID_INDEX
+		};
+
+		self.stats.elapsed = start.elapsed();
+		cell
+	}
+}
+
+#[napi]
+impl Qubic {
+	/// Creates a cube from its 64 cells, which defaults to an empty one.
+	#[napi(constructor)]
+	pub fn js_new(values: Option<Uint8Array>) -> Result<Self> {
+		let mut cells = [Player::Unset; BOARD_CELLS];
+		if let Some(v) = values {
+			let input = v.to_vec();
+			if input.len() != BOARD_CELLS {
+				return Err(Error::from_reason(format!("data must have exactly {BOARD_CELLS} numbers")));
+			}
+
+			for (cell, value) in cells.iter_mut().zip(input) {
+				*cell = Player::try_from(value).map_err(Error::from_reason)?;
+			}
+		}
+
+		Ok(Qubic::new(&cells))
+	}
+
+	#[napi(getter = board)]
+	pub fn js_get_board(&self) -> Uint8Array {
+		Uint8Array::new((0..BOARD_CELLS).map(|cell| self.cell(cell) as u8).collect())
+	}
+
+	#[napi(js_name = "available")]
+	pub fn js_available(&self, cell: i32) -> Result<bool> {
+		Ok(self.available(isize_to_usize!(cell, BOARD_CELLS)?))
+	}
+
+	#[napi(getter = finished)]
+	pub fn js_finished(&self) -> bool {
+		self.empty() == 0 || self.winner() != Player::Unset
+	}
+
+	/// The player with 4 pieces in a line, `Player.Unset` while the game is
+	/// ongoing or if it was drawn.
+	#[napi(getter = winner)]
+	pub fn js_get_winner(&self) -> Player {
+		self.winner()
+	}
+
+	/// Places a piece of `player` on `cell`, returning whether it completed a
+	/// line.
+	#[napi(js_name = "add")]
+	pub fn js_add(&mut self, cell: i32, player: Player) -> Result<bool> {
+		let c = isize_to_usize!(cell, BOARD_CELLS)?;
+		napi_assert!(player != Player::Unset);
+		napi_assert!(self.available(c));
+
+		self.add(c, player);
+		Ok(self.status(c))
+	}
+
+	/// The statistics of the last call to `getBestMove`, every counter is zero
+	/// before the first search.
+	#[napi(getter = lastSearchStats)]
+	pub fn js_get_last_search_stats(&self) -> SearchStats {
+		self.stats.into()
+	}
+
+	/// Returns the best cell for the machine, or for the player given in the
+	/// options, or `INVALID_INDEX` if the game is finished.
+	#[napi(js_name = "getBestMove")]
+	pub fn js_get_best_move(&mut self, options: Option<BestMoveArgument>) -> Result<i32> {
+		let (depth, player) = BestMoveOptions::resolve(options, 4)?;
+		Ok(self.get_best_move(depth, player) as i32)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::search::random::Random;
+
+	/// Creates a cube with the pieces of each player in the given cells.
+	fn board(human: &[usize], machine: &[usize]) -> Qubic {
+		let mut cells = [Player::Unset; BOARD_CELLS];
+		for &cell in human {
+			cells[cell] = Player::Human;
+		}
+		for &cell in machine {
+			cells[cell] = Player::Machine;
+		}
+
+		Qubic::new(&cells)
+	}
+
+	mod lines {
+		use super::*;
+
+		#[test]
+		fn test_lines() {
+			assert!(LINES.iter().all(|line| line.count_ones() == BOARD_SIZE as u32));
+			for (i, a) in LINES.iter().enumerate() {
+				assert!(LINES[i + 1..].iter().all(|b| a != b));
+			}
+		}
+
+		#[test]
+		fn test_cell_lines() {
+			// The corners and the inner cells are on 7 lines, the rest on 4:
+			let busy: Vec<usize> = (0..BOARD_CELLS).filter(|&c| CELL_LINES[c].count_ones() == 7).collect();
+			assert_eq!(busy, [0, 3, 12, 15, 21, 22, 25, 26, 37, 38, 41, 42, 48, 51, 60, 63]);
+			assert!((0..BOARD_CELLS).all(|c| matches!(CELL_LINES[c].count_ones(), 4 | 7)));
+		}
+	}
+
+	mod status {
+		use super::*;
+
+		macro_rules! gen_test {
+			($($name:ident: [$human:expr, $last:expr, $outcome:expr],)*) => ($(
+				#[test]
+				fn $name() {
+					assert_eq!(board(&$human, &[]).status($last), $outcome);
+				}
+			)*);
+		}
+
+		gen_test! {
+			test_three: [[0, 1, 2], 2, false],
+			test_row: [[4, 5, 6, 7], 5, true],
+			test_column: [[1, 5, 9, 13], 13, true],
+			test_pillar: [[6, 22, 38, 54], 38, true],
+			test_layer_diagonal: [[16, 21, 26, 31], 26, true],
+			test_layer_anti_diagonal: [[35, 38, 41, 44], 35, true],
+			test_vertical_diagonal: [[0, 17, 34, 51], 17, true],
+			test_space_diagonal: [[3, 22, 41, 60], 60, true],
+			// The end of a row does not continue in the next one:
+			test_no_wrap_around: [[2, 3, 4, 5], 3, false],
+		}
+	}
+
+	mod hash {
+		use super::*;
+
+		#[test]
+		fn test_matches_incremental_updates() {
+			let mut qubic = board(&[], &[]);
+			qubic.add(21, Player::Human);
+			qubic.add(42, Player::Machine);
+			assert_eq!(qubic.hash, board(&[21], &[42]).hash);
+
+			qubic.remove(21, Player::Human);
+			qubic.remove(42, Player::Machine);
+			assert_eq!(qubic.hash, 0);
+		}
+	}
+
+	mod forced_win {
+		use super::*;
+
+		#[test]
+		fn test_immediate_win() {
+			assert_eq!(board(&[], &[0, 1, 2]).forced_win(Player::Machine, 0), Some(3));
+		}
+
+		#[test]
+		fn test_double_threat() {
+			// Taking the corner 0 makes a row and a column of 3 at once:
+			let mut qubic = board(&[16, 33, 50], &[1, 2, 4, 8]);
+			assert_eq!(qubic.forced_win(Player::Machine, 1), Some(0));
+		}
+
+		#[test]
+		fn test_threat_sequence() {
+			let mut qubic = board(&[], &[1, 4, 8, 6, 10]);
+			assert_eq!(qubic.forced_win(Player::Machine, 1), None);
+
+			// 0 makes a column of 3, and once 12 blocks it, 2 makes a row and a
+			// column of 3 at once:
+			assert_eq!(qubic.forced_win(Player::Machine, 2), Some(0));
+			qubic.add(0, Player::Machine);
+			qubic.add(12, Player::Human);
+			assert_eq!(qubic.forced_win(Player::Machine, 1), Some(2));
+		}
+
+		#[test]
+		fn test_defends_first() {
+			let mut qubic = board(&[16, 17, 18], &[1, 2, 4, 8]);
+			assert_eq!(qubic.forced_win(Player::Machine, 4), None);
+		}
+	}
+
+	mod get_best_move {
+		use super::*;
+
+		macro_rules! gen_test {
+			($($name:ident: [$board:expr, $outcome:expr],)*) => ($(
+				#[test]
+				fn $name() {
+					let mut qubic = $board;
+					assert_eq!(qubic.get_best_move(4, Player::Machine), $outcome);
+				}
+			)*);
+		}
+
+		gen_test! {
+			test_win: [board(&[16, 17, 18], &[0, 1, 2]), 3],
+			test_block: [board(&[16, 17, 18], &[0, 21]), 19],
+			test_block_space_diagonal: [board(&[0, 21, 42], &[1, 6]), 63],
+			test_double_threat: [board(&[16, 33, 50], &[1, 2, 4, 8]), 0],
+			// Blocking the space diagonal at 0 also makes two lines of 3:
+			test_block_with_double_threat: [board(&[21, 42, 63], &[1, 2, 4, 8]), 0],
+			test_finished: [board(&[0, 1, 2, 3], &[4, 5, 6]), U_INVALID_INDEX],
+		}
+
+		#[test]
+		fn test_human_win() {
+			let mut qubic = board(&[16, 17, 18], &[0, 1, 5]);
+			assert_eq!(qubic.get_best_move(4, Player::Human), 19);
+		}
+
+		#[test]
+		fn test_human_block() {
+			let mut qubic = board(&[16, 21], &[0, 1, 2]);
+			assert_eq!(qubic.get_best_move(4, Player::Human), 3);
+		}
+
+		#[test]
+		fn test_stats() {
+			let mut qubic = board(&[21], &[]);
+			qubic.get_best_move(3, Player::Machine);
+
+			assert!(qubic.stats.nodes > 0);
+			assert!(qubic.stats.cutoffs > 0);
+			assert_eq!(qubic.stats.max_depth, 3);
+		}
+
+		#[test]
+		fn test_wins_against_random() {
+			let mut random = Random::new(7);
+			for _ in 0..4 {
+				let mut qubic = board(&[], &[]);
+				let winner = loop {
+					let empty: Vec<usize> = (0..BOARD_CELLS).filter(|&c| qubic.available(c)).collect();
+					let cell = random.pick(&empty);
+					qubic.add(cell, Player::Human);
+					if qubic.status(cell) {
+						break Player::Human;
+					}
+
+					let cell = qubic.get_best_move(2, Player::Machine);
+					qubic.add(cell, Player::Machine);
+					if qubic.status(cell) {
+						break Player::Machine;
+					}
+				};
+
+				assert_eq!(winner, Player::Machine);
+			}
+		}
+	}
+}
@@ -1,70 +1,107 @@
-use std::cmp;
+use std::{cmp, time::Instant};
 
 use napi::{bindgen_prelude::Uint8Array, Error, Result};
 
-use crate::{isize_to_usize, many_eq, napi_assert, Player, U_INVALID_INDEX};
+use crate::{
+	isize_to_usize, many_eq, napi_assert,
+	search::{
+		options::{BestMoveArgument, BestMoveOptions},
+		stats::{SearchStats, Stats},
+	},
+	Player, U_INVALID_INDEX,
+};
 
 pub const BOARD_WIDTH: usize = 3;
 pub const BOARD_HEIGHT: usize = 3;
 pub const BOARD_CELLS: usize = BOARD_WIDTH * BOARD_HEIGHT;
 
-const OUTCOME_HUMAN_WINS: i8 = -20;
-const OUTCOME_MACHINE_WINS: i8 = 20;
+/// The scores of the finished games from the perspective of the winner, wins
+/// are adjusted by the amount of empty cells left so the search prefers the
+/// fastest wins and the slowest losses.
+const OUTCOME_WIN: i8 = 20;
 const OUTCOME_DRAW: i8 = 0;
 
+/// The order in which the cells are searched when no move is forced: the
+/// center, the corners, and then the edges, from most to fewest lines.
+pub(crate) const CELL_ORDER: [usize; BOARD_CELLS] = [4, 0, 2, 6, 8, 1, 3, 5, 7];
+
+/// The eight lines of a 3×3 board, which boards made of several tic-tac-toe
+/// grids use too.
+pub(crate) const LINES: [[usize; 3]; 8] =
+	[[0, 1, 2], [3, 4, 5], [6, 7, 8], [0, 3, 6], [1, 4, 7], [2, 5, 8], [0, 4, 8], [2, 4, 6]];
+
 pub type AiCells = [Player; BOARD_CELLS];
 
-#[napi]
-pub struct TicTacToe {
-	cells: AiCells,
-	empty: u8,
+fn status_horizontal(cells: &AiCells, cell: usize) -> bool {
+	match cell {
+		0..=2 => many_eq!(cells[0], cells[1], cells[2]),
+		3..=5 => many_eq!(cells[3], cells[4], cells[5]),
+		6..=8 => many_eq!(cells[6], cells[7], cells[8]),
+		_ => unsafe { std::hint::unreachable_unchecked() },
+	}
 }
 
-impl TicTacToe {
-	pub fn new(cells: AiCells) -> Self {
-		let empty: u8 = cells.iter().filter(|&&c| c == Player::Unset).count().try_into().unwrap();
-		Self { cells, empty }
+fn status_vertical(cells: &AiCells, cell: usize) -> bool {
+	match cell {
+		0 | 3 | 6 => many_eq!(cells[0], cells[3], cells[6]),
+		1 | 4 | 7 => many_eq!(cells[1], cells[4], cells[7]),
+		2 | 5 | 8 => many_eq!(cells[2], cells[5], cells[8]),
+		_ => unsafe { std::hint::unreachable_unchecked() },
 	}
+}
 
-	fn status_horizontal(&self, cell: usize) -> bool {
-		match cell {
-			0..=2 => many_eq!(self.cells[0], self.cells[1], self.cells[2]),
-			3..=5 => many_eq!(self.cells[3], self.cells[4], self.cells[5]),
-			6..=8 => many_eq!(self.cells[6], self.cells[7], self.cells[8]),
-			_ => unsafe { std::hint::unreachable_unchecked() },
-		}
+fn status_diagonal_tl(cells: &AiCells, cell: usize) -> bool {
+	match cell {
+		0 | 4 | 8 => many_eq!(cells[0], cells[4], cells[8]),
+		_ => false,
 	}
+}
 
-	fn status_vertical(&self, cell: usize) -> bool {
-		match cell {
-			0 | 3 | 6 => many_eq!(self.cells[0], self.cells[3], self.cells[6]),
-			1 | 4 | 7 => many_eq!(self.cells[1], self.cells[4], self.cells[7]),
-			2 | 5 | 8 => many_eq!(self.cells[2], self.cells[5], self.cells[8]),
-			_ => unsafe { std::hint::unreachable_unchecked() },
-		}
+fn status_diagonal_bl(cells: &AiCells, cell: usize) -> bool {
+	match cell {
+		2 | 4 | 6 => many_eq!(cells[2], cells[4], cells[6]),
+		_ => false,
 	}
+}
 
-	fn status_diagonal_tl(&self, cell: usize) -> bool {
-		match cell {
-			0 | 4 | 8 => many_eq!(self.cells[0], self.cells[4], self.cells[8]),
-			_ => false,
-		}
-	}
+/// Checks whether the piece at `cell` of a 3×3 board is part of a line, which
+/// boards made of several tic-tac-toe grids use too.
+pub(crate) fn status(cells: &AiCells, cell: usize) -> bool {
+	debug_assert!(cell < BOARD_CELLS);
 
-	fn status_diagonal_bl(&self, cell: usize) -> bool {
-		match cell {
-			2 | 4 | 6 => many_eq!(self.cells[2], self.cells[4], self.cells[6]),
-			_ => false,
-		}
+	status_horizontal(cells, cell)
+		|| status_vertical(cells, cell)
+		|| status_diagonal_bl(cells, cell)
+		|| status_diagonal_tl(cells, cell)
+}
+
+/// The options of a [`TicTacToe`] board.
+#[napi(object)]
+#[derive(Debug, Default)]
+pub struct TicTacToeOptions {
+	/// Whether completing a line loses the game instead of winning it, defaults
+	/// to `false`.
+	pub misere: Option<bool>,
+}
+
+#[napi]
+pub struct TicTacToe {
+	cells: AiCells,
+	empty: u8,
+	/// Whether completing a line loses the game.
+	misere: bool,
+	/// The counters of the last search.
+	stats: Stats,
+}
+
+impl TicTacToe {
+	pub fn new(cells: AiCells) -> Self {
+		let empty: u8 = cells.iter().filter(|&&c| c == Player::Unset).count().try_into().unwrap();
+		Self { cells, empty, misere: false, stats: Stats::default() }
 	}
 
 	fn status(&self, cell: usize) -> bool {
-		debug_assert!(cell < BOARD_CELLS);
-
-		self.status_horizontal(cell)
-			|| self.status_vertical(cell)
-			|| self.status_diagonal_bl(cell)
-			|| self.status_diagonal_tl(cell)
+		status(&self.cells, cell)
 	}
 
 	fn available(&self, cell: usize) -> bool {
@@ -87,135 +124,155 @@ impl TicTacToe {
 		self.cells[cell] = Player::Unset;
 	}
 
-	fn min(&mut self, last_cell_offset: usize, remaining: u8, alpha: i8, beta: i8) -> i8 {
-		if self.status(last_cell_offset) {
-			return OUTCOME_MACHINE_WINS;
-		}
-
-		if remaining == 0 {
-			return OUTCOME_DRAW;
-		}
-
-		// Possible values for min_v are:
-		// -1 - win
-		//  0 - a tie
-		//  1 - loss
-		//
-		// We're initially setting it to 2 as worse than the worst case:
-		let mut min_v: i8 = i8::MAX;
-		let mut local_beta = beta;
-
-		for cell in 0..BOARD_CELLS {
-			if self.cells[cell] == Player::Unset {
-				// On the empty field player Human makes a move and calls Max
-				// That's one branch of the game tree:
-				self.add(cell, Player::Human);
+	/// Checks whether playing `cell` as `player` wins the game, leaving the
+	/// board unchanged.
+	fn is_winning_move(&mut self, cell: usize, player: Player) -> bool {
+		self.add(cell, player);
+		let won = self.status(cell);
+		self.remove(cell);
+		won
+	}
 
-				let m = self.max(cell, remaining - 1, alpha, local_beta);
+	/// Orders the empty cells for `player`, trying immediate wins first, then
+	/// forced blocks, and then the rest in [`CELL_ORDER`].
+	///
+	/// In misère, where completing a line loses, the cells that complete one
+	/// are tried last instead.
+	fn order_moves(&mut self, player: Player) -> ([usize; BOARD_CELLS], usize) {
+		let mut cells = [0; BOARD_CELLS];
+		let mut count = 0;
+		if self.misere {
+			for losing in [false, true] {
+				for cell in CELL_ORDER {
+					if self.available(cell) && self.is_winning_move(cell, player) == losing {
+						cells[count] = cell;
+						count += 1;
+					}
+				}
+			}
 
-				// Setting back the field to empty:
-				self.remove(cell);
+			return (cells, count);
+		}
 
-				// Fixing the min_v value if needed:
-				if m < min_v {
-					min_v = m;
+		for target in [Some(player), Some(player.opponent()), None] {
+			for cell in CELL_ORDER {
+				if !self.available(cell) || cells[..count].contains(&cell) {
+					continue;
+				}
 
-					local_beta = cmp::min(local_beta, min_v);
-					if alpha >= local_beta {
-						break;
-					}
+				if target.is_none_or(|p| self.is_winning_move(cell, p)) {
+					cells[count] = cell;
+					count += 1;
 				}
 			}
 		}
 
-		min_v
+		(cells, count)
+	}
+
+	/// Scores a line completed by the last move from the perspective of the
+	/// player to move, who lost unless playing misère.
+	fn outcome(&self, remaining: u8) -> i8 {
+		let score = OUTCOME_WIN + remaining as i8;
+		if self.misere {
+			score
+		} else {
+			-score
+		}
 	}
 
-	/// Maximum is Player::Machine
-	fn max(&mut self, last_cell_offset: usize, remaining: u8, alpha: i8, beta: i8) -> i8 {
+	/// Scores the position from the perspective of `player`, who is to move
+	/// after the opponent played `last_cell_offset`.
+	fn negamax(&mut self, player: Player, last_cell_offset: usize, remaining: u8, alpha: i8, beta: i8) -> i8 {
+		self.stats.visit(remaining);
+
 		if self.status(last_cell_offset) {
-			return OUTCOME_HUMAN_WINS;
+			self.stats.leaves += 1;
+			return self.outcome(remaining);
 		}
 
 		if remaining == 0 {
+			self.stats.leaves += 1;
 			return OUTCOME_DRAW;
 		}
 
-		// Possible values for max_v are:
+		// Possible values for best are:
 		// -1 - loss
 		//  0 - a tie
 		//  1 - win
 		//
 		// We're initially setting it to -2 as worse than the worst case:
-		let mut max_v: i8 = i8::MIN;
-		let mut local_alpha = alpha;
+		let mut best = i8::MIN;
+		let mut alpha = alpha;
 
-		for cell in 0..BOARD_CELLS {
-			if self.cells[cell] == Player::Unset {
-				// On the empty field player Machine makes a move and calls Min
-				// That's one branch of the game tree:
-				self.add(cell, Player::Machine);
+		let (cells, count) = self.order_moves(player);
+		for &cell in &cells[..count] {
+			// On the empty field the player makes a move and the opponent replies,
+			// that's one branch of the game tree:
+			self.add(cell, player);
 
-				let m = self.min(cell, remaining - 1, local_alpha, beta);
+			let m = -self.negamax(player.opponent(), cell, remaining - 1, -beta, -alpha);
 
-				// Setting back the field to empty:
-				self.remove(cell);
+			// Setting back the field to empty:
+			self.remove(cell);
 
-				// Fixing the max_v value if needed:
-				if m > max_v {
-					max_v = m;
+			// Fixing the best value if needed:
+			if m > best {
+				best = m;
 
-					local_alpha = cmp::max(local_alpha, max_v);
-					if local_alpha >= beta {
-						break;
-					}
+				alpha = cmp::max(alpha, best);
+				if alpha >= beta {
+					self.stats.cutoffs += 1;
+					break;
 				}
 			}
 		}
 
-		max_v
+		best
 	}
 
-	fn max_top(&mut self, remaining: u8) -> usize {
+	fn max_top(&mut self, player: Player, remaining: u8) -> usize {
 		if remaining == 0 {
 			return U_INVALID_INDEX;
 		}
 
-		const DEFAULT_ALPHA: i8 = i8::MIN;
+		// The scores are negated, so the window must be too:
+		const DEFAULT_ALPHA: i8 = -i8::MAX;
 		const DEFAULT_BETA: i8 = i8::MAX;
 
-		let mut max_v = i8::MIN;
-		let mut column = U_INVALID_INDEX;
-		for cell in 0..BOARD_WIDTH {
-			if !self.available(cell) {
-				continue;
-			}
+		self.stats = Stats::new(remaining);
 
-			// On the empty field player Machine makes a move and calls Min
-			// That's one branch of the game tree:
-			self.add(cell, Player::Machine);
+		let mut max_v = i8::MIN;
+		let mut best = U_INVALID_INDEX;
+		let (cells, count) = self.order_moves(player);
+		for &cell in &cells[..count] {
+			// On the empty field the player makes a move and the opponent replies,
+			// that's one branch of the game tree:
+			self.add(cell, player);
 
-			let points = self.min(cell, remaining, DEFAULT_ALPHA, DEFAULT_BETA);
+			let points = -self.negamax(player.opponent(), cell, remaining - 1, DEFAULT_ALPHA, DEFAULT_BETA);
 
 			// Setting back the field to empty:
 			self.remove(cell);
 
 			if points > max_v {
 				max_v = points;
-				column = cell;
+				best = cell;
 
-				// Break the loop earlier if we have found a winning move:
-				if points >= OUTCOME_MACHINE_WINS {
+				// Break the loop earlier if we have found a winning move, as none
+				// can win faster:
+				if points >= OUTCOME_WIN + (remaining - 1) as i8 {
 					break;
 				}
 			}
 		}
 
-		column
+		best
 	}
 
-	/// Returns the optimal move from the AI, -1 if no move was possible.
-	pub fn get_best_move(&mut self, maximum_depth: u8) -> usize {
+	/// Returns the optimal move for `player`, [`U_INVALID_INDEX`] if no move
+	/// was possible.
+	pub fn get_best_move(&mut self, maximum_depth: u8, player: Player) -> usize {
 		// If remaining is 9, then the board is empty.
 		//
 		// Strategically speaking, the middle position in TicTacToe is always the best,
@@ -233,18 +290,21 @@ impl TicTacToe {
 		// Hardcoding this is useful, on an empty board, there are 362,880
 		// possibilities. On a board with one move in, there are 40,320 possibilities.
 		// That's a lot less.
-		if self.empty == 9 {
-			4
-		} else {
-			self.max_top(cmp::min(self.empty, maximum_depth))
-		}
+		let start = Instant::now();
+		self.stats = Stats::default();
+
+		let cell = if self.empty == 9 { 4 } else { self.max_top(player, cmp::min(self.empty, maximum_depth)) };
+
+		self.stats.elapsed = start.elapsed();
+		cell
 	}
 }
 
 #[napi]
 impl TicTacToe {
 	#[napi(constructor)]
-	pub fn js_new(values: Option<Uint8Array>) -> Result<Self> {
+	pub fn js_new(values: Option<Uint8Array>, options: Option<TicTacToeOptions>) -> Result<Self> {
+		let misere = options.unwrap_or_default().misere.unwrap_or(false);
 		if let Some(v) = values {
 			let input = v.to_vec();
 			if input.len() != BOARD_CELLS {
@@ -256,9 +316,9 @@ impl TicTacToe {
 				cells[i] = Player::try_from(input[i]).map_err(Error::from_reason)?;
 			}
 
-			Ok(TicTacToe::new(cells))
+			Ok(TicTacToe { misere, ..TicTacToe::new(cells) })
 		} else {
-			Ok(Self { cells: [Player::Unset; BOARD_CELLS], empty: BOARD_CELLS as u8 })
+			Ok(TicTacToe { misere, ..TicTacToe::new([Player::Unset; BOARD_CELLS]) })
 		}
 	}
 
@@ -267,6 +327,12 @@ impl TicTacToe {
 		Uint8Array::new(self.cells.map(|v| v as u8).to_vec())
 	}
 
+	/// Whether completing a line loses the game.
+	#[napi(getter = misere)]
+	pub fn js_get_misere(&self) -> bool {
+		self.misere
+	}
+
 	#[napi(js_name = "available")]
 	pub fn js_available(&self, cell: i32) -> Result<bool> {
 		Ok(self.available(isize_to_usize!(cell, BOARD_CELLS)?))
@@ -277,19 +343,31 @@ impl TicTacToe {
 		self.empty == 0
 	}
 
+	/// Places a piece of `player` on `cell`, returning whether it completed a
+	/// line, which loses the game in misère.
 	#[napi(js_name = "add")]
 	pub fn js_add(&mut self, cell: i32, player: Player) -> Result<bool> {
 		let c = isize_to_usize!(cell, BOARD_CELLS)?;
-		napi_assert!(self.cells[c] != Player::Unset);
+		napi_assert!(self.available(c));
 
 		self.add(c, player);
 		self.empty -= 1;
 		Ok(self.status(c))
 	}
 
+	/// The statistics of the last call to `getBestMove`, every counter is zero
+	/// before the first search.
+	#[napi(getter = lastSearchStats)]
+	pub fn js_get_last_search_stats(&self) -> SearchStats {
+		self.stats.into()
+	}
+
+	/// Returns the best cell for the machine, or for the player given in the
+	/// options, or `INVALID_INDEX` if the board is full.
 	#[napi(js_name = "getBestMove")]
-	pub fn js_get_best_move(&mut self, depth: Option<i32>) -> Result<i32> {
-		Ok(self.get_best_move(depth.unwrap_or(5).try_into().unwrap()).try_into().unwrap())
+	pub fn js_get_best_move(&mut self, options: Option<BestMoveArgument>) -> Result<i32> {
+		let (depth, player) = BestMoveOptions::resolve(options, 5)?;
+		Ok(self.get_best_move(depth, player) as i32)
 	}
 }
 
@@ -306,6 +384,18 @@ mod tests {
 		})*);
 	}
 
+	/// Creates the cells with the given cells of each player.
+	fn cells(human: &[usize], machine: &[usize]) -> AiCells {
+		let mut cells = create_cells!();
+		for &cell in human {
+			cells[cell] = Player::Human;
+		}
+		for &cell in machine {
+			cells[cell] = Player::Machine;
+		}
+		cells
+	}
+
 	mod new {
 		use super::*;
 
@@ -335,8 +425,8 @@ mod tests {
 			($($name:ident: [$cells:expr, $last_cell_offset:expr],)*) => ($(
 				#[test]
 				fn $name() {
-					let board = TicTacToe::new($cells);
-					assert!(!board.status_horizontal($last_cell_offset));
+					let cells = $cells;
+					assert!(!status_horizontal(&cells, $last_cell_offset));
 				}
 			)*);
 		}
@@ -351,8 +441,8 @@ mod tests {
 			($($name:ident: [$cells:expr, $last_cell_offset:expr],)*) => ($(
 				#[test]
 				fn $name() {
-					let board = TicTacToe::new($cells);
-					assert!(board.status_horizontal($last_cell_offset));
+					let cells = $cells;
+					assert!(status_horizontal(&cells, $last_cell_offset));
 				}
 			)*);
 		}
@@ -377,8 +467,8 @@ mod tests {
 			($($name:ident: [$cells:expr, $last_cell_offset:expr],)*) => ($(
 				#[test]
 				fn $name() {
-					let board = TicTacToe::new($cells);
-					assert!(!board.status_vertical($last_cell_offset));
+					let cells = $cells;
+					assert!(!status_vertical(&cells, $last_cell_offset));
 				}
 			)*);
 		}
@@ -393,8 +483,8 @@ mod tests {
 			($($name:ident: [$cells:expr, $last_cell_offset:expr],)*) => ($(
 				#[test]
 				fn $name() {
-					let board = TicTacToe::new($cells);
-					assert!(board.status_vertical($last_cell_offset));
+					let cells = $cells;
+					assert!(status_vertical(&cells, $last_cell_offset));
 				}
 			)*);
 		}
@@ -424,11 +514,273 @@ mod tests {
 
 	mod remove {}
 
-	mod min {}
+	mod order_moves {
+		use super::*;
+
+		macro_rules! gen_test {
+			($($name:ident: [$cells:expr, $player:expr, $outcome:expr],)*) => ($(
+				#[test]
+				fn $name() {
+					let mut board = TicTacToe::new($cells);
+					let (cells, count) = board.order_moves($player);
+
+					assert_eq!(&cells[..count], &$outcome);
+				}
+			)*);
+		}
+
+		gen_test! {
+			test_empty: [create_cells!(), Player::Machine, [4, 0, 2, 6, 8, 1, 3, 5, 7]],
+			test_skips_taken_cells: [create_cells!(4), Player::Machine, [0, 2, 6, 8, 1, 3, 5, 7]],
+			// H H v
+			// _ _ _
+			// _ _ _
+			test_win_first: [create_cells!(0, 1), Player::Human, [2, 4, 6, 8, 3, 5, 7]],
+			test_block_first: [create_cells!(0, 1), Player::Machine, [2, 4, 6, 8, 3, 5, 7]],
+		}
+
+		#[test]
+		fn test_win_before_block() {
+			// H H v
+			// _ _ _
+			// M M v
+			let mut cells = create_cells!(0, 1);
+			cells[6] = Player::Machine;
+			cells[7] = Player::Machine;
+
+			let mut board = TicTacToe::new(cells);
+			assert_eq!(&board.order_moves(Player::Machine).0[..5], &[8, 2, 4, 3, 5]);
+			assert_eq!(&board.order_moves(Player::Human).0[..5], &[2, 8, 4, 3, 5]);
+		}
+
+		#[test]
+		fn test_misere_completing_last() {
+			// H H v
+			// _ _ _
+			// M M v
+			let mut board = TicTacToe { misere: true, ..TicTacToe::new(cells(&[0, 1], &[6, 7])) };
+			assert_eq!(board.order_moves(Player::Machine), ([4, 2, 3, 5, 8, 0, 0, 0, 0], 5));
+			assert_eq!(board.order_moves(Player::Human), ([4, 8, 3, 5, 2, 0, 0, 0, 0], 5));
+		}
+	}
+
+	mod negamax {
+		use super::*;
 
-	mod max {}
+		#[test]
+		fn test_prefers_faster_win() {
+			// M M M
+			// H H _
+			// _ _ _
+			let mut board = TicTacToe::new(cells(&[3, 4], &[0, 1, 2]));
+
+			assert_eq!(board.negamax(Player::Human, 2, 4, -i8::MAX, i8::MAX), -(OUTCOME_WIN + 4));
+			assert!(
+				board.negamax(Player::Human, 2, 4, -i8::MAX, i8::MAX)
+					< board.negamax(Player::Human, 2, 2, -i8::MAX, i8::MAX)
+			);
+		}
 
-	mod max_top {}
+		#[test]
+		fn test_misere_prefers_slower_loss() {
+			// M M M
+			// H H _
+			// _ _ _
+			let mut board = TicTacToe { misere: true, ..TicTacToe::new(cells(&[3, 4], &[0, 1, 2])) };
+
+			assert_eq!(board.negamax(Player::Human, 2, 4, -i8::MAX, i8::MAX), OUTCOME_WIN + 4);
+			assert!(
+				board.negamax(Player::Human, 2, 4, -i8::MAX, i8::MAX)
+					> board.negamax(Player::Human, 2, 2, -i8::MAX, i8::MAX)
+			);
+		}
+
+		#[test]
+		fn test_prefers_slower_loss() {
+			// H H H
+			// M M _
+			// _ _ _
+			let mut board = TicTacToe::new(cells(&[0, 1, 2], &[3, 4]));
+
+			assert_eq!(board.negamax(Player::Machine, 2, 4, -i8::MAX, i8::MAX), -(OUTCOME_WIN + 4));
+			assert!(
+				board.negamax(Player::Machine, 2, 4, -i8::MAX, i8::MAX)
+					< board.negamax(Player::Machine, 2, 2, -i8::MAX, i8::MAX)
+			);
+		}
+	}
+
+	mod max_top {
+		use super::*;
+
+		macro_rules! gen_test {
+			($($name:ident: [$cells:expr, $player:expr, $outcome:expr],)*) => ($(
+				#[test]
+				fn $name() {
+					let mut board = TicTacToe::new($cells);
+					let remaining = board.empty;
+
+					assert_eq!(board.max_top($player, remaining), $outcome);
+				}
+			)*);
+		}
+
+		gen_test! {
+			// H M H
+			// _ v _
+			// _ _ _
+			test_top_row_filled: [cells(&[0, 2], &[1]), Player::Machine, 4],
+			// H M H
+			// H M M
+			// M H v
+			test_last_cell: [cells(&[0, 2, 3, 7], &[1, 4, 5, 6]), Player::Machine, 8],
+			// M M v
+			// _ H _
+			// H _ _
+			test_win: [cells(&[4, 6], &[0, 1]), Player::Machine, 2],
+			// H _ _
+			// _ H _
+			// _ _ v
+			test_block: [cells(&[0, 4], &[1]), Player::Machine, 8],
+			// H H v
+			// _ M _
+			// M _ _
+			test_human_win: [cells(&[0, 1], &[4, 6]), Player::Human, 2],
+			// M H _
+			// _ M _
+			// _ _ v
+			test_human_block: [cells(&[1], &[0, 4]), Player::Human, 8],
+		}
+
+		#[test]
+		fn test_full_board() {
+			let mut board = TicTacToe::new(cells(&[0, 2, 3, 7, 8], &[1, 4, 5, 6]));
+			assert_eq!(board.max_top(Player::Machine, 0), U_INVALID_INDEX);
+		}
+	}
 
-	mod get_best_move {}
+	mod get_best_move {
+		use std::collections::HashSet;
+
+		use super::*;
+
+		fn winner(cells: &AiCells) -> Player {
+			LINES
+				.iter()
+				.find(|&&[a, b, c]| cells[a] != Player::Unset && cells[a] == cells[b] && cells[b] == cells[c])
+				.map_or(Player::Unset, |&[a, ..]| cells[a])
+		}
+
+		/// Scores every move of `player` with a plain minimax over the whole
+		/// game tree, using the same scale as the search from the perspective
+		/// of the machine.
+		fn reference(cells: &mut AiCells, player: Player, misere: bool) -> [Option<i8>; BOARD_CELLS] {
+			let mut scores = [None; BOARD_CELLS];
+			let empty = cells.iter().filter(|&&c| c == Player::Unset).count() as i8 - 1;
+			for cell in 0..BOARD_CELLS {
+				if cells[cell] != Player::Unset {
+					continue;
+				}
+
+				cells[cell] = player;
+				let line = winner(cells);
+				let winner = if misere && line != Player::Unset { line.opponent() } else { line };
+				scores[cell] = Some(match winner {
+					Player::Machine => OUTCOME_WIN + empty,
+					Player::Human => -OUTCOME_WIN - empty,
+					Player::Unset if empty == 0 => OUTCOME_DRAW,
+					Player::Unset => {
+						let replies = reference(cells, player.opponent(), misere).into_iter().flatten();
+						if player == Player::Machine {
+							replies.min().unwrap()
+						} else {
+							replies.max().unwrap()
+						}
+					}
+				});
+				cells[cell] = Player::Unset;
+			}
+
+			scores
+		}
+
+		/// Collects every unfinished position reachable with the machine to
+		/// move, deduplicated by their base 3 encoding and the player to
+		/// move.
+		fn positions(cells: &mut AiCells, player: Player, seen: &mut HashSet<u32>, out: &mut Vec<AiCells>) {
+			let key = cells.iter().fold(player as u32, |key, &cell| key * 3 + cell as u32);
+			if winner(cells) != Player::Unset || !cells.contains(&Player::Unset) || !seen.insert(key) {
+				return;
+			}
+
+			if player == Player::Machine {
+				out.push(*cells);
+			}
+			for cell in 0..BOARD_CELLS {
+				if cells[cell] == Player::Unset {
+					cells[cell] = player;
+					positions(cells, player.opponent(), seen, out);
+					cells[cell] = Player::Unset;
+				}
+			}
+		}
+
+		macro_rules! gen_all_positions_test {
+			($($name:ident: [$player:expr, $misere:expr],)*) => ($(
+				#[test]
+				fn $name() {
+					let (mut seen, mut all) = (HashSet::new(), Vec::new());
+					positions(&mut create_cells!(), Player::Machine, &mut seen, &mut all);
+					positions(&mut create_cells!(), Player::Human, &mut seen, &mut all);
+
+					// Every unfinished position of the game, as both players can start:
+					assert_eq!(all.len(), 4_520);
+					for cells in all {
+						let scores = reference(&mut cells.clone(), $player, $misere);
+						let best = match $player {
+							Player::Machine => scores.iter().flatten().max().unwrap(),
+							_ => scores.iter().flatten().min().unwrap(),
+						};
+						let cell = TicTacToe { misere: $misere, ..TicTacToe::new(cells) }.get_best_move(9, $player);
+
+						assert_eq!(scores[cell].as_ref(), Some(best), "{cells:?}");
+					}
+				}
+			)*);
+		}
+
+		gen_all_positions_test! {
+			test_all_positions: [Player::Machine, false],
+			test_all_positions_misere: [Player::Machine, true],
+			test_all_positions_human: [Player::Human, false],
+			test_all_positions_human_misere: [Player::Human, true],
+		}
+
+		#[test]
+		fn test_misere_draws() {
+			// With the best play from both sides, misère is a draw:
+			let scores = reference(&mut create_cells!(), Player::Machine, true);
+			assert_eq!(scores.iter().flatten().max(), Some(&OUTCOME_DRAW));
+			assert_eq!(scores[4], Some(OUTCOME_DRAW));
+		}
+
+		#[test]
+		fn test_stats() {
+			let mut board = TicTacToe::new(create_cells!(4));
+			board.get_best_move(5, Player::Machine);
+
+			assert!(board.stats.nodes > 0);
+			assert!(board.stats.leaves > 0 && board.stats.leaves < board.stats.nodes);
+			assert!(board.stats.cutoffs > 0);
+			assert_eq!(board.stats.tt_hits, 0);
+		}
+
+		#[test]
+		fn test_stats_opening_move() {
+			let mut board = TicTacToe::new(create_cells!());
+
+			assert_eq!(board.get_best_move(5, Player::Machine), 4);
+			assert_eq!(board.stats.nodes, 0);
+		}
+	}
 }
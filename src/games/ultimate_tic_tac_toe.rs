@@ -0,0 +1,658 @@
+use std::{cmp, time::Instant};
+
+use napi::{bindgen_prelude::Uint8Array, Error, Result};
+
+use super::tic_tac_toe::{self, BOARD_CELLS, LINES};
+use crate::{
+	isize_to_usize, napi_assert,
+	search::{
+		options::{BestMoveArgument, BestMoveOptions},
+		stats::{SearchStats, Stats},
+	},
+	Player, U_INVALID_INDEX,
+};
+
+/// The amount of sub-boards, laid out as the cells of a tic-tac-toe board.
+pub const BOARDS: usize = 9;
+/// The amount of cells, every sub-board stores its 9 cells contiguously.
+pub const CELLS: usize = BOARDS * BOARD_CELLS;
+
+/// The scores of the finished games from the perspective of the winner, wins
+/// are adjusted by the remaining depth so the search prefers the fastest wins
+/// and the slowest losses.
+const OUTCOME_WIN: i32 = 1_000_000;
+const OUTCOME_DRAW: i32 = 0;
+
+/// The score of a line holding `n` pieces of a single player and no piece of
+/// the other one, by `n`, inside a sub-board.
+const CELL_LINE_WEIGHTS: [i32; 3] = [0, 1, 4];
+/// The score of a line holding `n` sub-boards won by a single player and no
+/// sub-board won by the other one nor drawn, by `n`, in the big board.
+const BOARD_LINE_WEIGHTS: [i32; 3] = [0, 60, 300];
+/// The score of a sub-board won, by its position in the big board: the center
+/// takes part in more lines than the corners, and these in more than the
+/// edges.
+const BOARD_WEIGHTS: [i32; BOARDS] = [30, 20, 30, 20, 40, 20, 30, 20, 30];
+
+/// The state of a sub-board.
+#[napi]
+#[repr(u8)]
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "tune", derive(Clone, Copy))]
+pub enum BoardStatus {
+	/// The sub-board still accepts moves.
+	Ongoing,
+	/// The human completed a line in the sub-board.
+	Human,
+	/// The machine completed a line in the sub-board.
+	Machine,
+	/// The sub-board was filled without a line.
+	Draw,
+}
+
+impl BoardStatus {
+	/// Returns the player that won the sub-board, if any.
+	fn winner(self) -> Player {
+		match self {
+			BoardStatus::Human => Player::Human,
+			BoardStatus::Machine => Player::Machine,
+			_ => Player::Unset,
+		}
+	}
+}
+
+impl From<Player> for BoardStatus {
+	fn from(player: Player) -> Self {
+		match player {
+			Player::Unset => BoardStatus::Ongoing,
+			Player::Human => BoardStatus::Human,
+			Player::Machine => BoardStatus::Machine,
+		}
+	}
+}
+
+/// Ultimate tic-tac-toe: a tic-tac-toe board of tic-tac-toe boards, where the
+/// cell a player moves in sends the opponent to the matching sub-board, and
+/// winning three sub-boards in a row wins the game.
+///
+/// Cells are numbered by sub-board, so cell `9 * b + c` is the cell `c` of the
+/// sub-board `b`, both numbered like the cells of `TicTacToe`.
+#[napi]
+pub struct UltimateTicTacToe {
+	cells: [Player; CELLS],
+	boards: [BoardStatus; BOARDS],
+	/// The amount of empty cells of each sub-board.
+	empty: [u8; BOARDS],
+	/// The sub-board the next move must be played in, or [`U_INVALID_INDEX`] if
+	/// any open sub-board is allowed.
+	next: usize,
+	winner: Player,
+	/// The counters of the last search.
+	stats: Stats,
+}
+
+impl UltimateTicTacToe {
+	/// Creates a board from its cells, where the next move must be played in
+	/// `next`, or anywhere if it is [`U_INVALID_INDEX`] or a closed sub-board.
+	pub fn new(cells: [Player; CELLS], next: usize) -> Self {
+		let mut board = Self {
+			cells,
+			boards: [BoardStatus::Ongoing; BOARDS],
+			empty: [0; BOARDS],
+			next: U_INVALID_INDEX,
+			winner: Player::Unset,
+			stats: Stats::default(),
+		};
+
+		for b in 0..BOARDS {
+			let sub_board = board.sub_board(b);
+			board.empty[b] = sub_board.iter().filter(|&&c| c == Player::Unset).count() as u8;
+			board.boards[b] =
+				match (0..BOARD_CELLS).find(|&c| sub_board[c] != Player::Unset && tic_tac_toe::status(&sub_board, c)) {
+					Some(c) => sub_board[c].into(),
+					None if board.empty[b] == 0 => BoardStatus::Draw,
+					None => BoardStatus::Ongoing,
+				};
+		}
+
+		let won = board.won_boards();
+		board.winner = (0..BOARDS)
+			.find(|&b| won[b] != Player::Unset && tic_tac_toe::status(&won, b))
+			.map_or(Player::Unset, |b| won[b]);
+		board.next = board.redirect(next);
+		board
+	}
+
+	/// Returns the cells of the sub-board `b`.
+	fn sub_board(&self, b: usize) -> [Player; BOARD_CELLS] {
+		self.cells[b * BOARD_CELLS..(b + 1) * BOARD_CELLS].try_into().unwrap()
+	}
+
+	/// Returns the big board, with the winner of every sub-board.
+	fn won_boards(&self) -> [Player; BOARDS] {
+		self.boards.map(BoardStatus::winner)
+	}
+
+	/// Returns the sub-board a player is sent to, which is any of them once it
+	/// is closed.
+	fn redirect(&self, b: usize) -> usize {
+		if b < BOARDS && self.boards[b] == BoardStatus::Ongoing {
+			b
+		} else {
+			U_INVALID_INDEX
+		}
+	}
+
+	/// Checks whether a move can be played in `cell` when the next move must be
+	/// played in `next`.
+	fn available(&self, cell: usize, next: usize) -> bool {
+		debug_assert!(cell < CELLS);
+
+		let b = cell / BOARD_CELLS;
+		self.cells[cell] == Player::Unset
+			&& self.boards[b] == BoardStatus::Ongoing
+			&& (next == U_INVALID_INDEX || next == b)
+	}
+
+	/// Places a piece, closing its sub-board if it completes a line or fills
+	/// it, and returns the sub-board the opponent is sent to.
+	fn add(&mut self, cell: usize, player: Player) -> usize {
+		debug_assert!(self.available(cell, U_INVALID_INDEX));
+
+		let b = cell / BOARD_CELLS;
+		self.cells[cell] = player;
+		self.empty[b] -= 1;
+
+		if tic_tac_toe::status(&self.sub_board(b), cell % BOARD_CELLS) {
+			self.boards[b] = player.into();
+		} else if self.empty[b] == 0 {
+			self.boards[b] = BoardStatus::Draw;
+		}
+
+		self.redirect(cell % BOARD_CELLS)
+	}
+
+	/// Removes a piece, reopening its sub-board, as moves can only be played in
+	/// open sub-boards.
+	fn remove(&mut self, cell: usize) {
+		debug_assert!(self.cells[cell] != Player::Unset);
+
+		let b = cell / BOARD_CELLS;
+		self.cells[cell] = Player::Unset;
+		self.empty[b] += 1;
+		self.boards[b] = BoardStatus::Ongoing;
+	}
+
+	/// Checks whether the piece at `cell` won the game, by winning its
+	/// sub-board and completing a line of sub-boards.
+	fn status(&self, cell: usize) -> bool {
+		let b = cell / BOARD_CELLS;
+		self.boards[b].winner() != Player::Unset && tic_tac_toe::status(&self.won_boards(), b)
+	}
+
+	/// Checks whether playing `cell` as `player` wins its sub-board, leaving
+	/// the board unchanged.
+	fn wins_sub_board(&mut self, cell: usize, player: Player) -> bool {
+		let b = cell / BOARD_CELLS;
+		self.cells[cell] = player;
+		let won = tic_tac_toe::status(&self.sub_board(b), cell % BOARD_CELLS);
+		self.cells[cell] = Player::Unset;
+		won
+	}
+
+	/// Orders the moves for `player` when the next move must be played in
+	/// `next`: moves winning a sub-board first, then the ones blocking the
+	/// opponent's, and last the ones sending the opponent to a closed
+	/// sub-board, as they let it play anywhere.
+	fn order_moves(&mut self, player: Player, next: usize) -> ([usize; CELLS], usize) {
+		let mut keyed = [(0u8, 0usize); CELLS];
+		let mut count = 0;
+		let boards = if next == U_INVALID_INDEX { 0..BOARDS } else { next..next + 1 };
+		for b in boards {
+			if self.boards[b] != BoardStatus::Ongoing {
+				continue;
+			}
+
+			for c in 0..BOARD_CELLS {
+				let cell = b * BOARD_CELLS + c;
+				if self.cells[cell] != Player::Unset {
+					continue;
+				}
+
+				let key = if self.wins_sub_board(cell, player) {
+					0
+				} else if self.wins_sub_board(cell, player.opponent()) {
+					1
+				} else if self.boards[c] != BoardStatus::Ongoing {
+					3
+				} else {
+					2
+				};
+
+				keyed[count] = (key, cell);
+				count += 1;
+			}
+		}
+
+		keyed[..count].sort_by_key(|&(key, _)| key);
+
+		let mut cells = [0; CELLS];
+		for (cell, &(_, c)) in cells.iter_mut().zip(&keyed[..count]) {
+			*cell = c;
+		}
+
+		(cells, count)
+	}
+
+	/// Scores a line of 3 values, by the weight of the pieces it holds if only
+	/// one player is in it, positive for the machine.
+	fn evaluate_line(line: [Player; 3], weights: &[i32; 3]) -> i32 {
+		let human = line.iter().filter(|&&p| p == Player::Human).count();
+		let machine = line.iter().filter(|&&p| p == Player::Machine).count();
+
+		match (human, machine) {
+			(0, n) if n < 3 => weights[n],
+			(n, 0) if n < 3 => -weights[n],
+			_ => 0,
+		}
+	}
+
+	/// Scores the board from the perspective of the machine: the sub-boards won
+	/// and the lines of sub-boards still open, plus the lines still open in the
+	/// sub-boards being played.
+	fn evaluate(&self) -> i32 {
+		let won = self.won_boards();
+
+		let mut score = 0;
+		for line in LINES {
+			// Drawn sub-boards block the lines of both players:
+			if line.iter().any(|&b| self.boards[b] == BoardStatus::Draw) {
+				continue;
+			}
+
+			score += Self::evaluate_line(line.map(|b| won[b]), &BOARD_LINE_WEIGHTS);
+		}
+
+		for (b, (&status, weight)) in self.boards.iter().zip(BOARD_WEIGHTS).enumerate() {
+			score += match status {
+				BoardStatus::Machine => weight,
+				BoardStatus::Human => -weight,
+				BoardStatus::Draw => 0,
+				BoardStatus::Ongoing => {
+					let sub_board = self.sub_board(b);
+					LINES.iter().map(|line| Self::evaluate_line(line.map(|c| sub_board[c]), &CELL_LINE_WEIGHTS)).sum()
+				}
+			};
+		}
+
+		score
+	}
+
+	/// Scores the position from the perspective of `player`, who is to move in
+	/// the sub-board `next` after the opponent played `last_cell`.
+	fn negamax(&mut self, player: Player, last_cell: usize, next: usize, remaining: u8, alpha: i32, beta: i32) -> i32 {
+		self.stats.visit(remaining);
+
+		if self.status(last_cell) {
+			self.stats.leaves += 1;
+			return -(OUTCOME_WIN + remaining as i32);
+		}
+
+		let (cells, count) = self.order_moves(player, next);
+		if count == 0 {
+			self.stats.leaves += 1;
+			return OUTCOME_DRAW;
+		}
+
+		if remaining == 0 {
+			self.stats.leaves += 1;
+			return match player {
+				Player::Machine => self.evaluate(),
+				_ => -self.evaluate(),
+			};
+		}
+
+		let mut max_v = i32::MIN;
+		let mut local_alpha = alpha;
+		for &cell in &cells[..count] {
+			let next = self.add(cell, player);
+			let m = -self.negamax(player.opponent(), cell, next, remaining - 1, -beta, -local_alpha);
+			self.remove(cell);
+
+			if m > max_v {
+				max_v = m;
+
+				local_alpha = cmp::max(local_alpha, max_v);
+				if local_alpha >= beta {
+					self.stats.cutoffs += 1;
+					break;
+				}
+			}
+		}
+
+		max_v
+	}
+
+	fn max_top(&mut self, player: Player, remaining: u8) -> usize {
+		if remaining == 0 {
+			return U_INVALID_INDEX;
+		}
+
+		// The root is not visited, its children are the first ply:
+		self.stats = Stats::new(remaining - 1);
+
+		let mut max_v = i32::MIN;
+		let mut best = U_INVALID_INDEX;
+		let mut alpha = -i32::MAX;
+		let (cells, count) = self.order_moves(player, self.next);
+		for &cell in &cells[..count] {
+			let next = self.add(cell, player);
+			let points = -self.negamax(player.opponent(), cell, next, remaining - 1, -i32::MAX, -alpha);
+			self.remove(cell);
+
+			if points > max_v {
+				max_v = points;
+				best = cell;
+				alpha = max_v;
+			}
+		}
+
+		best
+	}
+
+	/// Checks whether the game ended, either won or without moves left.
+	fn finished(&self) -> bool {
+		self.winner != Player::Unset || self.boards.iter().all(|&b| b != BoardStatus::Ongoing)
+	}
+
+	/// Returns the best move for `player`, searching `maximum_depth` plies
+	/// deep, or [`U_INVALID_INDEX`] if the game is finished.
+	pub fn get_best_move(&mut self, maximum_depth: u8, player: Player) -> usize {
+		let start = Instant::now();
+		self.stats = Stats::default();
+
+		let cell = if self.finished() {
+			U_INVALID_INDEX
+		} else if self.empty.iter().all(|&e| e as usize == BOARD_CELLS) {
+			// The center of the center sub-board takes part in the most lines and
+			// sends the opponent back to it:
+			4 * BOARD_CELLS + 4
+		} else {
+			self.max_top(player, maximum_depth)
+		};
+
+		self.stats.elapsed = start.elapsed();
+		cell
+	}
+}
+
+#[napi]
+impl UltimateTicTacToe {
+	/// Creates a board from its 81 cells, where the next move must be played in
+	/// the sub-board `nextBoard`, or in any open one if it is not set.
+	#[napi(constructor)]
+	pub fn js_new(values: Option<Uint8Array>, next_board: Option<i32>) -> Result<Self> {
+		let mut cells = [Player::Unset; CELLS];
+		if let Some(v) = values {
+			let input = v.to_vec();
+			if input.len() != CELLS {
+				return Err(Error::from_reason("data must have exactly 81 numbers"));
+			}
+
+			for (cell, value) in cells.iter_mut().zip(input) {
+				*cell = Player::try_from(value).map_err(Error::from_reason)?;
+			}
+		}
+
+		let next = match next_board {
+			Some(next_board) => isize_to_usize!(next_board, BOARDS)?,
+			None => U_INVALID_INDEX,
+		};
+
+		Ok(UltimateTicTacToe::new(cells, next))
+	}
+
+	#[napi(getter = board)]
+	pub fn js_get_board(&self) -> Uint8Array {
+		Uint8Array::new(self.cells.map(|v| v as u8).to_vec())
+	}
+
+	/// The state of every sub-board, as `BoardStatus` values.
+	#[napi(getter = boards)]
+	pub fn js_get_boards(&self) -> Uint8Array {
+		Uint8Array::new(self.boards.map(|v| v as u8).to_vec())
+	}
+
+	/// The sub-board the next move must be played in, or `INVALID_INDEX` if
+	/// any open sub-board is allowed.
+	#[napi(getter = nextBoard)]
+	pub fn js_get_next_board(&self) -> u32 {
+		self.next as u32
+	}
+
+	#[napi(js_name = "available")]
+	pub fn js_available(&self, cell: i32) -> Result<bool> {
+		Ok(!self.finished() && self.available(isize_to_usize!(cell, CELLS)?, self.next))
+	}
+
+	#[napi(getter = finished)]
+	pub fn js_finished(&self) -> bool {
+		self.finished()
+	}
+
+	#[napi(js_name = "add")]
+	pub fn js_add(&mut self, cell: i32, player: Player) -> Result<bool> {
+		let c = isize_to_usize!(cell, CELLS)?;
+		napi_assert!(!self.finished() && self.available(c, self.next));
+
+		self.next = self.add(c, player);
+		if self.status(c) {
+			self.winner = player;
+		}
+
+		Ok(self.winner != Player::Unset)
+	}
+
+	/// The statistics of the last call to `getBestMove`, every counter is zero
+	/// before the first search.
+	#[napi(getter = lastSearchStats)]
+	pub fn js_get_last_search_stats(&self) -> SearchStats {
+		self.stats.into()
+	}
+
+	/// Returns the best cell for the machine, or for the player given in the
+	/// options, or `INVALID_INDEX` if the game is finished.
+	#[napi(js_name = "getBestMove")]
+	pub fn js_get_best_move(&mut self, options: Option<BestMoveArgument>) -> Result<i32> {
+		let (depth, player) = BestMoveOptions::resolve(options, 6)?;
+		Ok(self.get_best_move(depth, player) as i32)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Creates the cells with the given cells of each player.
+	fn cells(human: &[usize], machine: &[usize]) -> [Player; CELLS] {
+		let mut cells = [Player::Unset; CELLS];
+		for &cell in human {
+			cells[cell] = Player::Human;
+		}
+		for &cell in machine {
+			cells[cell] = Player::Machine;
+		}
+		cells
+	}
+
+	/// Returns the cells of the line `line` of the sub-board `b`.
+	fn line(b: usize, line: usize) -> [usize; 3] {
+		LINES[line].map(|c| b * BOARD_CELLS + c)
+	}
+
+	mod new {
+		use super::*;
+
+		#[test]
+		fn test_empty() {
+			let board = UltimateTicTacToe::new(cells(&[], &[]), U_INVALID_INDEX);
+
+			assert_eq!(board.boards, [BoardStatus::Ongoing; BOARDS]);
+			assert_eq!(board.empty, [9; BOARDS]);
+			assert_eq!(board.next, U_INVALID_INDEX);
+			assert_eq!(board.winner, Player::Unset);
+		}
+
+		#[test]
+		fn test_closed_boards() {
+			// Sub-board 0 is won by the human, sub-board 8 is drawn:
+			let human = [line(0, 0).as_slice(), &[72, 74, 75, 79]].concat();
+			let board = UltimateTicTacToe::new(cells(&human, &[73, 76, 77, 78, 80]), 0);
+
+			assert_eq!(board.boards[0], BoardStatus::Human);
+			assert_eq!(board.boards[8], BoardStatus::Draw);
+			assert_eq!(board.empty[0], 6);
+			// The closed sub-board lets the next move be played anywhere:
+			assert_eq!(board.next, U_INVALID_INDEX);
+		}
+
+		#[test]
+		fn test_winner() {
+			let machine = [line(2, 6), line(4, 3), line(6, 1)].concat();
+			let board = UltimateTicTacToe::new(cells(&[], &machine), 3);
+
+			assert_eq!(board.winner, Player::Machine);
+			assert!(board.finished());
+			assert_eq!(board.next, 3);
+		}
+	}
+
+	mod add {
+		use super::*;
+
+		#[test]
+		fn test_sends_to_board() {
+			let mut board = UltimateTicTacToe::new(cells(&[], &[]), U_INVALID_INDEX);
+
+			assert_eq!(board.add(41, Player::Human), 5);
+			assert!(board.available(46, 5));
+			assert!(!board.available(40, 5));
+		}
+
+		#[test]
+		fn test_wins_board() {
+			let mut board = UltimateTicTacToe::new(cells(&[9, 10], &[]), U_INVALID_INDEX);
+
+			// Cell 2 of sub-board 1 sends the opponent to sub-board 2:
+			assert_eq!(board.add(11, Player::Human), 2);
+			assert_eq!(board.boards[1], BoardStatus::Human);
+
+			board.remove(11);
+			assert_eq!(board.boards[1], BoardStatus::Ongoing);
+			assert_eq!(board.empty[1], 7);
+		}
+
+		#[test]
+		fn test_sent_to_closed_board() {
+			let mut board = UltimateTicTacToe::new(cells(&line(1, 0), &[]), U_INVALID_INDEX);
+
+			assert_eq!(board.add(1, Player::Machine), U_INVALID_INDEX);
+		}
+	}
+
+	mod status {
+		use super::*;
+
+		#[test]
+		fn test_line_of_boards() {
+			let human = [line(0, 0), line(1, 0), [18, 19, 0]].concat();
+			let mut board = UltimateTicTacToe::new(cells(&human[..8], &[]), U_INVALID_INDEX);
+			assert!(!board.status(19));
+
+			board.add(20, Player::Human);
+			assert!(board.status(20));
+		}
+
+		#[test]
+		fn test_board_without_line() {
+			let board = UltimateTicTacToe::new(cells(&line(4, 6), &[]), U_INVALID_INDEX);
+
+			assert!(!board.status(42));
+		}
+	}
+
+	mod evaluate {
+		use super::*;
+
+		#[test]
+		fn test_empty() {
+			assert_eq!(UltimateTicTacToe::new(cells(&[], &[]), U_INVALID_INDEX).evaluate(), 0);
+		}
+
+		#[test]
+		fn test_symmetric() {
+			let human = UltimateTicTacToe::new(cells(&line(4, 0), &[0]), U_INVALID_INDEX);
+			let machine = UltimateTicTacToe::new(cells(&[0], &line(4, 0)), U_INVALID_INDEX);
+
+			assert_eq!(human.evaluate(), -machine.evaluate());
+			assert!(machine.evaluate() > 0);
+		}
+	}
+
+	mod get_best_move {
+		use super::*;
+
+		macro_rules! gen_test {
+			($($name:ident: [$human:expr, $machine:expr, $next:expr, $outcome:expr],)*) => ($(
+				#[test]
+				fn $name() {
+					let mut board = UltimateTicTacToe::new(cells(&$human, &$machine), $next);
+					assert_eq!(board.get_best_move(4, Player::Machine), $outcome);
+				}
+			)*);
+		}
+
+		gen_test! {
+			test_opening: [[], [], U_INVALID_INDEX, 40],
+			// Winning sub-board 4 completes the middle column of sub-boards:
+			test_win: [
+				[27, 28, 55, 57],
+				[line(1, 0).as_slice(), &line(7, 0), &[36, 37]].concat(),
+				4,
+				38
+			],
+			// The human wins the game with sub-board 4 unless it is blocked:
+			test_block: [
+				[line(1, 0).as_slice(), &line(7, 0), &[36, 37]].concat(),
+				[27, 28, 56, 57],
+				4,
+				38
+			],
+			test_finished: [[], [line(0, 0), line(1, 0), line(2, 0)].concat(), U_INVALID_INDEX, U_INVALID_INDEX],
+		}
+
+		#[test]
+		fn test_human_win() {
+			let human = [line(1, 0).as_slice(), &line(7, 0), &[36, 37]].concat();
+			let mut board = UltimateTicTacToe::new(cells(&human, &[27, 28, 56, 57]), 4);
+			assert_eq!(board.get_best_move(4, Player::Human), 38);
+		}
+
+		#[test]
+		fn test_human_block() {
+			let machine = [line(1, 0).as_slice(), &line(7, 0), &[36, 37]].concat();
+			let mut board = UltimateTicTacToe::new(cells(&[27, 28, 55, 57], &machine), 4);
+			assert_eq!(board.get_best_move(4, Player::Human), 38);
+		}
+
+		#[test]
+		fn test_stays_in_next_board() {
+			let mut board = UltimateTicTacToe::new(cells(&[40], &[]), 4);
+			let cell = board.get_best_move(4, Player::Machine);
+
+			assert_eq!(cell / BOARD_CELLS, 4);
+			assert_eq!(board.stats.max_depth, 4);
+			assert!(board.stats.cutoffs > 0);
+		}
+	}
+}
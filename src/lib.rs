@@ -1,24 +1,72 @@
 #![deny(clippy::all)]
 #![feature(portable_simd)]
-#![feature(iter_map_windows)]
 
 #[macro_use]
 extern crate napi_derive;
 
+// The tuner disables the N-API bindings, which leaves the games it does not
+// play unused:
 mod games {
+	#[cfg_attr(feature = "tune", allow(dead_code))]
+	pub mod checkers;
 	pub mod connect_four;
+	#[cfg_attr(feature = "tune", allow(dead_code))]
+	pub mod dots_and_boxes;
+	#[cfg_attr(feature = "tune", allow(dead_code))]
+	pub mod hex;
+	#[cfg_attr(feature = "tune", allow(dead_code))]
+	pub mod mancala;
+	#[cfg_attr(feature = "tune", allow(dead_code))]
+	pub mod mnk_game;
+	#[cfg_attr(feature = "tune", allow(dead_code))]
+	pub mod nine_mens_morris;
+	#[cfg_attr(feature = "tune", allow(dead_code))]
+	pub mod notakto;
+	#[cfg_attr(feature = "tune", allow(dead_code))]
+	pub mod othello;
+	#[cfg_attr(feature = "tune", allow(dead_code))]
+	pub mod qubic;
+	#[cfg_attr(feature = "tune", allow(dead_code))]
 	pub mod tic_tac_toe;
+	#[cfg_attr(feature = "tune", allow(dead_code))]
+	pub mod ultimate_tic_tac_toe;
 }
 
+mod search {
+	pub mod alpha_beta;
+	pub mod game;
+	pub mod mcts;
+	pub mod options;
+	pub mod random;
+	pub mod stats;
+	pub mod tt;
+}
+
+#[cfg(feature = "tune")]
+pub mod tuning;
+
 #[napi]
 #[repr(u8)]
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "tune", derive(Clone, Copy))]
 pub enum Player {
 	Unset,
 	Human,
 	Machine,
 }
 
+impl Player {
+	/// Returns the player playing against this one, [`Player::Unset`] has no
+	/// opponent and is returned as-is.
+	pub fn opponent(self) -> Self {
+		match self {
+			Player::Unset => Player::Unset,
+			Player::Human => Player::Machine,
+			Player::Machine => Player::Human,
+		}
+	}
+}
+
 impl From<Player> for u8 {
 	fn from(value: Player) -> Self {
 		unsafe { std::mem::transmute_copy(&value) }
@@ -38,6 +86,18 @@ impl TryFrom<u8> for Player {
 	}
 }
 
+/// The algorithm a game uses to pick its moves.
+#[napi]
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "tune", derive(Clone, Copy))]
+pub enum Engine {
+	/// Minimax search with alpha-beta pruning over the game's evaluation.
+	AlphaBeta,
+	/// Monte Carlo Tree Search, which plays out random games and needs no
+	/// evaluation.
+	MonteCarlo,
+}
+
 pub const U_INVALID_INDEX: usize = 255;
 
 #[napi]
@@ -0,0 +1,203 @@
+use std::time::Instant;
+
+use super::{
+	game::{Game, Outcome},
+	stats::Stats,
+};
+
+/// The score of a won game, adjusted by the remaining depth so the search
+/// prefers the fastest wins and the slowest losses.
+pub const OUTCOME_WIN: i32 = 1_000_000;
+
+/// A game position that can be scored without playing it to its end, so the
+/// [`AlphaBeta`] engine can stop at any depth.
+pub trait Evaluate: Game {
+	/// Scores the position from the perspective of the player to move, within
+	/// `(-OUTCOME_WIN, OUTCOME_WIN)`.
+	fn evaluate(&self) -> i32;
+}
+
+/// A depth-limited negamax search with alpha-beta pruning over any
+/// [`Evaluate`] game.
+///
+/// Unlike the searches written for a single game, positions are copied rather
+/// than undone, and a player may move several times in a row.
+#[derive(Debug, Default)]
+pub struct AlphaBeta {
+	/// The counters of the last search.
+	stats: Stats,
+}
+
+impl AlphaBeta {
+	/// Returns the best move for the player to move in `state`, searching
+	/// `depth` plies deep, or [`None`] if the game is finished.
+	pub fn search<G: Evaluate>(&mut self, state: &G, depth: u8) -> Option<G::Move> {
+		let start = Instant::now();
+
+		// The root is not visited, its children are the first ply:
+		self.stats = Stats::new(depth.saturating_sub(1));
+
+		let mut best = None;
+		if depth > 0 && state.outcome().is_none() {
+			let mut alpha = -i32::MAX;
+			for mv in state.moves() {
+				let mut child = state.clone();
+				child.play(mv);
+
+				let score = self.child_score(state, &child, depth - 1, alpha, i32::MAX);
+				if best.is_none() || score > alpha {
+					alpha = score;
+					best = Some(mv);
+				}
+			}
+		}
+
+		self.stats.elapsed = start.elapsed();
+		best
+	}
+
+	pub fn stats(&self) -> Stats {
+		self.stats
+	}
+
+	/// Scores `child` from the perspective of the player to move in `parent`.
+	fn child_score<G: Evaluate>(&mut self, parent: &G, child: &G, remaining: u8, alpha: i32, beta: i32) -> i32 {
+		if child.player() == parent.player() {
+			self.negamax(child, remaining, alpha, beta)
+		} else {
+			-self.negamax(child, remaining, -beta, -alpha)
+		}
+	}
+
+	/// Scores `state` from the perspective of the player to move.
+	fn negamax<G: Evaluate>(&mut self, state: &G, remaining: u8, mut alpha: i32, beta: i32) -> i32 {
+		self.stats.visit(remaining);
+
+		match state.outcome() {
+			Some(Outcome::Win(player)) => {
+				self.stats.leaves += 1;
+				let score = OUTCOME_WIN + remaining as i32;
+				return if player == state.player() { score } else { -score };
+			}
+			Some(Outcome::Draw) => {
+				self.stats.leaves += 1;
+				return 0;
+			}
+			None => {}
+		}
+
+		if remaining == 0 {
+			self.stats.leaves += 1;
+			return state.evaluate();
+		}
+
+		let mut best = -i32::MAX;
+		for mv in state.moves() {
+			let mut child = state.clone();
+			child.play(mv);
+
+			best = best.max(self.child_score(state, &child, remaining - 1, alpha, beta));
+			alpha = alpha.max(best);
+			if alpha >= beta {
+				self.stats.cutoffs += 1;
+				break;
+			}
+		}
+
+		best
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Player;
+
+	/// A game of Nim where players take 1 or 2 stones, and the player taking
+	/// the last stone wins. With `bonus`, taking 2 stones grants another turn.
+	#[derive(Debug, Clone, PartialEq)]
+	struct Nim {
+		stones: u8,
+		player: Player,
+		bonus: bool,
+	}
+
+	impl Game for Nim {
+		type Move = u8;
+
+		fn player(&self) -> Player {
+			self.player
+		}
+
+		fn moves(&self) -> Vec<u8> {
+			(1..=self.stones.min(2)).collect()
+		}
+
+		fn play(&mut self, mv: u8) {
+			self.stones -= mv;
+			if !(self.bonus && mv == 2) || self.stones == 0 {
+				self.player = self.player.opponent();
+			}
+		}
+
+		fn outcome(&self) -> Option<Outcome> {
+			(self.stones == 0).then(|| Outcome::Win(self.player.opponent()))
+		}
+	}
+
+	impl Evaluate for Nim {
+		fn evaluate(&self) -> i32 {
+			0
+		}
+	}
+
+	fn nim(stones: u8, bonus: bool) -> Nim {
+		Nim { stones, player: Player::Machine, bonus }
+	}
+
+	mod search {
+		use super::*;
+
+		macro_rules! gen_test {
+			($($name:ident: [$state:expr, $depth:expr, $outcome:expr],)*) => ($(
+				#[test]
+				fn $name() {
+					assert_eq!(AlphaBeta::default().search(&$state, $depth), $outcome);
+				}
+			)*);
+		}
+
+		gen_test! {
+			test_leave_multiple_of_three: [nim(4, false), 8, Some(1)],
+			test_take_two: [nim(5, false), 8, Some(2)],
+			test_take_last: [nim(2, false), 1, Some(2)],
+			test_finished: [nim(0, false), 8, None],
+			test_no_depth: [nim(4, false), 0, None],
+			// Taking 2 of 3 stones keeps the turn, and takes the last one next:
+			test_extra_turn: [nim(3, true), 8, Some(2)],
+		}
+
+		#[test]
+		fn test_stats() {
+			let mut search = AlphaBeta::default();
+			search.search(&nim(10, false), 6);
+
+			assert!(search.stats().nodes > 0);
+			assert!(search.stats().cutoffs > 0);
+			assert_eq!(search.stats().max_depth, 6);
+		}
+	}
+
+	mod negamax {
+		use super::*;
+
+		#[test]
+		fn test_prefers_faster_win() {
+			let mut search = AlphaBeta::default();
+			let state = Nim { player: Player::Human, ..nim(0, false) };
+
+			assert_eq!(search.negamax(&state, 3, -i32::MAX, i32::MAX), -(OUTCOME_WIN + 3));
+			assert_eq!(search.negamax(&nim(3, false), 4, -i32::MAX, i32::MAX), -(OUTCOME_WIN + 2));
+		}
+	}
+}
@@ -0,0 +1,49 @@
+use super::random::Random;
+use crate::Player;
+
+/// The result of a finished game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+	Win(Player),
+	Draw,
+}
+
+impl Outcome {
+	/// Returns the reward of the outcome for `player`: `1` for a win, `0.5` for
+	/// a draw, and `0` for a loss.
+	pub fn reward(self, player: Player) -> f64 {
+		match self {
+			Outcome::Win(winner) if winner == player => 1.0,
+			Outcome::Win(_) => 0.0,
+			Outcome::Draw => 0.5,
+		}
+	}
+}
+
+/// A game position that the generic search algorithms can play out.
+///
+/// Unlike the boards exposed to JavaScript, a position tracks the player to
+/// move, so games where a player may move several times in a row can be
+/// searched too.
+pub trait Game: Clone {
+	type Move: Copy + PartialEq;
+
+	/// Returns the player to move.
+	fn player(&self) -> Player;
+
+	/// Returns the legal moves for the player to move, which is empty once the
+	/// game is finished.
+	fn moves(&self) -> Vec<Self::Move>;
+
+	/// Plays a legal move for the player to move.
+	fn play(&mut self, mv: Self::Move);
+
+	/// Returns the outcome of the game, or [`None`] if it is still ongoing.
+	fn outcome(&self) -> Option<Outcome>;
+
+	/// Picks a move during a heuristic-guided playout, or [`None`] to fall back
+	/// to a random move.
+	fn heuristic_move(&mut self, _moves: &[Self::Move], _random: &mut Random) -> Option<Self::Move> {
+		None
+	}
+}
@@ -0,0 +1,379 @@
+use std::time::{Duration, Instant};
+
+use super::{
+	game::{Game, Outcome},
+	random::Random,
+	stats::Stats,
+};
+use crate::Player;
+
+/// The exploration constant of UCT, `√2` is the theoretical optimum for
+/// rewards in `[0, 1]`.
+const EXPLORATION: f64 = std::f64::consts::SQRT_2;
+
+const DEFAULT_ITERATIONS: u32 = 10_000;
+
+/// How the moves of a simulation are picked.
+#[napi]
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "tune", derive(Clone, Copy))]
+pub enum Playout {
+	/// Every move is picked at random.
+	Random,
+	/// Moves are picked by the game's heuristic, such as taking immediate wins
+	/// or blocking the opponent's, falling back to random moves.
+	#[cfg_attr(feature = "tune", allow(dead_code))]
+	Heuristic,
+}
+
+/// The options of the Monte Carlo Tree Search engine.
+#[napi(object)]
+#[derive(Debug, Default)]
+pub struct MonteCarloOptions {
+	/// The maximum amount of iterations per move, at least 1, defaults to
+	/// 10,000 when neither this nor `time` are set.
+	pub iterations: Option<u32>,
+	/// The maximum time per move, in milliseconds.
+	pub time: Option<u32>,
+	/// How the simulations are played, defaults to `Playout.Random`.
+	pub playout: Option<Playout>,
+	/// The seed of the random number generator, defaults to the system clock.
+	pub seed: Option<u32>,
+}
+
+#[derive(Debug, Clone)]
+struct Node<M> {
+	/// The move that led to this node, [`None`] for the root.
+	mv: Option<M>,
+	/// The player who made the move that led to this node.
+	player: Player,
+	children: Vec<usize>,
+	untried: Vec<M>,
+	visits: u32,
+	reward: f64,
+}
+
+impl<M> Node<M> {
+	fn new(mv: Option<M>, player: Player, untried: Vec<M>) -> Self {
+		Self { mv, player, children: Vec::new(), untried, visits: 0, reward: 0.0 }
+	}
+
+	fn uct(&self, parent_visits: f64) -> f64 {
+		let visits = self.visits as f64;
+		self.reward / visits + EXPLORATION * (parent_visits.ln() / visits).sqrt()
+	}
+}
+
+/// A Monte Carlo Tree Search engine using UCT for the selection.
+///
+/// The tree is kept between searches, [`MonteCarlo::advance`] moves its root
+/// down as moves are played, so the statistics of the followed line are reused
+/// by the next search.
+#[derive(Debug, Clone)]
+pub struct MonteCarlo<G: Game> {
+	iterations: Option<u32>,
+	time: Option<Duration>,
+	playout: Playout,
+	random: Random,
+	nodes: Vec<Node<G::Move>>,
+	root: Option<G>,
+	/// The counters of the last search, where every iteration visits the nodes
+	/// of its path and evaluates its simulation as a leaf.
+	stats: Stats,
+}
+
+impl<G: Game + PartialEq> MonteCarlo<G> {
+	/// Creates an engine from `options`, failing if they allow no iteration.
+	pub fn new(options: MonteCarloOptions) -> Result<Self, String> {
+		let iterations = match (options.iterations, options.time) {
+			(Some(0), _) => return Err("iterations must be at least 1".into()),
+			(None, None) => Some(DEFAULT_ITERATIONS),
+			(iterations, _) => iterations,
+		};
+
+		Ok(Self {
+			iterations,
+			time: options.time.map(|ms| Duration::from_millis(ms.into())),
+			playout: options.playout.unwrap_or(Playout::Random),
+			random: options.seed.map_or_else(Random::from_time, |seed| Random::new(seed.into())),
+			nodes: Vec::new(),
+			root: None,
+			stats: Stats::default(),
+		})
+	}
+
+	/// Returns the best move for the player to move in `state`, or [`None`] if
+	/// the game is finished. Runs at least one iteration, even once the time
+	/// is up, so there is always a move to return.
+	pub fn search(&mut self, state: &G) -> Option<G::Move> {
+		if state.outcome().is_some() {
+			return None;
+		}
+
+		if self.root.as_ref() != Some(state) {
+			self.reset(state);
+		}
+
+		self.stats = Stats::default();
+
+		let start = Instant::now();
+		let mut iterations = 0;
+		loop {
+			self.iterate();
+			iterations += 1;
+
+			if self.iterations.is_some_and(|max| iterations >= max)
+				|| self.time.is_some_and(|max| start.elapsed() >= max)
+			{
+				break;
+			}
+		}
+
+		self.stats.elapsed = start.elapsed();
+		self.nodes[0].children.iter().map(|&index| &self.nodes[index]).max_by_key(|node| node.visits)?.mv
+	}
+
+	pub fn stats(&self) -> Stats {
+		self.stats
+	}
+
+	/// Moves the root of the tree to the child reached by `mv`, discarding the
+	/// statistics of every other branch.
+	pub fn advance(&mut self, mv: G::Move) {
+		let Some(mut root) = self.root.take() else {
+			return;
+		};
+
+		let Some(child) = self.nodes[0].children.iter().copied().find(|&index| self.nodes[index].mv == Some(mv)) else {
+			self.nodes.clear();
+			return;
+		};
+
+		// Copy the subtree in breadth-first order, remapping the children:
+		let mut nodes = vec![self.nodes[child].clone()];
+		nodes[0].mv = None;
+		let mut index = 0;
+		while index < nodes.len() {
+			let children = std::mem::take(&mut nodes[index].children);
+			for old in children {
+				let new = nodes.len();
+				nodes[index].children.push(new);
+				nodes.push(self.nodes[old].clone());
+			}
+			index += 1;
+		}
+
+		root.play(mv);
+		self.nodes = nodes;
+		self.root = Some(root);
+	}
+
+	fn reset(&mut self, state: &G) {
+		self.nodes.clear();
+		self.nodes.push(Node::new(None, Player::Unset, state.moves()));
+		self.root = Some(state.clone());
+	}
+
+	fn select(&self, index: usize) -> usize {
+		let parent_visits = self.nodes[index].visits as f64;
+		let children = &self.nodes[index].children;
+
+		// The children are never empty when selecting, and their visits are
+		// always positive as they are visited right after their expansion:
+		*children
+			.iter()
+			.max_by(|&&a, &&b| self.nodes[a].uct(parent_visits).total_cmp(&self.nodes[b].uct(parent_visits)))
+			.unwrap()
+	}
+
+	fn iterate(&mut self) {
+		let mut game = self.root.clone().unwrap();
+		let mut path = vec![0];
+		let mut index = 0;
+
+		// Selection, descend through fully expanded nodes:
+		while self.nodes[index].untried.is_empty() && !self.nodes[index].children.is_empty() {
+			index = self.select(index);
+			game.play(self.nodes[index].mv.unwrap());
+			path.push(index);
+		}
+
+		// Expansion, add one of the untried moves:
+		if !self.nodes[index].untried.is_empty() {
+			let untried = &mut self.nodes[index].untried;
+			let mv = untried.swap_remove(self.random.below(untried.len() as u64) as usize);
+
+			let player = game.player();
+			game.play(mv);
+
+			let child = self.nodes.len();
+			self.nodes.push(Node::new(Some(mv), player, game.moves()));
+			self.nodes[index].children.push(child);
+			path.push(child);
+		}
+
+		self.stats.nodes += path.len() as u64;
+		self.stats.leaves += 1;
+		self.stats.max_depth = self.stats.max_depth.max((path.len() - 1).try_into().unwrap_or(u8::MAX));
+
+		// Simulation, play the game out until it finishes:
+		let outcome = self.simulate(game);
+
+		// Backpropagation, every node is rewarded from the perspective of the
+		// player who made the move leading to it:
+		for index in path {
+			let node = &mut self.nodes[index];
+			node.visits += 1;
+			node.reward += outcome.reward(node.player);
+		}
+	}
+
+	fn simulate(&mut self, mut game: G) -> Outcome {
+		loop {
+			if let Some(outcome) = game.outcome() {
+				return outcome;
+			}
+
+			let moves = game.moves();
+			let mv = match self.playout {
+				Playout::Heuristic => game.heuristic_move(&moves, &mut self.random),
+				Playout::Random => None,
+			};
+
+			game.play(mv.unwrap_or_else(|| self.random.pick(&moves)));
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// A game of Nim where players take 1 or 2 stones, and the player taking
+	/// the last stone wins.
+	#[derive(Debug, Clone, PartialEq)]
+	struct Nim {
+		stones: u8,
+		player: Player,
+	}
+
+	impl Game for Nim {
+		type Move = u8;
+
+		fn player(&self) -> Player {
+			self.player
+		}
+
+		fn moves(&self) -> Vec<u8> {
+			(1..=self.stones.min(2)).collect()
+		}
+
+		fn play(&mut self, mv: u8) {
+			self.stones -= mv;
+			self.player = self.player.opponent();
+		}
+
+		fn outcome(&self) -> Option<Outcome> {
+			(self.stones == 0).then(|| Outcome::Win(self.player.opponent()))
+		}
+	}
+
+	fn engine() -> MonteCarlo<Nim> {
+		MonteCarlo::new(MonteCarloOptions { iterations: Some(2_000), seed: Some(42), ..Default::default() }).unwrap()
+	}
+
+	mod new {
+		use super::*;
+
+		#[test]
+		fn test_no_iterations() {
+			let options = MonteCarloOptions { iterations: Some(0), ..Default::default() };
+
+			assert_eq!(MonteCarlo::<Nim>::new(options).err(), Some("iterations must be at least 1".into()));
+		}
+	}
+
+	mod search {
+		use super::*;
+
+		macro_rules! gen_test {
+			($($name:ident: [$stones:expr, $outcome:expr],)*) => ($(
+				#[test]
+				fn $name() {
+					let mut mcts = engine();
+					let state = Nim { stones: $stones, player: Player::Machine };
+
+					assert_eq!(mcts.search(&state), $outcome);
+				}
+			)*);
+		}
+
+		gen_test! {
+			test_finished: [0, None],
+			test_take_last: [1, Some(1)],
+			test_take_both: [2, Some(2)],
+			test_leave_multiple_of_three_from_4: [4, Some(1)],
+			test_leave_multiple_of_three_from_5: [5, Some(2)],
+			test_leave_multiple_of_three_from_7: [7, Some(1)],
+		}
+
+		#[test]
+		fn test_reproducible_with_seed() {
+			let state = Nim { stones: 10, player: Player::Machine };
+			let (mut a, mut b) = (engine(), engine());
+
+			assert_eq!(a.search(&state), b.search(&state));
+			assert_eq!(a.nodes.len(), b.nodes.len());
+		}
+
+		#[test]
+		fn test_time_up() {
+			let options = MonteCarloOptions { time: Some(0), seed: Some(42), ..Default::default() };
+			let mut mcts = MonteCarlo::new(options).unwrap();
+
+			assert_eq!(mcts.search(&Nim { stones: 1, player: Player::Machine }), Some(1));
+			assert_eq!(mcts.stats().leaves, 1);
+		}
+
+		#[test]
+		fn test_stats() {
+			let mut mcts = engine();
+			mcts.search(&Nim { stones: 10, player: Player::Machine });
+
+			let stats = mcts.stats();
+			assert_eq!(stats.leaves, 2_000);
+			assert!(stats.nodes > stats.leaves);
+			assert!(stats.max_depth > 1 && stats.max_depth <= 10);
+		}
+	}
+
+	mod advance {
+		use super::*;
+
+		#[test]
+		fn test_reuses_subtree() {
+			let mut mcts = engine();
+			let state = Nim { stones: 10, player: Player::Machine };
+			mcts.search(&state);
+
+			let child = mcts.nodes[0].children.iter().copied().find(|&i| mcts.nodes[i].mv == Some(1)).unwrap();
+			let visits = mcts.nodes[child].visits;
+			mcts.advance(1);
+
+			assert_eq!(mcts.root, Some(Nim { stones: 9, player: Player::Human }));
+			assert_eq!(mcts.nodes[0].visits, visits);
+			assert!(mcts.nodes[0].mv.is_none());
+		}
+
+		#[test]
+		fn test_unknown_move_discards_tree() {
+			let mut mcts = engine();
+			let state = Nim { stones: 1, player: Player::Machine };
+			mcts.search(&state);
+			mcts.advance(2);
+
+			assert!(mcts.root.is_none());
+			assert!(mcts.nodes.is_empty());
+		}
+	}
+}
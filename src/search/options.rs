@@ -0,0 +1,55 @@
+use napi::{bindgen_prelude::Either, Error, Result};
+
+use crate::{napi_assert, Player};
+
+/// The options of `getBestMove`, for the games where either player can be
+/// searched for.
+#[napi(object)]
+#[derive(Debug, Default)]
+pub struct BestMoveOptions {
+	/// How many plies deep to search, defaults to the depth of the game.
+	pub depth: Option<i32>,
+	/// The player to find a move for, defaults to `Player.Machine`, or to the
+	/// player to move in the games that track whose turn it is.
+	#[napi(js_name = "for")]
+	pub player: Option<Player>,
+}
+
+/// The argument of `getBestMove`, either the depth to search or the options.
+pub type BestMoveArgument = Either<i32, BestMoveOptions>;
+
+/// Reads a depth to search given by JavaScript, `default_depth` unless given.
+pub fn parse_depth(depth: Option<i32>, default_depth: u8) -> Result<u8> {
+	match depth {
+		None => Ok(default_depth),
+		Some(depth) => u8::try_from(depth).map_err(|_| Error::from_reason("depth must be between 0 and 255")),
+	}
+}
+
+impl BestMoveOptions {
+	/// Reads the argument of `getBestMove`, returning the depth to search,
+	/// `default_depth` unless given, and the player to find a move for.
+	pub fn resolve(argument: Option<BestMoveArgument>, default_depth: u8) -> Result<(u8, Player)> {
+		Self::resolve_or(argument, default_depth, Player::Machine)
+	}
+
+	/// Reads the argument of `getBestMove` like [`BestMoveOptions::resolve`],
+	/// finding a move for `default_player` unless given.
+	pub fn resolve_or(
+		argument: Option<BestMoveArgument>,
+		default_depth: u8,
+		default_player: Player,
+	) -> Result<(u8, Player)> {
+		let options = match argument {
+			None => BestMoveOptions::default(),
+			Some(Either::A(depth)) => BestMoveOptions { depth: Some(depth), player: None },
+			Some(Either::B(options)) => options,
+		};
+
+		let depth = parse_depth(options.depth, default_depth)?;
+		let player = options.player.unwrap_or(default_player);
+		napi_assert!(player != Player::Unset);
+
+		Ok((depth, player))
+	}
+}
@@ -0,0 +1,61 @@
+/// A small xorshift generator, so results can be reproduced from a seed.
+#[derive(Debug, Clone)]
+pub struct Random(u64);
+
+/// Mixed into the seeds, so nearby ones start from unrelated states.
+const SEED_MIX: u64 = 0x9E37_79B9_7F4A_7C15;
+
+impl Random {
+	pub fn new(seed: u64) -> Self {
+		// The state must never be zero, otherwise the generator gets stuck, so
+		// the seed mixing into zero starts from the state of seed zero:
+		match seed ^ SEED_MIX {
+			0 => Self(SEED_MIX),
+			state => Self(state),
+		}
+	}
+
+	/// Creates a generator seeded from the system clock.
+	pub fn from_time() -> Self {
+		let nanos = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map_or(0, |d| d.as_nanos());
+		Self::new(nanos as u64)
+	}
+
+	pub fn next_u64(&mut self) -> u64 {
+		self.0 ^= self.0 << 13;
+		self.0 ^= self.0 >> 7;
+		self.0 ^= self.0 << 17;
+		self.0
+	}
+
+	pub fn below(&mut self, max: u64) -> u64 {
+		self.next_u64() % max
+	}
+
+	/// Picks a random element from a non-empty slice.
+	pub fn pick<T: Copy>(&mut self, values: &[T]) -> T {
+		debug_assert!(!values.is_empty());
+
+		values[self.below(values.len() as u64) as usize]
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	mod new {
+		use super::*;
+
+		#[test]
+		fn test_zero_state() {
+			let (mut random, mut zero) = (Random::new(SEED_MIX), Random::new(0));
+
+			for _ in 0..3 {
+				let value = random.next_u64();
+				assert_ne!(value, 0);
+				assert_eq!(value, zero.next_u64());
+			}
+		}
+	}
+}
@@ -0,0 +1,100 @@
+use std::{cmp, time::Duration};
+
+/// The counters of the work done by a search.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+	/// The depth of the search, used to compute the ply of the visited nodes.
+	horizon: u8,
+	/// The amount of nodes visited, including the leaves.
+	pub nodes: u64,
+	/// The amount of nodes scored without searching their children, as either
+	/// the game ended or the depth limit was reached.
+	pub leaves: u64,
+	/// The amount of nodes whose remaining moves were pruned.
+	pub cutoffs: u64,
+	/// The amount of nodes found in the transposition table.
+	pub tt_hits: u64,
+	/// The deepest ply reached, counting from the root.
+	pub max_depth: u8,
+	pub elapsed: Duration,
+}
+
+impl Stats {
+	/// Creates the counters of a search `horizon` plies deep.
+	pub fn new(horizon: u8) -> Self {
+		Self { horizon, ..Default::default() }
+	}
+
+	/// Counts a node visited with `remaining` plies left to search.
+	pub fn visit(&mut self, remaining: u8) {
+		self.nodes += 1;
+		self.max_depth = cmp::max(self.max_depth, (self.horizon + 1).saturating_sub(remaining));
+	}
+
+	/// Adds the counters of a search run alongside this one.
+	pub fn merge(&mut self, other: &Stats) {
+		self.nodes += other.nodes;
+		self.leaves += other.leaves;
+		self.cutoffs += other.cutoffs;
+		self.tt_hits += other.tt_hits;
+		self.max_depth = cmp::max(self.max_depth, other.max_depth);
+	}
+}
+
+/// The statistics of the last search, as returned by `lastSearchStats`.
+#[napi(object)]
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "tune", allow(dead_code))]
+pub struct SearchStats {
+	/// The amount of nodes visited, including the leaves.
+	pub nodes: i64,
+	/// The amount of positions scored without searching further, either by the
+	/// static evaluation or because the game ended.
+	pub leaf_evaluations: i64,
+	/// The amount of nodes whose remaining moves were pruned.
+	pub cutoffs: i64,
+	/// The amount of positions found in the transposition table.
+	pub tt_hits: i64,
+	/// The deepest ply reached, counting from the current position.
+	pub max_depth: u32,
+	/// The time spent searching, in milliseconds.
+	pub elapsed: f64,
+}
+
+impl From<Stats> for SearchStats {
+	fn from(stats: Stats) -> Self {
+		Self {
+			nodes: stats.nodes as i64,
+			leaf_evaluations: stats.leaves as i64,
+			cutoffs: stats.cutoffs as i64,
+			tt_hits: stats.tt_hits as i64,
+			max_depth: stats.max_depth.into(),
+			elapsed: stats.elapsed.as_secs_f64() * 1000.0,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_visit() {
+		let mut stats = Stats::new(3);
+		stats.visit(3);
+		stats.visit(1);
+		stats.visit(2);
+
+		assert_eq!(stats.nodes, 3);
+		assert_eq!(stats.max_depth, 3);
+	}
+
+	#[test]
+	fn test_merge() {
+		let mut a = Stats { nodes: 5, leaves: 3, cutoffs: 1, tt_hits: 2, max_depth: 4, ..Stats::new(4) };
+		let b = Stats { nodes: 7, leaves: 4, cutoffs: 2, tt_hits: 0, max_depth: 5, ..Stats::new(4) };
+		a.merge(&b);
+
+		assert_eq!((a.nodes, a.leaves, a.cutoffs, a.tt_hits, a.max_depth), (12, 7, 3, 2, 5));
+	}
+}
@@ -0,0 +1,212 @@
+/// The kind of value stored in an [`Entry`], as alpha-beta only computes exact
+/// values for nodes whose score falls within the window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bound {
+	/// The value is the exact score of the position.
+	Exact,
+	/// The search failed high, the score is at least the value.
+	Lower,
+	/// The search failed low, the score is at most the value.
+	Upper,
+}
+
+impl Bound {
+	/// Classifies `value` as found by a search with the window `[alpha, beta]`.
+	pub fn from_window(value: i32, alpha: i32, beta: i32) -> Self {
+		if value <= alpha {
+			Bound::Upper
+		} else if value >= beta {
+			Bound::Lower
+		} else {
+			Bound::Exact
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Entry<M> {
+	key: u64,
+	/// The remaining depth the position was searched with.
+	pub depth: u8,
+	pub value: i32,
+	pub bound: Bound,
+	/// The best move found, which is tried first when the position is searched
+	/// again.
+	pub mv: Option<M>,
+}
+
+impl<M> Entry<M> {
+	/// Returns the value of the entry if it is usable to cut a search of
+	/// `depth` remaining plies with the window `[alpha, beta]`.
+	pub fn cutoff(&self, depth: u8, alpha: i32, beta: i32) -> Option<i32> {
+		if self.depth < depth {
+			return None;
+		}
+
+		match self.bound {
+			Bound::Exact => Some(self.value),
+			Bound::Lower if self.value >= beta => Some(self.value),
+			Bound::Upper if self.value <= alpha => Some(self.value),
+			_ => None,
+		}
+	}
+}
+
+/// A fixed-size hash table of searched positions, indexed by their Zobrist key.
+///
+/// An empty table, as created by [`TranspositionTable::default`], never stores
+/// nor finds anything, and is free to clone.
+#[derive(Debug, Clone)]
+pub struct TranspositionTable<M> {
+	entries: Vec<Option<Entry<M>>>,
+}
+
+impl<M> Default for TranspositionTable<M> {
+	fn default() -> Self {
+		Self { entries: Vec::new() }
+	}
+}
+
+impl<M: Copy> TranspositionTable<M> {
+	/// Creates a table of `2^bits` entries.
+	pub fn new(bits: u8) -> Self {
+		Self { entries: vec![None; 1 << bits] }
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.entries.is_empty()
+	}
+
+	fn index(&self, key: u64) -> usize {
+		(key as usize) & (self.entries.len() - 1)
+	}
+
+	pub fn probe(&self, key: u64) -> Option<&Entry<M>> {
+		if self.is_empty() {
+			return None;
+		}
+
+		self.entries[self.index(key)].as_ref().filter(|entry| entry.key == key)
+	}
+
+	/// Stores a searched position, replacing the previous entry of its slot
+	/// unless it holds the same position searched deeper.
+	pub fn store(&mut self, key: u64, depth: u8, value: i32, bound: Bound, mv: Option<M>) {
+		if self.is_empty() {
+			return;
+		}
+
+		let index = self.index(key);
+		if let Some(entry) = &self.entries[index] {
+			if entry.key == key && entry.depth > depth {
+				return;
+			}
+		}
+
+		self.entries[index] = Some(Entry { key, depth, value, bound, mv });
+	}
+}
+
+/// Generates `N` pseudo-random keys for Zobrist hashing at compile time.
+pub const fn zobrist_keys<const N: usize>(seed: u64) -> [u64; N] {
+	let mut keys = [0; N];
+	let mut state = seed;
+	let mut i = 0;
+	while i < N {
+		// SplitMix64, which produces well distributed keys from any seed:
+		state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+		let mut z = state;
+		z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+		z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+		keys[i] = z ^ (z >> 31);
+		i += 1;
+	}
+
+	keys
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	mod bound {
+		use super::*;
+
+		#[test]
+		fn test_from_window() {
+			assert_eq!(Bound::from_window(-5, 0, 10), Bound::Upper);
+			assert_eq!(Bound::from_window(0, 0, 10), Bound::Upper);
+			assert_eq!(Bound::from_window(5, 0, 10), Bound::Exact);
+			assert_eq!(Bound::from_window(10, 0, 10), Bound::Lower);
+		}
+	}
+
+	mod cutoff {
+		use super::*;
+
+		fn entry(depth: u8, value: i32, bound: Bound) -> Entry<u8> {
+			Entry { key: 0, depth, value, bound, mv: None }
+		}
+
+		#[test]
+		fn test_shallower_entry() {
+			assert_eq!(entry(2, 5, Bound::Exact).cutoff(3, 0, 10), None);
+		}
+
+		#[test]
+		fn test_exact() {
+			assert_eq!(entry(3, 5, Bound::Exact).cutoff(3, 0, 10), Some(5));
+		}
+
+		#[test]
+		fn test_lower() {
+			assert_eq!(entry(3, 5, Bound::Lower).cutoff(3, 0, 10), None);
+			assert_eq!(entry(3, 15, Bound::Lower).cutoff(3, 0, 10), Some(15));
+		}
+
+		#[test]
+		fn test_upper() {
+			assert_eq!(entry(3, 5, Bound::Upper).cutoff(3, 0, 10), None);
+			assert_eq!(entry(3, -5, Bound::Upper).cutoff(3, 0, 10), Some(-5));
+		}
+	}
+
+	mod table {
+		use super::*;
+
+		#[test]
+		fn test_empty_table() {
+			let mut table = TranspositionTable::<u8>::default();
+			table.store(1, 1, 1, Bound::Exact, Some(1));
+
+			assert!(table.probe(1).is_none());
+		}
+
+		#[test]
+		fn test_store_and_probe() {
+			let mut table = TranspositionTable::new(4);
+			table.store(1, 2, 3, Bound::Lower, Some(4u8));
+
+			let entry = table.probe(1).unwrap();
+			assert_eq!((entry.depth, entry.value, entry.bound, entry.mv), (2, 3, Bound::Lower, Some(4)));
+			assert!(table.probe(17).is_none());
+		}
+
+		#[test]
+		fn test_keeps_deeper_entry() {
+			let mut table = TranspositionTable::new(4);
+			table.store(1, 5, 3, Bound::Exact, Some(4u8));
+			table.store(1, 2, 7, Bound::Exact, Some(0u8));
+
+			assert_eq!(table.probe(1).unwrap().value, 3);
+		}
+	}
+
+	#[test]
+	fn test_zobrist_keys_are_unique() {
+		let keys = zobrist_keys::<128>(1);
+		for (i, a) in keys.iter().enumerate() {
+			assert!(keys[i + 1..].iter().all(|b| a != b));
+		}
+	}
+}
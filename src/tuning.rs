@@ -0,0 +1,231 @@
+//! Offline tuning of the [`ConnectFour`] evaluation weights.
+//!
+//! The tuner implements Texel's method: positions are collected from
+//! self-play games, and the weights are adjusted with a local search to
+//! minimise the mean squared error between the game result and the win
+//! probability predicted by a logistic function of the static evaluation.
+
+use std::fmt::Write;
+
+use crate::{
+	games::connect_four::{AiCells, ConnectFour, BOARD_CELLS, BOARD_WIDTH},
+	Player,
+};
+pub use crate::{
+	games::connect_four::{Weights, DEFAULT_WEIGHTS},
+	search::random::Random,
+};
+
+/// The amount of moves played at random at the start of every self-play game,
+/// so the games do not all follow the same line.
+const RANDOM_OPENING_PLIES: usize = 4;
+
+/// The chance, out of 100, of playing a random move after the opening.
+const RANDOM_MOVE_CHANCE: u64 = 10;
+
+/// A position seen during self-play, alongside the result of its game.
+#[derive(Debug, Clone)]
+pub struct Sample {
+	cells: AiCells,
+	/// The result of the game from the perspective of [`Player::Machine`]: `1`
+	/// for a win, `0.5` for a draw, and `0` for a loss.
+	result: f64,
+}
+
+/// Returns the best move for `player`, searching a fresh copy of the board so
+/// the transposition table does not carry over between moves.
+fn best_move(board: &ConnectFour, player: Player, depth: u8) -> usize {
+	let cells: AiCells = board.cells().try_into().unwrap();
+	ConnectFour::new(cells).get_best_move(depth, player)
+}
+
+fn random_move(board: &ConnectFour, random: &mut Random) -> usize {
+	let columns: Vec<usize> = (0..BOARD_WIDTH).filter(|&c| board.available(c)).collect();
+	random.pick(&columns)
+}
+
+/// Plays `games` self-play games searching `depth` plies deep, and returns
+/// every non-terminal position reached alongside the result of its game.
+pub fn generate_samples(games: usize, depth: u8, random: &mut Random) -> Vec<Sample> {
+	let mut samples = Vec::new();
+	for _ in 0..games {
+		let mut board = ConnectFour::new([Player::Unset; BOARD_CELLS]);
+		let mut positions = Vec::new();
+		let mut player = if random.below(2) == 0 { Player::Human } else { Player::Machine };
+		let mut result = 0.5;
+
+		for ply in 0.. {
+			let column = if ply < RANDOM_OPENING_PLIES || random.below(100) < RANDOM_MOVE_CHANCE {
+				random_move(&board, random)
+			} else {
+				best_move(&board, player, depth)
+			};
+
+			if board.play(column, player) {
+				result = if player == Player::Machine { 1.0 } else { 0.0 };
+				break;
+			}
+
+			if board.js_finished() {
+				break;
+			}
+
+			if ply >= RANDOM_OPENING_PLIES {
+				positions.push(board.cells().try_into().unwrap());
+			}
+			player = player.opponent();
+		}
+
+		samples.extend(positions.into_iter().map(|cells| Sample { cells, result }));
+	}
+
+	samples
+}
+
+fn sigmoid(score: f64, k: f64) -> f64 {
+	1.0 / (1.0 + (-k * score).exp())
+}
+
+/// Computes the mean squared error between the results of the samples and the
+/// predictions made from their evaluation with `weights`.
+pub fn error(samples: &[Sample], weights: Weights, k: f64) -> f64 {
+	let total: f64 = samples
+		.iter()
+		.map(|sample| {
+			let score = ConnectFour::with_weights(sample.cells, weights).evaluate(Player::Machine);
+			(sample.result - sigmoid(score as f64, k)).powi(2)
+		})
+		.sum();
+
+	total / samples.len() as f64
+}
+
+/// Finds the scaling constant that best maps the evaluation with `weights` to
+/// the results of the samples.
+pub fn scaling_factor(samples: &[Sample], weights: Weights) -> f64 {
+	let mut best = 0.01;
+	let mut best_error = error(samples, weights, best);
+
+	// Refine the constant one order of magnitude at a time:
+	let mut step = 0.01;
+	for _ in 0..4 {
+		let start = (best - step * 9.0).max(step / 10.0);
+		for i in 0..=18 {
+			let k = start + step * i as f64;
+			let e = error(samples, weights, k);
+			if e < best_error {
+				best = k;
+				best_error = e;
+			}
+		}
+
+		step /= 10.0;
+	}
+
+	best
+}
+
+fn get(weights: &Weights, index: usize) -> i32 {
+	match index {
+		0 => weights.center,
+		1 => weights.three,
+		2 => weights.two,
+		3 => weights.opponent_three,
+		4 => weights.opponent_two,
+		_ => unreachable!(),
+	}
+}
+
+fn set(weights: &mut Weights, index: usize, value: i32) {
+	match index {
+		0 => weights.center = value,
+		1 => weights.three = value,
+		2 => weights.two = value,
+		3 => weights.opponent_three = value,
+		4 => weights.opponent_two = value,
+		_ => unreachable!(),
+	}
+}
+
+/// Runs a local search over the weights starting from `initial`, and returns
+/// the weights with the lowest error found.
+///
+/// Every parameter is nudged up and down by a step, keeping the change if it
+/// lowers the error, until no parameter improves, at which point the step is
+/// halved until it reaches 1.
+pub fn tune(samples: &[Sample], initial: Weights, k: f64, mut step: i32) -> Weights {
+	let mut best = initial;
+	let mut best_error = error(samples, best, k);
+
+	while step > 0 {
+		let mut improved = true;
+		while improved {
+			improved = false;
+			for index in 0..5 {
+				for delta in [step, -step] {
+					let mut candidate = best;
+					set(&mut candidate, index, get(&best, index) + delta);
+
+					let e = error(samples, candidate, k);
+					if e < best_error {
+						best = candidate;
+						best_error = e;
+						improved = true;
+						break;
+					}
+				}
+			}
+		}
+
+		step /= 2;
+	}
+
+	best
+}
+
+/// Renders the weights as the source of `src/games/connect_four/weights.rs`.
+pub fn to_source(weights: Weights) -> String {
+	let mut out = String::new();
+	out.push_str("// This file is generated by the evaluation tuner, do not edit it manually.\n");
+	out.push_str("//\n");
+	out.push_str("// To regenerate it, run the `tune` binary with the `tune` feature enabled:\n");
+	out.push_str("// cargo run --release --features tune --bin tune -- --output <this file>\n\n");
+	out.push_str("use super::Weights;\n\n");
+	out.push_str("/// The weights compiled in as the default evaluation of [`super::ConnectFour`].\n");
+	writeln!(
+		out,
+		"pub const DEFAULT_WEIGHTS: Weights =\n\tWeights {{ center: {}, three: {}, two: {}, opponent_three: {}, opponent_two: {} }};",
+		weights.center, weights.three, weights.two, weights.opponent_three, weights.opponent_two
+	)
+	.unwrap();
+	out
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_generate_samples_is_reproducible() {
+		let a = generate_samples(4, 2, &mut Random::new(7));
+		let b = generate_samples(4, 2, &mut Random::new(7));
+
+		assert!(!a.is_empty());
+		assert_eq!(a.len(), b.len());
+		assert!(a.iter().zip(&b).all(|(a, b)| a.cells == b.cells && a.result == b.result));
+	}
+
+	#[test]
+	fn test_tune_does_not_increase_error() {
+		let samples = generate_samples(8, 2, &mut Random::new(42));
+		let k = scaling_factor(&samples, DEFAULT_WEIGHTS);
+		let tuned = tune(&samples, DEFAULT_WEIGHTS, k, 4);
+
+		assert!(error(&samples, tuned, k) <= error(&samples, DEFAULT_WEIGHTS, k));
+	}
+
+	#[test]
+	fn test_to_source_matches_default_weights() {
+		assert_eq!(to_source(DEFAULT_WEIGHTS), include_str!("games/connect_four/weights.rs"));
+	}
+}
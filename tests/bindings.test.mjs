@@ -1,9 +1,53 @@
-import { ConnectFour, TicTacToe } from '../index.mjs';
+import { Checkers, ConnectFour, DotsAndBoxes, Hex, Mancala, MnkGame, MultiplayerConnectFour, NineMensMorris, Notakto, Othello, Qubic, TicTacToe, UltimateTicTacToe } from '../index.mjs';
+
+test('Checkers is a class', () => {
+	expect(typeof Checkers).toBe('function');
+});
 
 test('ConnectFour is a class', () => {
 	expect(typeof ConnectFour).toBe('function');
 });
 
+test('DotsAndBoxes is a class', () => {
+	expect(typeof DotsAndBoxes).toBe('function');
+});
+
+test('Hex is a class', () => {
+	expect(typeof Hex).toBe('function');
+});
+
+test('Mancala is a class', () => {
+	expect(typeof Mancala).toBe('function');
+});
+
+test('MnkGame is a class', () => {
+	expect(typeof MnkGame).toBe('function');
+});
+
+test('MultiplayerConnectFour is a class', () => {
+	expect(typeof MultiplayerConnectFour).toBe('function');
+});
+
+test('NineMensMorris is a class', () => {
+	expect(typeof NineMensMorris).toBe('function');
+});
+
+test('Notakto is a class', () => {
+	expect(typeof Notakto).toBe('function');
+});
+
+test('Othello is a class', () => {
+	expect(typeof Othello).toBe('function');
+});
+
+test('Qubic is a class', () => {
+	expect(typeof Qubic).toBe('function');
+});
+
 test('TicTacToe is a class', () => {
 	expect(typeof TicTacToe).toBe('function');
 });
+
+test('UltimateTicTacToe is a class', () => {
+	expect(typeof UltimateTicTacToe).toBe('function');
+});